@@ -92,6 +92,8 @@ pub fn register_builtin_macros(resolver: &mut dyn ResolverExpand, edition: Editi
         global_allocator: global_allocator::expand,
         test: test::expand_test,
         test_case: test::expand_test_case,
+        test_setup: test::expand_test_setup,
+        test_teardown: test::expand_test_teardown,
     }
 
     register_derive! {
@@ -5,6 +5,7 @@
 use rustc_ast as ast;
 use rustc_ast::attr;
 use rustc_ast::ptr::P;
+use rustc_ast::token;
 use rustc_ast_pretty::pprust;
 use rustc_expand::base::*;
 use rustc_session::Session;
@@ -68,6 +69,144 @@ pub fn expand_bench(
     expand_test_or_bench(cx, attr_sp, item, true)
 }
 
+pub fn expand_test_setup(
+    cx: &mut ExtCtxt<'_>,
+    attr_sp: Span,
+    meta_item: &ast::MetaItem,
+    item: Annotatable,
+) -> Vec<Annotatable> {
+    check_builtin_macro_attribute(cx, meta_item, sym::test_setup);
+    expand_test_hook(cx, attr_sp, item, "Setup")
+}
+
+pub fn expand_test_teardown(
+    cx: &mut ExtCtxt<'_>,
+    attr_sp: Span,
+    meta_item: &ast::MetaItem,
+    item: Annotatable,
+) -> Vec<Annotatable> {
+    check_builtin_macro_attribute(cx, meta_item, sym::test_teardown);
+    expand_test_hook(cx, attr_sp, item, "Teardown")
+}
+
+// #[test_setup] and #[test_teardown] functions run before and after each test of their module.
+// Like tests, they become a public const marked with an inert attribute, here a `test::TestHook`
+// marked with "rustc_test_hook_marker", which the test generation logic passes to libtest.
+fn expand_test_hook(
+    cx: &mut ExtCtxt<'_>,
+    attr_sp: Span,
+    item: Annotatable,
+    kind: &str,
+) -> Vec<Annotatable> {
+    // If we're not in test configuration, remove the annotated item
+    if !cx.ecfg.should_test {
+        return vec![];
+    }
+
+    let item = match item {
+        Annotatable::Item(i) => i,
+        other => {
+            cx.struct_span_err(
+                other.span(),
+                "test hooks are only allowed on non associated functions outside of functions",
+            )
+            .emit();
+            return vec![other];
+        }
+    };
+
+    if !has_test_signature(cx, &item, false) {
+        return vec![Annotatable::Item(item)];
+    }
+
+    let (sp, attr_sp) = (cx.with_def_site_ctxt(item.span), cx.with_def_site_ctxt(attr_sp));
+
+    let test_id = Ident::new(sym::test, attr_sp);
+
+    // creates test::$name
+    let test_path = |name| cx.path(sp, vec![test_id, Ident::from_str_and_span(name, sp)]);
+
+    // creates $name: $expr
+    let field = |name, expr| cx.field_imm(sp, Ident::from_str_and_span(name, sp), expr);
+
+    let mod_path = &cx.current_expansion.module.mod_path[1..];
+    let module_path = mod_path.iter().map(|x| x.to_string()).collect::<Vec<String>>().join("::");
+
+    let mut hook_const = cx.item(
+        sp,
+        Ident::new(item.ident.name, sp),
+        vec![
+            // #[cfg(test)]
+            cx.attribute(attr::mk_list_item(
+                Ident::new(sym::cfg, attr_sp),
+                vec![attr::mk_nested_word_item(Ident::new(sym::test, attr_sp))],
+            )),
+            // #[rustc_test_hook_marker]
+            cx.attribute(cx.meta_word(attr_sp, sym::rustc_test_hook_marker)),
+        ],
+        // const $ident: test::TestHook =
+        ast::ItemKind::Const(
+            ast::Defaultness::Final,
+            cx.ty(sp, ast::TyKind::Path(None, test_path("TestHook"))),
+            // test::TestHook {
+            Some(cx.expr_struct(
+                sp,
+                test_path("TestHook"),
+                vec![
+                    // module_path: "path::to::module"
+                    field("module_path", cx.expr_str(sp, Symbol::intern(&module_path))),
+                    // kind: test::TestHookKind::Setup | test::TestHookKind::Teardown
+                    field(
+                        "kind",
+                        cx.expr_path(cx.path(
+                            sp,
+                            vec![
+                                test_id,
+                                Ident::from_str_and_span("TestHookKind", sp),
+                                Ident::from_str_and_span(kind, sp),
+                            ],
+                        )),
+                    ),
+                    // hook: || test::assert_test_result($hook_fn())
+                    field(
+                        "hook",
+                        cx.lambda0(
+                            sp,
+                            cx.expr_call(
+                                sp,
+                                cx.expr_path(test_path("assert_test_result")),
+                                vec![cx.expr_call(
+                                    sp,
+                                    cx.expr_path(cx.path(sp, vec![item.ident])),
+                                    vec![],
+                                )],
+                            ),
+                        ),
+                    ),
+                ],
+            )), // }
+        ),
+    );
+    hook_const = hook_const.map(|mut hc| {
+        hc.vis.kind = ast::VisibilityKind::Public;
+        hc
+    });
+
+    // extern crate test
+    let test_extern = cx.item(sp, test_id, vec![], ast::ItemKind::ExternCrate(None));
+
+    tracing::debug!("synthetic test hook:\n{}\n", pprust::item_to_string(&hook_const));
+
+    vec![
+        // Access to libtest under a hygienic name
+        Annotatable::Item(test_extern),
+        // The generated hook
+        Annotatable::Item(hook_const),
+        // The original item
+        Annotatable::Item(item),
+    ]
+}
+
 pub fn expand_test_or_bench(
     cx: &mut ExtCtxt<'_>,
     attr_sp: Span,
@@ -107,10 +246,17 @@ pub fn expand_test_or_bench(
         return vec![Annotatable::Item(item)];
     }
 
+    let params = if is_bench { None } else { test_params(cx, &item) };
+    if let Some(cases) = &params {
+        if cases.is_empty() {
+            return vec![Annotatable::Item(item)];
+        }
+    }
+
     // has_*_signature will report any errors in the type so compilation
     // will fail. We shouldn't try to expand in this case because the errors
     // would be spurious.
-    if (!is_bench && !has_test_signature(cx, &item))
+    if (!is_bench && !has_test_signature(cx, &item, params.is_some()))
         || (is_bench && !has_bench_signature(cx, &item))
     {
         return vec![Annotatable::Item(item)];
@@ -150,187 +296,206 @@ pub fn expand_test_or_bench(
     // creates $name: $expr
     let field = |name, expr| cx.field_imm(sp, Ident::from_str_and_span(name, sp), expr);
 
-    let test_fn = if is_bench {
-        // A simple ident for a lambda
-        let b = Ident::from_str_and_span("b", attr_sp);
+    let test_fn = |args| {
+        if is_bench {
+            // A simple ident for a lambda
+            let b = Ident::from_str_and_span("b", attr_sp);
+
+            cx.expr_call(
+                sp,
+                cx.expr_path(test_path("StaticBenchFn")),
+                vec![
+                    // |b| self::test::assert_test_result(
+                    cx.lambda1(
+                        sp,
+                        cx.expr_call(
+                            sp,
+                            cx.expr_path(test_path("assert_test_result")),
+                            vec![
+                                // super::$test_fn(b)
+                                cx.expr_call(
+                                    sp,
+                                    cx.expr_path(cx.path(sp, vec![item.ident])),
+                                    vec![cx.expr_ident(sp, b)],
+                                ),
+                            ],
+                        ),
+                        b,
+                    ), // )
+                ],
+            )
+        } else {
+            cx.expr_call(
+                sp,
+                cx.expr_path(test_path("StaticTestFn")),
+                vec![
+                    // || {
+                    cx.lambda0(
+                        sp,
+                        // test::assert_test_result(
+                        cx.expr_call(
+                            sp,
+                            cx.expr_path(test_path("assert_test_result")),
+                            vec![
+                                // $test_fn($args)
+                                cx.expr_call(sp, cx.expr_path(cx.path(sp, vec![item.ident])), args), // )
+                            ],
+                        ), // }
+                    ), // )
+                ],
+            )
+        }
+    };
 
-        cx.expr_call(
+    // A parametrized test becomes one test per case, named `path::to::test::case_N`.
+    let cases = match params {
+        Some(cases) => cases.into_iter().enumerate().map(|(i, args)| (Some(i + 1), args)).collect(),
+        None => vec![(None, Vec::new())],
+    };
+    let path = item_path(
+        // skip the name of the root module
+        &cx.current_expansion.module.mod_path[1..],
+        &item.ident,
+    );
+
+    let mut test_consts = Vec::new();
+    for (case, args) in cases {
+        let (name, ident) = match case {
+            Some(case) => (
+                format!("{}::case_{}", path, case),
+                Ident::from_str_and_span(&format!("{}_case_{}", item.ident, case), sp),
+            ),
+            None => (path.clone(), Ident::new(item.ident.name, sp)),
+        };
+        let mut test_const = cx.item(
             sp,
-            cx.expr_path(test_path("StaticBenchFn")),
+            ident,
             vec![
-                // |b| self::test::assert_test_result(
-                cx.lambda1(
-                    sp,
-                    cx.expr_call(
-                        sp,
-                        cx.expr_path(test_path("assert_test_result")),
-                        vec![
-                            // super::$test_fn(b)
-                            cx.expr_call(
-                                sp,
-                                cx.expr_path(cx.path(sp, vec![item.ident])),
-                                vec![cx.expr_ident(sp, b)],
-                            ),
-                        ],
-                    ),
-                    b,
-                ), // )
+                // #[cfg(test)]
+                cx.attribute(attr::mk_list_item(
+                    Ident::new(sym::cfg, attr_sp),
+                    vec![attr::mk_nested_word_item(Ident::new(sym::test, attr_sp))],
+                )),
+                // #[rustc_test_marker]
+                cx.attribute(cx.meta_word(attr_sp, sym::rustc_test_marker)),
             ],
-        )
-    } else {
-        cx.expr_call(
-            sp,
-            cx.expr_path(test_path("StaticTestFn")),
-            vec![
-                // || {
-                cx.lambda0(
-                    sp,
-                    // test::assert_test_result(
-                    cx.expr_call(
+            // const $ident: test::TestDescAndFn =
+            ast::ItemKind::Const(
+                ast::Defaultness::Final,
+                cx.ty(sp, ast::TyKind::Path(None, test_path("TestDescAndFn"))),
+                // test::TestDescAndFn {
+                Some(
+                    cx.expr_struct(
                         sp,
-                        cx.expr_path(test_path("assert_test_result")),
+                        test_path("TestDescAndFn"),
                         vec![
-                            // $test_fn()
-                            cx.expr_call(sp, cx.expr_path(cx.path(sp, vec![item.ident])), vec![]), // )
-                        ],
-                    ), // }
-                ), // )
-            ],
-        )
-    };
-
-    let mut test_const = cx.item(
-        sp,
-        Ident::new(item.ident.name, sp),
-        vec![
-            // #[cfg(test)]
-            cx.attribute(attr::mk_list_item(
-                Ident::new(sym::cfg, attr_sp),
-                vec![attr::mk_nested_word_item(Ident::new(sym::test, attr_sp))],
-            )),
-            // #[rustc_test_marker]
-            cx.attribute(cx.meta_word(attr_sp, sym::rustc_test_marker)),
-        ],
-        // const $ident: test::TestDescAndFn =
-        ast::ItemKind::Const(
-            ast::Defaultness::Final,
-            cx.ty(sp, ast::TyKind::Path(None, test_path("TestDescAndFn"))),
-            // test::TestDescAndFn {
-            Some(
-                cx.expr_struct(
-                    sp,
-                    test_path("TestDescAndFn"),
-                    vec![
-                        // desc: test::TestDesc {
-                        field(
-                            "desc",
-                            cx.expr_struct(
-                                sp,
-                                test_path("TestDesc"),
-                                vec![
-                                    // name: "path::to::test"
-                                    field(
-                                        "name",
-                                        cx.expr_call(
-                                            sp,
-                                            cx.expr_path(test_path("StaticTestName")),
-                                            vec![cx.expr_str(
-                                                sp,
-                                                Symbol::intern(&item_path(
-                                                    // skip the name of the root module
-                                                    &cx.current_expansion.module.mod_path[1..],
-                                                    &item.ident,
-                                                )),
-                                            )],
-                                        ),
-                                    ),
-                                    // ignore: true | false
-                                    field(
-                                        "ignore",
-                                        cx.expr_bool(sp, should_ignore(&cx.sess, &item)),
-                                    ),
-                                    // allow_fail: true | false
-                                    field(
-                                        "allow_fail",
-                                        cx.expr_bool(sp, should_fail(&cx.sess, &item)),
-                                    ),
-                                    // should_panic: ...
-                                    field(
-                                        "should_panic",
-                                        match should_panic(cx, &item) {
-                                            // test::ShouldPanic::No
-                                            ShouldPanic::No => {
-                                                cx.expr_path(should_panic_path("No"))
-                                            }
-                                            // test::ShouldPanic::Yes
-                                            ShouldPanic::Yes(None) => {
-                                                cx.expr_path(should_panic_path("Yes"))
-                                            }
-                                            // test::ShouldPanic::YesWithMessage("...")
-                                            ShouldPanic::Yes(Some(sym)) => cx.expr_call(
+                            // desc: test::TestDesc {
+                            field(
+                                "desc",
+                                cx.expr_struct(
+                                    sp,
+                                    test_path("TestDesc"),
+                                    vec![
+                                        // name: "path::to::test"
+                                        field(
+                                            "name",
+                                            cx.expr_call(
                                                 sp,
-                                                cx.expr_path(should_panic_path("YesWithMessage")),
-                                                vec![cx.expr_str(sp, sym)],
+                                                cx.expr_path(test_path("StaticTestName")),
+                                                vec![cx.expr_str(sp, Symbol::intern(&name))],
                                             ),
-                                        },
-                                    ),
-                                    // test_type: ...
-                                    field(
-                                        "test_type",
-                                        match test_type(cx) {
-                                            // test::TestType::UnitTest
-                                            TestType::UnitTest => {
-                                                cx.expr_path(test_type_path("UnitTest"))
-                                            }
-                                            // test::TestType::IntegrationTest
-                                            TestType::IntegrationTest => {
-                                                cx.expr_path(test_type_path("IntegrationTest"))
-                                            }
-                                            // test::TestPath::Unknown
-                                            TestType::Unknown => {
-                                                cx.expr_path(test_type_path("Unknown"))
-                                            }
-                                        },
-                                    ),
-                                    // },
-                                ],
+                                        ),
+                                        // ignore: true | false
+                                        field(
+                                            "ignore",
+                                            cx.expr_bool(sp, should_ignore(&cx.sess, &item)),
+                                        ),
+                                        // allow_fail: true | false
+                                        field(
+                                            "allow_fail",
+                                            cx.expr_bool(sp, should_fail(&cx.sess, &item)),
+                                        ),
+                                        // should_panic: ...
+                                        field(
+                                            "should_panic",
+                                            match should_panic(cx, &item) {
+                                                // test::ShouldPanic::No
+                                                ShouldPanic::No => {
+                                                    cx.expr_path(should_panic_path("No"))
+                                                }
+                                                // test::ShouldPanic::Yes
+                                                ShouldPanic::Yes(None) => {
+                                                    cx.expr_path(should_panic_path("Yes"))
+                                                }
+                                                // test::ShouldPanic::YesWithMessage("...")
+                                                ShouldPanic::Yes(Some(sym)) => cx.expr_call(
+                                                    sp,
+                                                    cx.expr_path(should_panic_path(
+                                                        "YesWithMessage",
+                                                    )),
+                                                    vec![cx.expr_str(sp, sym)],
+                                                ),
+                                            },
+                                        ),
+                                        // test_type: ...
+                                        field(
+                                            "test_type",
+                                            match test_type(cx) {
+                                                // test::TestType::UnitTest
+                                                TestType::UnitTest => {
+                                                    cx.expr_path(test_type_path("UnitTest"))
+                                                }
+                                                // test::TestType::IntegrationTest
+                                                TestType::IntegrationTest => {
+                                                    cx.expr_path(test_type_path("IntegrationTest"))
+                                                }
+                                                // test::TestPath::Unknown
+                                                TestType::Unknown => {
+                                                    cx.expr_path(test_type_path("Unknown"))
+                                                }
+                                            },
+                                        ),
+                                        // },
+                                    ],
+                                ),
                             ),
-                        ),
-                        // testfn: test::StaticTestFn(...) | test::StaticBenchFn(...)
-                        field("testfn", test_fn), // }
-                    ],
-                ), // }
+                            // testfn: test::StaticTestFn(...) | test::StaticBenchFn(...)
+                            field("testfn", test_fn(args)), // }
+                        ],
+                    ), // }
+                ),
             ),
-        ),
-    );
-    test_const = test_const.map(|mut tc| {
-        tc.vis.kind = ast::VisibilityKind::Public;
-        tc
-    });
+        );
+        test_const = test_const.map(|mut tc| {
+            tc.vis.kind = ast::VisibilityKind::Public;
+            tc
+        });
+
+        tracing::debug!("synthetic test item:\n{}\n", pprust::item_to_string(&test_const));
+        test_consts.push(test_const);
+    }
 
     // extern crate test
     let test_extern = cx.item(sp, test_id, vec![], ast::ItemKind::ExternCrate(None));
 
-    tracing::debug!("synthetic test item:\n{}\n", pprust::item_to_string(&test_const));
-
     if is_stmt {
-        vec![
-            // Access to libtest under a hygienic name
-            Annotatable::Stmt(P(cx.stmt_item(sp, test_extern))),
-            // The generated test case
-            Annotatable::Stmt(P(cx.stmt_item(sp, test_const))),
+        // Access to libtest under a hygienic name
+        iter::once(test_extern)
+            // The generated test cases
+            .chain(test_consts)
             // The original item
-            Annotatable::Stmt(P(cx.stmt_item(sp, item))),
-        ]
+            .chain(iter::once(item))
+            .map(|item| Annotatable::Stmt(P(cx.stmt_item(sp, item))))
+            .collect()
     } else {
-        vec![
-            // Access to libtest under a hygienic name
-            Annotatable::Item(test_extern),
-            // The generated test case
-            Annotatable::Item(test_const),
+        // Access to libtest under a hygienic name
+        iter::once(test_extern)
+            // The generated test cases
+            .chain(test_consts)
             // The original item
-            Annotatable::Item(item),
-        ]
+            .chain(iter::once(item))
+            .map(Annotatable::Item)
+            .collect()
     }
 }
 
@@ -394,6 +559,49 @@ fn should_panic(cx: &ExtCtxt<'_>, i: &ast::Item) -> ShouldPanic {
     }
 }
 
+/// Parses the cases of `#[test_params(case1, case2, ...)]` into the arguments of the test for
+/// each of them: a case is a tuple of arguments, or a single argument. Returns `None` if the test
+/// isn't parametrized, and reports an error and returns no cases if the attribute is malformed.
+fn test_params(cx: &ExtCtxt<'_>, i: &ast::Item) -> Option<Vec<Vec<P<ast::Expr>>>> {
+    let attr = cx.sess.find_by_name(&i.attrs, sym::test_params)?;
+    let tokens = match &attr.get_normal_item().args {
+        ast::MacArgs::Delimited(_, ast::MacDelimiter::Parenthesis, tokens) => tokens.clone(),
+        _ => {
+            cx.span_err(
+                attr.span,
+                "attribute must be of the form `#[test_params(case1, case2, ...)]`",
+            );
+            return Some(Vec::new());
+        }
+    };
+
+    let mut p = cx.new_parser_from_tts(tokens);
+    let mut cases = Vec::new();
+    while p.token != token::Eof {
+        let case = match p.parse_expr() {
+            Ok(case) => case,
+            Err(mut err) => {
+                err.emit();
+                return Some(Vec::new());
+            }
+        };
+        cases.push(match case.kind {
+            ast::ExprKind::Tup(ref args) => args.clone(),
+            _ => vec![case],
+        });
+        if p.token != token::Eof {
+            if let Err(mut err) = p.expect(&token::Comma) {
+                err.emit();
+                return Some(Vec::new());
+            }
+        }
+    }
+    if cases.is_empty() {
+        cx.span_err(attr.span, "`#[test_params]` requires at least one case");
+    }
+    Some(cases)
+}
+
 enum TestType {
     UnitTest,
     IntegrationTest,
@@ -422,7 +630,7 @@ fn test_type(cx: &ExtCtxt<'_>) -> TestType {
     }
 }
 
-fn has_test_signature(cx: &ExtCtxt<'_>, i: &ast::Item) -> bool {
+fn has_test_signature(cx: &ExtCtxt<'_>, i: &ast::Item, has_params: bool) -> bool {
     let has_should_panic_attr = cx.sess.contains_name(&i.attrs, sym::should_panic);
     let sd = &cx.sess.parse_sess.span_diagnostic;
     if let ast::ItemKind::Fn(_, ref sig, ref generics, _) = i.kind {
@@ -447,7 +655,7 @@ fn has_test_signature(cx: &ExtCtxt<'_>, i: &ast::Item) -> bool {
             _ => true,
         };
 
-        if !sig.decl.inputs.is_empty() {
+        if !sig.decl.inputs.is_empty() && !has_params {
             sd.span_err(i.span, "functions used as tests can not have any arguments");
             return false;
         }
@@ -21,6 +21,8 @@
 struct Test {
     span: Span,
     ident: Ident,
+    /// Whether this is a `#[test_setup]` or `#[test_teardown]` hook instead of a test.
+    is_hook: bool,
 }
 
 struct TestCtxt<'a> {
@@ -97,7 +99,12 @@ fn visit_crate(&mut self, c: &mut ast::Crate) {
         if is_test_case(&self.cx.ext_cx.sess, &item) {
             debug!("this is a test item");
 
-            let test = Test { span: item.span, ident: item.ident };
+            let test = Test { span: item.span, ident: item.ident, is_hook: false };
+            self.tests.push(test);
+        } else if is_test_hook(&self.cx.ext_cx.sess, &item) {
+            debug!("this is a test hook");
+
+            let test = Test { span: item.span, ident: item.ident, is_hook: true };
             self.tests.push(test);
         }
 
@@ -263,6 +270,9 @@ fn generate_test_harness(
 /// we remove the outer mark, and try resolving at its def-site, which will
 /// then resolve to `test_const`.
 ///
+/// If there are `#[test_setup]` or `#[test_teardown]` hooks, they are passed in a second slice
+/// to `test::test_main_static_with_hooks` instead.
+///
 /// The expansion here can be controlled by two attributes:
 ///
 /// [`TestCtxt::reexport_test_harness_main`] provides a different name for the `main`
@@ -273,9 +283,18 @@ fn mk_main(cx: &mut TestCtxt<'_>) -> P<ast::Item> {
     let ecx = &cx.ext_cx;
     let test_id = Ident::new(sym::test, sp);
 
-    let runner_name = match cx.panic_strategy {
-        PanicStrategy::Unwind => "test_main_static",
-        PanicStrategy::Abort => "test_main_static_abort",
+    let (hooks, tests): (Vec<_>, Vec<_>) = cx.test_cases.iter().partition(|test| test.is_hook);
+    if cx.test_runner.is_some() {
+        for hook in &hooks {
+            ecx.span_err(hook.span, "test hooks are not supported with a custom test runner");
+        }
+    }
+
+    let runner_name = match (cx.panic_strategy, hooks.is_empty()) {
+        (PanicStrategy::Unwind, true) => "test_main_static",
+        (PanicStrategy::Unwind, false) => "test_main_static_with_hooks",
+        (PanicStrategy::Abort, true) => "test_main_static_abort",
+        (PanicStrategy::Abort, false) => "test_main_static_abort_with_hooks",
     };
 
     // test::test_main_static(...)
@@ -287,7 +306,11 @@ fn mk_main(cx: &mut TestCtxt<'_>) -> P<ast::Item> {
     test_runner.span = sp;
 
     let test_main_path_expr = ecx.expr_path(test_runner);
-    let call_test_main = ecx.expr_call(sp, test_main_path_expr, vec![mk_tests_slice(cx, sp)]);
+    let mut args = vec![mk_tests_slice(cx, &tests, sp)];
+    if !hooks.is_empty() && cx.test_runner.is_none() {
+        args.push(mk_tests_slice(cx, &hooks, sp));
+    }
+    let call_test_main = ecx.expr_call(sp, test_main_path_expr, args);
     let call_test_main = ecx.stmt_expr(call_test_main);
 
     // extern crate test
@@ -334,15 +357,15 @@ fn mk_main(cx: &mut TestCtxt<'_>) -> P<ast::Item> {
     cx.ext_cx.monotonic_expander().fully_expand_fragment(main).make_items().pop().unwrap()
 }
 
-/// Creates a slice containing every test like so:
+/// Creates a slice containing every test (or hook) like so:
 /// &[&test1, &test2]
-fn mk_tests_slice(cx: &TestCtxt<'_>, sp: Span) -> P<ast::Expr> {
-    debug!("building test vector from {} tests", cx.test_cases.len());
+fn mk_tests_slice(cx: &TestCtxt<'_>, tests: &[&Test], sp: Span) -> P<ast::Expr> {
+    debug!("building test vector from {} tests", tests.len());
     let ecx = &cx.ext_cx;
 
     ecx.expr_vec_slice(
         sp,
-        cx.test_cases
+        tests
             .iter()
             .map(|test| {
                 ecx.expr_addr_of(test.span, ecx.expr_path(ecx.path(test.span, vec![test.ident])))
@@ -355,6 +378,10 @@ fn is_test_case(sess: &Session, i: &ast::Item) -> bool {
     sess.contains_name(&i.attrs, sym::rustc_test_marker)
 }
 
+fn is_test_hook(sess: &Session, i: &ast::Item) -> bool {
+    sess.contains_name(&i.attrs, sym::rustc_test_hook_marker)
+}
+
 fn get_test_runner(
     sess: &Session,
     sd: &rustc_errors::Handler,
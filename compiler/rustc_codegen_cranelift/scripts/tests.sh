@@ -28,6 +28,15 @@ function no_sysroot_tests() {
     $RUN_WRAPPER ./target/out/mini_core_hello_world abc bcd
     # (echo "break set -n main"; echo "run"; sleep 1; echo "si -c 10"; sleep 1; echo "frame variable") | lldb -- ./target/out/mini_core_hello_world abc bcd
 
+    if [[ "$unamestr" == 'Linux' && "$TARGET_TRIPLE" == x86_64-* ]]; then
+        echo "[DEBUGINFO] mini_core_hello_world"
+        readelf --debug-dump=info ./target/out/mini_core_hello_world > target/out/mini_core_hello_world.dwarf
+        grep -q DW_TAG_variable target/out/mini_core_hello_world.dwarf
+        grep -q DW_TAG_member target/out/mini_core_hello_world.dwarf
+    else
+        echo "[DEBUGINFO] mini_core_hello_world (skipped)"
+    fi
+
     echo "[AOT] arbitrary_self_types_pointers_and_wrappers"
     $MY_RUSTC example/arbitrary_self_types_pointers_and_wrappers.rs --crate-name arbitrary_self_types_pointers_and_wrappers --crate-type bin --target "$TARGET_TRIPLE"
     $RUN_WRAPPER ./target/out/arbitrary_self_types_pointers_and_wrappers
@@ -2,8 +2,10 @@
 
 mod emit;
 mod line_info;
+mod types;
 mod unwind;
 
+use crate::pointer::PointerBase;
 use crate::prelude::*;
 
 use rustc_index::vec::IndexVec;
@@ -39,7 +41,6 @@ pub(crate) struct DebugContext<'tcx> {
     dwarf: DwarfUnit,
     unit_range_list: RangeList,
 
-    clif_types: FxHashMap<Type, UnitEntryId>,
     types: FxHashMap<Ty<'tcx>, UnitEntryId>,
 }
 
@@ -115,148 +116,20 @@ pub(crate) fn new(tcx: TyCtxt<'tcx>, isa: &dyn TargetIsa) -> Self {
             dwarf,
             unit_range_list: RangeList(Vec::new()),
 
-            clif_types: FxHashMap::default(),
             types: FxHashMap::default(),
         }
     }
 
-    fn dwarf_ty_for_clif_ty(&mut self, ty: Type) -> UnitEntryId {
-        if let Some(type_id) = self.clif_types.get(&ty) {
-            return *type_id;
-        }
-
-        let new_entry = |dwarf: &mut DwarfUnit, tag| dwarf.unit.add(dwarf.unit.root(), tag);
-
-        let primitive = |dwarf: &mut DwarfUnit, ate| {
-            let type_id = new_entry(dwarf, gimli::DW_TAG_base_type);
-            let type_entry = dwarf.unit.get_mut(type_id);
-            type_entry.set(gimli::DW_AT_encoding, AttributeValue::Encoding(ate));
-            type_id
-        };
-
-        let type_id = if ty.is_bool() {
-            primitive(&mut self.dwarf, gimli::DW_ATE_boolean)
-        } else if ty.is_int() {
-            primitive(&mut self.dwarf, gimli::DW_ATE_address)
-        } else if ty.is_float() {
-            primitive(&mut self.dwarf, gimli::DW_ATE_float)
-        } else {
-            new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type)
-        };
-
-        let type_entry = self.dwarf.unit.get_mut(type_id);
-        type_entry.set(
-            gimli::DW_AT_name,
-            AttributeValue::String(format!("{}", ty).replace('i', "u").into_bytes()),
-        );
-        type_entry.set(
-            gimli::DW_AT_byte_size,
-            AttributeValue::Udata(u64::from(ty.bytes())),
-        );
-
-        type_id
-    }
-
-    fn dwarf_ty(&mut self, ty: Ty<'tcx>) -> UnitEntryId {
-        if let Some(type_id) = self.types.get(ty) {
-            return *type_id;
-        }
-
-        let new_entry = |dwarf: &mut DwarfUnit, tag| dwarf.unit.add(dwarf.unit.root(), tag);
-
-        let primitive = |dwarf: &mut DwarfUnit, ate| {
-            let type_id = new_entry(dwarf, gimli::DW_TAG_base_type);
-            let type_entry = dwarf.unit.get_mut(type_id);
-            type_entry.set(gimli::DW_AT_encoding, AttributeValue::Encoding(ate));
-            type_id
-        };
-
-        let name = format!("{}", ty);
-        let layout = self.tcx.layout_of(ParamEnv::reveal_all().and(ty)).unwrap();
-
-        let type_id = match ty.kind() {
-            ty::Bool => primitive(&mut self.dwarf, gimli::DW_ATE_boolean),
-            ty::Char => primitive(&mut self.dwarf, gimli::DW_ATE_UTF),
-            ty::Uint(_) => primitive(&mut self.dwarf, gimli::DW_ATE_unsigned),
-            ty::Int(_) => primitive(&mut self.dwarf, gimli::DW_ATE_signed),
-            ty::Float(_) => primitive(&mut self.dwarf, gimli::DW_ATE_float),
-            ty::Ref(_, pointee_ty, _mutbl)
-            | ty::RawPtr(ty::TypeAndMut {
-                ty: pointee_ty,
-                mutbl: _mutbl,
-            }) => {
-                let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_pointer_type);
-
-                // Ensure that type is inserted before recursing to avoid duplicates
-                self.types.insert(ty, type_id);
-
-                let pointee = self.dwarf_ty(pointee_ty);
-
-                let type_entry = self.dwarf.unit.get_mut(type_id);
-
-                //type_entry.set(gimli::DW_AT_mutable, AttributeValue::Flag(mutbl == rustc_hir::Mutability::Mut));
-                type_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(pointee));
-
-                type_id
-            }
-            ty::Adt(adt_def, _substs) if adt_def.is_struct() && !layout.is_unsized() => {
-                let type_id = new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type);
-
-                // Ensure that type is inserted before recursing to avoid duplicates
-                self.types.insert(ty, type_id);
-
-                let variant = adt_def.non_enum_variant();
-
-                for (field_idx, field_def) in variant.fields.iter().enumerate() {
-                    let field_offset = layout.fields.offset(field_idx);
-                    let field_layout = layout
-                        .field(
-                            &layout::LayoutCx {
-                                tcx: self.tcx,
-                                param_env: ParamEnv::reveal_all(),
-                            },
-                            field_idx,
-                        )
-                        .unwrap();
-
-                    let field_type = self.dwarf_ty(field_layout.ty);
-
-                    let field_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_member);
-                    let field_entry = self.dwarf.unit.get_mut(field_id);
-
-                    field_entry.set(
-                        gimli::DW_AT_name,
-                        AttributeValue::String(field_def.ident.as_str().to_string().into_bytes()),
-                    );
-                    field_entry.set(
-                        gimli::DW_AT_data_member_location,
-                        AttributeValue::Udata(field_offset.bytes()),
-                    );
-                    field_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(field_type));
-                }
-
-                type_id
-            }
-            _ => new_entry(&mut self.dwarf, gimli::DW_TAG_structure_type),
-        };
-
-        let type_entry = self.dwarf.unit.get_mut(type_id);
-
-        type_entry.set(gimli::DW_AT_name, AttributeValue::String(name.into_bytes()));
-        type_entry.set(
-            gimli::DW_AT_byte_size,
-            AttributeValue::Udata(layout.size.bytes()),
-        );
-
-        self.types.insert(ty, type_id);
-
-        type_id
-    }
-
-    fn define_local(&mut self, scope: UnitEntryId, name: String, ty: Ty<'tcx>) -> UnitEntryId {
+    fn define_local(
+        &mut self,
+        scope: UnitEntryId,
+        tag: gimli::DwTag,
+        name: String,
+        ty: Ty<'tcx>,
+    ) -> UnitEntryId {
         let dw_ty = self.dwarf_ty(ty);
 
-        let var_id = self.dwarf.unit.add(scope, gimli::DW_TAG_variable);
+        let var_id = self.dwarf.unit.add(scope, tag);
         let var_entry = self.dwarf.unit.get_mut(var_id);
 
         var_entry.set(gimli::DW_AT_name, AttributeValue::String(name.into_bytes()));
@@ -312,82 +185,57 @@ pub(crate) fn define_function(
         // Using Udata for DW_AT_high_pc requires at least DWARF4
         func_entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(u64::from(end)));
 
-        // FIXME Remove once actual debuginfo for locals works.
-        for (i, (param, &val)) in context
-            .func
-            .signature
-            .params
-            .iter()
-            .zip(
-                context
-                    .func
-                    .dfg
-                    .block_params(context.func.layout.entry_block().unwrap()),
-            )
-            .enumerate()
-        {
-            use cranelift_codegen::ir::ArgumentPurpose;
-            let base_name = match param.purpose {
-                ArgumentPurpose::Normal => "arg",
-                ArgumentPurpose::StructArgument(_) => "struct_arg",
-                ArgumentPurpose::StructReturn => "sret",
-                ArgumentPurpose::Link
-                | ArgumentPurpose::FramePointer
-                | ArgumentPurpose::CalleeSaved => continue,
-                ArgumentPurpose::VMContext
-                | ArgumentPurpose::SignatureId
-                | ArgumentPurpose::CallerTLS
-                | ArgumentPurpose::CalleeTLS
-                | ArgumentPurpose::StackLimit => unreachable!(),
-            };
-            let name = format!("{}{}", base_name, i);
-
-            let dw_ty = self.dwarf_ty_for_clif_ty(param.value_type);
-            let loc =
-                translate_loc(isa, context.func.locations[val], &context.func.stack_slots).unwrap();
-
-            let arg_id = self
-                .dwarf
-                .unit
-                .add(entry_id, gimli::DW_TAG_formal_parameter);
-            let var_entry = self.dwarf.unit.get_mut(arg_id);
+        let value_labels_ranges = match context.build_value_labels_ranges(isa) {
+            Ok(value_labels_ranges) => value_labels_ranges,
+            Err(err) => {
+                // The variables are still described, debuggers show them as optimized out.
+                self.tcx.sess.warn(&format!(
+                    "failed to compute the locations of the variables of `{}`: {}",
+                    name, err
+                ));
+                Default::default()
+            }
+        };
 
-            var_entry.set(gimli::DW_AT_name, AttributeValue::String(name.into_bytes()));
-            var_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(dw_ty));
-            var_entry.set(gimli::DW_AT_location, AttributeValue::Exprloc(loc));
-        }
+        // FIXME implement scopes. All variables are currently visible in the whole function.
+        for var_debug_info in &mir.var_debug_info {
+            let place = match var_debug_info.value {
+                VarDebugInfoContents::Place(place) => place,
+                // FIXME emit DW_AT_const_value for these
+                VarDebugInfoContents::Const(_) => continue,
+            };
 
-        // FIXME make it more reliable and implement scopes before re-enabling this.
-        if false {
-            let value_labels_ranges = context.build_value_labels_ranges(isa).unwrap();
-
-            for (local, _local_decl) in mir.local_decls.iter_enumerated() {
-                let ty = self.tcx.subst_and_normalize_erasing_regions(
-                    instance.substs,
-                    ty::ParamEnv::reveal_all(),
-                    mir.local_decls[local].ty,
-                );
-                let var_id = self.define_local(entry_id, format!("{:?}", local), ty);
-
-                let location = place_location(
-                    self,
-                    isa,
-                    symbol,
-                    context,
-                    &local_map,
-                    &value_labels_ranges,
-                    Place {
-                        local,
-                        projection: ty::List::empty(),
-                    },
-                );
+            let ty = self.tcx.subst_and_normalize_erasing_regions(
+                instance.substs,
+                ty::ParamEnv::reveal_all(),
+                place.ty(mir, self.tcx).ty,
+            );
+            let is_arg = place.projection.is_empty()
+                && (1..=mir.arg_count).contains(&place.local.as_usize());
+            let tag = if is_arg {
+                gimli::DW_TAG_formal_parameter
+            } else {
+                gimli::DW_TAG_variable
+            };
+            let var_id = self.define_local(entry_id, tag, var_debug_info.name.to_string(), ty);
+            self.emit_location(var_id, var_debug_info.source_info.span);
+
+            let location = place_location(
+                self,
+                isa,
+                symbol,
+                context,
+                &local_map,
+                &value_labels_ranges,
+                place,
+            );
 
+            // Variables without a location are shown as optimized out by debuggers.
+            if let Some(location) = location {
                 let var_entry = self.dwarf.unit.get_mut(var_id);
                 var_entry.set(gimli::DW_AT_location, location);
             }
         }
-
-        // FIXME create locals for all entries in mir.var_debug_info
     }
 }
 
@@ -402,17 +250,18 @@ fn place_location<'tcx>(
         Vec<ValueLocRange>,
     >,
     place: Place<'tcx>,
-) -> AttributeValue {
-    assert!(place.projection.is_empty()); // FIXME implement them
+) -> Option<AttributeValue> {
+    let cplace = local_map[place.local];
 
-    match local_map[place.local].inner() {
-        CPlaceInner::Var(_local, var) => {
+    match cplace.inner() {
+        CPlaceInner::Var(_local, var) if place.projection.is_empty() => {
             let value_label = cranelift_codegen::ir::ValueLabel::new(var.index());
-            if let Some(value_loc_ranges) = value_labels_ranges.get(&value_label) {
-                let loc_list = LocationList(
-                    value_loc_ranges
-                        .iter()
-                        .map(|value_loc_range| Location::StartEnd {
+            let value_loc_ranges = value_labels_ranges.get(&value_label)?;
+            let loc_list = LocationList(
+                value_loc_ranges
+                    .iter()
+                    .filter_map(|value_loc_range| {
+                        Some(Location::StartEnd {
                             begin: Address::Symbol {
                                 symbol,
                                 addend: i64::from(value_loc_range.start),
@@ -425,63 +274,183 @@ fn place_location<'tcx>(
                                 isa,
                                 value_loc_range.loc,
                                 &context.func.stack_slots,
-                            )
-                            .unwrap(),
+                            )?,
                         })
-                        .collect(),
-                );
-                let loc_list_id = debug_context.dwarf.unit.locations.add(loc_list);
-
-                AttributeValue::LocationListRef(loc_list_id)
-            } else {
-                // FIXME set value labels for unused locals
+                    })
+                    .collect(),
+            );
+            let loc_list_id = debug_context.dwarf.unit.locations.add(loc_list);
 
-                AttributeValue::Exprloc(Expression::new())
-            }
+            Some(AttributeValue::LocationListRef(loc_list_id))
         }
-        CPlaceInner::VarPair(_, _, _) => {
-            // FIXME implement this
+        CPlaceInner::VarPair(_local, var1, var2) if place.projection.is_empty() => {
+            let layout = cplace.layout();
+            let (a_scalar, b_scalar) = match &layout.abi {
+                Abi::ScalarPair(a, b) => (a, b),
+                _ => unreachable!(),
+            };
+            let a_size = a_scalar.value.size(&debug_context.tcx);
+            let b_offset = crate::value_and_place::scalar_pair_calculate_b_offset(
+                debug_context.tcx,
+                a_scalar,
+                b_scalar,
+            );
+            let b_offset = Size::from_bytes(i64::from(b_offset) as u64);
+            let b_size = b_scalar.value.size(&debug_context.tcx);
+
+            let ranges1 = value_labels_ranges.get(&ValueLabel::new(var1.index()))?;
+            let ranges2 = value_labels_ranges.get(&ValueLabel::new(var2.index()))?;
+
+            // Both halves are only known where both of their ranges overlap.
+            let mut locations = Vec::new();
+            for range1 in ranges1 {
+                for range2 in ranges2 {
+                    let start = range1.start.max(range2.start);
+                    let end = range1.end.min(range2.end);
+                    if start >= end {
+                        continue;
+                    }
+
+                    let stack_slots = &context.func.stack_slots;
+                    let mut expr = Expression::new();
+                    if push_loc(&mut expr, isa, range1.loc, stack_slots).is_none() {
+                        continue;
+                    }
+                    expr.op_piece(a_size.bytes());
+                    if b_offset > a_size {
+                        // The padding between both halves has no location.
+                        expr.op_piece((b_offset - a_size).bytes());
+                    }
+                    if push_loc(&mut expr, isa, range2.loc, stack_slots).is_none() {
+                        continue;
+                    }
+                    expr.op_piece(b_size.bytes());
+
+                    locations.push(Location::StartEnd {
+                        begin: Address::Symbol {
+                            symbol,
+                            addend: i64::from(start),
+                        },
+                        end: Address::Symbol {
+                            symbol,
+                            addend: i64::from(end),
+                        },
+                        data: expr,
+                    });
+                }
+            }
+            let loc_list_id = debug_context
+                .dwarf
+                .unit
+                .locations
+                .add(LocationList(locations));
 
-            AttributeValue::Exprloc(Expression::new())
+            Some(AttributeValue::LocationListRef(loc_list_id))
         }
-        CPlaceInner::VarLane(_, _, _) => {
-            // FIXME implement this
+        CPlaceInner::Addr(ptr, None) => {
+            let offset = projection_offset(debug_context.tcx, cplace.layout(), place)?;
+            let (base, ptr_offset) = ptr.base_and_offset();
+            let offset = i64::from(ptr_offset) + i64::try_from(offset.bytes()).unwrap();
+
+            match base {
+                PointerBase::Stack(stack_slot) => {
+                    let mut expr = Expression::new();
+                    push_stack_slot_address(
+                        &mut expr,
+                        isa,
+                        stack_slot,
+                        offset,
+                        &context.func.stack_slots,
+                    )?;
+                    Some(AttributeValue::Exprloc(expr))
+                }
+                // FIXME use the value labels of the pointer once they are set. The location of the
+                // pointer at the start of the function isn't valid in the rest of it.
+                PointerBase::Addr(_) => None,
+                // Zero-sized places don't have an address.
+                PointerBase::Dangling(_) => None,
+            }
+        }
+        // FIXME implement lanes, unsized places and projections of locals stored in registers
+        CPlaceInner::Var(..)
+        | CPlaceInner::VarPair(..)
+        | CPlaceInner::VarLane(..)
+        | CPlaceInner::Addr(_, Some(_)) => None,
+    }
+}
 
-            AttributeValue::Exprloc(Expression::new())
+/// Computes the offset of `place` in its local, if it can be done statically.
+fn projection_offset<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    mut layout: TyAndLayout<'tcx>,
+    place: Place<'tcx>,
+) -> Option<Size> {
+    let cx = layout::LayoutCx {
+        tcx,
+        param_env: ParamEnv::reveal_all(),
+    };
+
+    let mut offset = Size::ZERO;
+    for &elem in place.projection {
+        match elem {
+            PlaceElem::Field(field, _ty) => {
+                offset += layout.fields.offset(field.index());
+                layout = layout.field(&cx, field.index()).unwrap();
+            }
+            PlaceElem::Downcast(_name, variant) => {
+                layout = layout.for_variant(&cx, variant);
+            }
+            // FIXME support derefs
+            _ => return None,
         }
-        CPlaceInner::Addr(_, _) => {
-            // FIXME implement this (used by arguments and returns)
+    }
 
-            AttributeValue::Exprloc(Expression::new())
+    Some(offset)
+}
 
-            // For PointerBase::Stack:
-            //AttributeValue::Exprloc(translate_loc(ValueLoc::Stack(*stack_slot), &context.func.stack_slots).unwrap())
-        }
+/// Pushes the address `offset` bytes after the start of `stack_slot` to `expr`.
+fn push_stack_slot_address(
+    expr: &mut Expression,
+    isa: &dyn TargetIsa,
+    stack_slot: StackSlot,
+    offset: i64,
+    stack_slots: &StackSlots,
+) -> Option<()> {
+    // FIXME support other architectures. The stack slot offsets are relative to the stack pointer
+    // on entry, which is 16 bytes above RBP once the return address and RBP itself are pushed.
+    if isa.triple().architecture != target_lexicon::Architecture::X86_64 {
+        return None;
     }
+
+    let ss_offset = stack_slots[stack_slot].offset?;
+    expr.op_breg(X86_64::RBP, i64::from(ss_offset) + 16 + offset);
+    Some(())
 }
 
 // Adapted from https://github.com/CraneStation/wasmtime/blob/5a1845b4caf7a5dba8eda1fef05213a532ed4259/crates/debug/src/transform/expression.rs#L59-L137
-fn translate_loc(
+fn push_loc(
+    expr: &mut Expression,
     isa: &dyn TargetIsa,
     loc: ValueLoc,
     stack_slots: &StackSlots,
-) -> Option<Expression> {
+) -> Option<()> {
     match loc {
         ValueLoc::Reg(reg) => {
             let machine_reg = isa.map_dwarf_register(reg).unwrap();
-            let mut expr = Expression::new();
             expr.op_reg(gimli::Register(machine_reg));
-            Some(expr)
-        }
-        ValueLoc::Stack(ss) => {
-            if let Some(ss_offset) = stack_slots[ss].offset {
-                let mut expr = Expression::new();
-                expr.op_breg(X86_64::RBP, i64::from(ss_offset) + 16);
-                Some(expr)
-            } else {
-                None
-            }
+            Some(())
         }
+        ValueLoc::Stack(ss) => push_stack_slot_address(expr, isa, ss, 0, stack_slots),
         _ => None,
     }
 }
+
+fn translate_loc(
+    isa: &dyn TargetIsa,
+    loc: ValueLoc,
+    stack_slots: &StackSlots,
+) -> Option<Expression> {
+    let mut expr = Expression::new();
+    push_loc(&mut expr, isa, loc, stack_slots)?;
+    Some(expr)
+}
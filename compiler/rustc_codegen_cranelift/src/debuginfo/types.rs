@@ -0,0 +1,300 @@
+//! Type debuginfo generation (`DW_TAG_*_type` entries of `.debug_info`)
+
+use crate::prelude::*;
+
+use rustc_middle::ty::layout::PrimitiveExt;
+use rustc_target::abi::{Primitive, TagEncoding, Variants};
+
+use gimli::write::{AttributeValue, UnitEntryId};
+
+use super::DebugContext;
+
+impl<'tcx> DebugContext<'tcx> {
+    fn layout_cx(&self) -> layout::LayoutCx<'tcx, TyCtxt<'tcx>> {
+        layout::LayoutCx {
+            tcx: self.tcx,
+            param_env: ParamEnv::reveal_all(),
+        }
+    }
+
+    fn new_type_entry(&mut self, tag: gimli::DwTag) -> UnitEntryId {
+        self.dwarf.unit.add(self.dwarf.unit.root(), tag)
+    }
+
+    pub(super) fn dwarf_ty(&mut self, ty: Ty<'tcx>) -> UnitEntryId {
+        if let Some(type_id) = self.types.get(ty) {
+            return *type_id;
+        }
+
+        let name = format!("{}", ty);
+        let layout = self.tcx.layout_of(ParamEnv::reveal_all().and(ty)).unwrap();
+
+        let type_id = match ty.kind() {
+            ty::Bool => self.primitive_ty(gimli::DW_ATE_boolean),
+            ty::Char => self.primitive_ty(gimli::DW_ATE_UTF),
+            ty::Uint(_) => self.primitive_ty(gimli::DW_ATE_unsigned),
+            ty::Int(_) => self.primitive_ty(gimli::DW_ATE_signed),
+            ty::Float(_) => self.primitive_ty(gimli::DW_ATE_float),
+            ty::Ref(_, pointee_ty, _) | ty::RawPtr(ty::TypeAndMut { ty: pointee_ty, .. }) => {
+                self.pointer_ty(ty, pointee_ty, layout)
+            }
+            ty::Adt(adt_def, _substs) if adt_def.is_enum() => self.enum_ty(ty, adt_def, layout),
+            ty::Adt(adt_def, _substs) if !layout.is_unsized() => {
+                let tag = if adt_def.is_union() {
+                    gimli::DW_TAG_union_type
+                } else {
+                    gimli::DW_TAG_structure_type
+                };
+                let type_id = self.new_type_entry(tag);
+
+                // Ensure that type is inserted before recursing to avoid duplicates
+                self.types.insert(ty, type_id);
+
+                let names = field_names(adt_def.non_enum_variant());
+                self.add_fields(type_id, layout, names);
+
+                type_id
+            }
+            ty::Tuple(_) | ty::Closure(..) => {
+                let type_id = self.new_type_entry(gimli::DW_TAG_structure_type);
+
+                // Ensure that type is inserted before recursing to avoid duplicates
+                self.types.insert(ty, type_id);
+
+                let names = (0..layout.fields.count()).map(|field_idx| format!("__{}", field_idx));
+                self.add_fields(type_id, layout, names);
+
+                type_id
+            }
+            _ => self.new_type_entry(gimli::DW_TAG_structure_type),
+        };
+
+        let type_entry = self.dwarf.unit.get_mut(type_id);
+
+        type_entry.set(gimli::DW_AT_name, AttributeValue::String(name.into_bytes()));
+        type_entry.set(
+            gimli::DW_AT_byte_size,
+            AttributeValue::Udata(layout.size.bytes()),
+        );
+
+        self.types.insert(ty, type_id);
+
+        type_id
+    }
+
+    fn primitive_ty(&mut self, ate: gimli::DwAte) -> UnitEntryId {
+        let type_id = self.new_type_entry(gimli::DW_TAG_base_type);
+        let type_entry = self.dwarf.unit.get_mut(type_id);
+        type_entry.set(gimli::DW_AT_encoding, AttributeValue::Encoding(ate));
+        type_id
+    }
+
+    /// Adds a `DW_TAG_member` to `type_id` for each field of `layout`.
+    fn add_fields(
+        &mut self,
+        type_id: UnitEntryId,
+        layout: TyAndLayout<'tcx>,
+        names: impl Iterator<Item = String>,
+    ) {
+        for (field_idx, name) in names.enumerate() {
+            let field_offset = layout.fields.offset(field_idx);
+            let field_layout = layout.field(&self.layout_cx(), field_idx).unwrap();
+            let field_type = self.dwarf_ty(field_layout.ty);
+            self.add_member(type_id, name, field_type, field_offset);
+        }
+    }
+
+    fn add_member(
+        &mut self,
+        type_id: UnitEntryId,
+        name: String,
+        member_type: UnitEntryId,
+        offset: Size,
+    ) -> UnitEntryId {
+        let member_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_member);
+        let member_entry = self.dwarf.unit.get_mut(member_id);
+
+        member_entry.set(gimli::DW_AT_name, AttributeValue::String(name.into_bytes()));
+        member_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(member_type));
+        member_entry.set(
+            gimli::DW_AT_data_member_location,
+            AttributeValue::Udata(offset.bytes()),
+        );
+
+        member_id
+    }
+
+    /// Thin pointers are described as pointers to their pointee. Fat pointers are described like
+    /// a struct holding the data pointer and the metadata, as the LLVM backend does.
+    fn pointer_ty(
+        &mut self,
+        ty: Ty<'tcx>,
+        pointee_ty: Ty<'tcx>,
+        layout: TyAndLayout<'tcx>,
+    ) -> UnitEntryId {
+        if let Abi::ScalarPair(_, _) = layout.abi {
+            let type_id = self.new_type_entry(gimli::DW_TAG_structure_type);
+
+            // Ensure that type is inserted before recursing to avoid duplicates
+            self.types.insert(ty, type_id);
+
+            // The metadata depends on the unsized tail of the pointee, e.g. `&Path` carries the
+            // length of the `[u8]` at its end.
+            let pointee_tail = self
+                .tcx
+                .struct_tail_erasing_lifetimes(pointee_ty, ParamEnv::reveal_all());
+            let (data_ty, meta_name) = match pointee_tail.kind() {
+                ty::Slice(elem_ty) => (*elem_ty, "length"),
+                ty::Str => (self.tcx.types.u8, "length"),
+                _ => (self.tcx.mk_unit(), "vtable"),
+            };
+            let data_type = self.dwarf_ty(self.tcx.mk_imm_ptr(data_ty));
+            let meta_layout = layout.field(&self.layout_cx(), 1).unwrap();
+            let meta_type = self.dwarf_ty(meta_layout.ty);
+
+            self.add_member(
+                type_id,
+                "data_ptr".to_string(),
+                data_type,
+                layout.fields.offset(0),
+            );
+            self.add_member(
+                type_id,
+                meta_name.to_string(),
+                meta_type,
+                layout.fields.offset(1),
+            );
+
+            return type_id;
+        }
+
+        let type_id = self.new_type_entry(gimli::DW_TAG_pointer_type);
+
+        // Ensure that type is inserted before recursing to avoid duplicates
+        self.types.insert(ty, type_id);
+
+        let pointee = self.dwarf_ty(pointee_ty);
+
+        let type_entry = self.dwarf.unit.get_mut(type_id);
+        type_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(pointee));
+
+        type_id
+    }
+
+    /// Enums are described as a struct containing a `DW_TAG_variant_part`, which selects the
+    /// active variant from the value of the tag, like the LLVM backend does. Each variant is a
+    /// member of the same size as the enum, whose type is a struct holding the fields of the
+    /// variant.
+    fn enum_ty(
+        &mut self,
+        ty: Ty<'tcx>,
+        adt_def: &'tcx ty::AdtDef,
+        layout: TyAndLayout<'tcx>,
+    ) -> UnitEntryId {
+        let type_id = self.new_type_entry(gimli::DW_TAG_structure_type);
+
+        // Ensure that type is inserted before recursing to avoid duplicates
+        self.types.insert(ty, type_id);
+
+        // Enums without variants are uninhabited and have no fields to describe.
+        if adt_def.variants.is_empty() {
+            return type_id;
+        }
+
+        let (tag, tag_encoding, tag_field, variants) = match &layout.variants {
+            Variants::Single { index } => {
+                // Enums with a single inhabited variant have the layout of that variant.
+                let variant = &adt_def.variants[*index];
+                let variant_layout = layout.for_variant(&self.layout_cx(), *index);
+                self.add_fields(type_id, variant_layout, field_names(variant));
+                return type_id;
+            }
+            Variants::Multiple {
+                tag,
+                tag_encoding,
+                tag_field,
+                variants,
+            } => (tag, tag_encoding, *tag_field, variants),
+        };
+
+        let variant_part_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_variant_part);
+        let tag_type = self.dwarf_ty(tag.value.to_int_ty(self.tcx));
+        let tag_id = self.add_member(
+            variant_part_id,
+            "RUST$ENUM$DISR".to_string(),
+            tag_type,
+            layout.fields.offset(tag_field),
+        );
+        let tag_entry = self.dwarf.unit.get_mut(tag_id);
+        tag_entry.set(gimli::DW_AT_artificial, AttributeValue::Flag(true));
+        let variant_part_entry = self.dwarf.unit.get_mut(variant_part_id);
+        variant_part_entry.set(gimli::DW_AT_discr, AttributeValue::UnitRef(tag_id));
+
+        let tag_size = tag.value.size(&self.tcx);
+        let tag_signed = matches!(tag.value, Primitive::Int(_, true));
+        for variant_idx in variants.indices() {
+            let tag_value = match tag_encoding {
+                TagEncoding::Direct => {
+                    let discr = ty.discriminant_for_variant(self.tcx, variant_idx).unwrap();
+                    Some(discr.val)
+                }
+                TagEncoding::Niche {
+                    dataful_variant,
+                    niche_variants,
+                    niche_start,
+                } => {
+                    if variant_idx == *dataful_variant {
+                        // The dataful variant is selected by all the other values of the tag.
+                        None
+                    } else {
+                        let niche_value = variant_idx.as_u32() - niche_variants.start().as_u32();
+                        Some(u128::from(niche_value).wrapping_add(*niche_start))
+                    }
+                }
+            };
+
+            let variant_id = self.dwarf.unit.add(variant_part_id, gimli::DW_TAG_variant);
+            if let Some(tag_value) = tag_value {
+                let tag_value = tag_size.truncate(tag_value);
+                let discr_value = if tag_signed {
+                    AttributeValue::Sdata(tag_size.sign_extend(tag_value) as i128 as i64)
+                } else {
+                    AttributeValue::Udata(tag_value as u64)
+                };
+                let variant_entry = self.dwarf.unit.get_mut(variant_id);
+                variant_entry.set(gimli::DW_AT_discr_value, discr_value);
+            }
+
+            let variant = &adt_def.variants[variant_idx];
+            let variant_type = self.new_type_entry(gimli::DW_TAG_structure_type);
+            let variant_type_entry = self.dwarf.unit.get_mut(variant_type);
+            variant_type_entry.set(
+                gimli::DW_AT_name,
+                AttributeValue::String(variant.ident.to_string().into_bytes()),
+            );
+            variant_type_entry.set(
+                gimli::DW_AT_byte_size,
+                AttributeValue::Udata(layout.size.bytes()),
+            );
+
+            let variant_layout = layout.for_variant(&self.layout_cx(), variant_idx);
+            self.add_fields(variant_type, variant_layout, field_names(variant));
+
+            self.add_member(
+                variant_id,
+                variant.ident.to_string(),
+                variant_type,
+                Size::ZERO,
+            );
+        }
+
+        type_id
+    }
+}
+
+fn field_names(variant: &ty::VariantDef) -> impl Iterator<Item = String> + '_ {
+    variant
+        .fields
+        .iter()
+        .map(|field_def| field_def.ident.to_string())
+}
@@ -53,7 +53,7 @@ fn codegen_field<'tcx>(
     }
 }
 
-fn scalar_pair_calculate_b_offset(
+pub(crate) fn scalar_pair_calculate_b_offset(
     tcx: TyCtxt<'_>,
     a_scalar: &Scalar,
     b_scalar: &Scalar,
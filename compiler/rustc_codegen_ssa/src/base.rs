@@ -854,6 +854,9 @@ fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguR
     if tcx.dep_graph.previous_work_product(work_product_id).is_none() {
         // We don't have anything cached for this CGU. This can happen
         // if the CGU did not exist in the previous session.
+        if tcx.sess.opts.debugging_opts.incremental_explain {
+            rustc_incremental::explain_cgu_codegen(tcx, &cgu.name().as_str(), None);
+        }
         return CguReuse::No;
     }
 
@@ -884,6 +887,9 @@ fn determine_cgu_reuse<'tcx>(tcx: TyCtxt<'tcx>, cgu: &CodegenUnit<'tcx>) -> CguR
             _ => CguReuse::PreLto,
         }
     } else {
+        if tcx.sess.opts.debugging_opts.incremental_explain {
+            rustc_incremental::explain_cgu_codegen(tcx, &cgu.name().as_str(), Some(&dep_node));
+        }
         CguReuse::No
     }
 }
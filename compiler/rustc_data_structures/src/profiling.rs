@@ -79,8 +79,21 @@
 //! invocation) and allocate the corresponding strings together with a mapping
 //! for `DepNodeIndex as StringId`.
 //!
+//!
+//! ## Query Summary
+//!
+//! With `-Z self-profile-summary`, query events are additionally kept in
+//! memory so that the compiler can print (or write as JSON) a per-query
+//! summary at exit, without needing the `measureme` tools. See the `summary`
+//! module for details.
+//!
 //! [mm]: https://github.com/rust-lang/measureme/
 
+mod summary;
+
+pub use self::summary::{QueryData, QuerySummary};
+use self::summary::{QueryEventKind, QuerySummaryRecorder};
+
 use crate::cold_path;
 use crate::fx::FxHashMap;
 
@@ -299,7 +312,7 @@ pub fn generic_activity_with_args(
     #[inline(always)]
     pub fn query_provider(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::QUERY_PROVIDERS, |profiler| {
-            TimingGuard::start(profiler, profiler.query_event_kind, EventId::INVALID)
+            TimingGuard::start_query(profiler, profiler.query_event_kind, QueryEventKind::Provider)
         })
     }
 
@@ -319,7 +332,11 @@ pub fn query_cache_hit(&self, query_invocation_id: QueryInvocationId) {
     #[inline(always)]
     pub fn query_blocked(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::QUERY_BLOCKED, |profiler| {
-            TimingGuard::start(profiler, profiler.query_blocked_event_kind, EventId::INVALID)
+            TimingGuard::start_query(
+                profiler,
+                profiler.query_blocked_event_kind,
+                QueryEventKind::Blocked,
+            )
         })
     }
 
@@ -329,10 +346,10 @@ pub fn query_blocked(&self) -> TimingGuard<'_> {
     #[inline(always)]
     pub fn incr_cache_loading(&self) -> TimingGuard<'_> {
         self.exec(EventFilter::INCR_CACHE_LOADS, |profiler| {
-            TimingGuard::start(
+            TimingGuard::start_query(
                 profiler,
                 profiler.incremental_load_result_event_kind,
-                EventId::INVALID,
+                QueryEventKind::IncrementalLoad,
             )
         })
     }
@@ -354,6 +371,10 @@ fn instant_query_event(
                 thread_id,
             );
 
+            if let Some(summary) = &profiler.summary {
+                summary.record_instant(QueryEventKind::CacheHit, query_invocation_id.0, thread_id);
+            }
+
             TimingGuard::none()
         }));
     }
@@ -390,6 +411,9 @@ pub struct SelfProfiler {
     incremental_load_result_event_kind: StringId,
     query_blocked_event_kind: StringId,
     query_cache_hit_event_kind: StringId,
+
+    /// Query events kept in memory for `-Z self-profile-summary`.
+    summary: Option<QuerySummaryRecorder>,
 }
 
 impl SelfProfiler {
@@ -397,6 +421,7 @@ pub fn new(
         output_directory: &Path,
         crate_name: Option<&str>,
         event_filters: &Option<Vec<String>>,
+        record_query_summary: bool,
     ) -> Result<SelfProfiler, Box<dyn Error + Send + Sync>> {
         fs::create_dir_all(output_directory)?;
 
@@ -444,6 +469,18 @@ pub fn new(
             event_filter_mask = EventFilter::DEFAULT;
         }
 
+        // The summary is computed from the query events, so make sure all of
+        // them are recorded, regardless of the filter.
+        let summary = if record_query_summary {
+            event_filter_mask |= EventFilter::QUERY_PROVIDERS
+                | EventFilter::QUERY_CACHE_HITS
+                | EventFilter::QUERY_BLOCKED
+                | EventFilter::INCR_CACHE_LOADS;
+            Some(QuerySummaryRecorder::new())
+        } else {
+            None
+        };
+
         Ok(SelfProfiler {
             profiler,
             event_filter_mask,
@@ -453,6 +490,7 @@ pub fn new(
             incremental_load_result_event_kind,
             query_blocked_event_kind,
             query_cache_hit_event_kind,
+            summary,
         })
     }
 
@@ -511,10 +549,42 @@ pub fn query_key_recording_enabled(&self) -> bool {
     pub fn event_id_builder(&self) -> EventIdBuilder<'_> {
         EventIdBuilder::new(&self.profiler)
     }
+
+    pub fn query_summary_enabled(&self) -> bool {
+        self.summary.is_some()
+    }
+
+    /// Associates the given query invocations with the name of their query,
+    /// for use in the query summary.
+    pub fn map_query_invocation_ids_to_query_name<I>(&self, from: I, query_name: &'static str)
+    where
+        I: Iterator<Item = QueryInvocationId>,
+    {
+        if let Some(summary) = &self.summary {
+            summary.map_query_invocation_ids(query_name, from.map(|qid| qid.0));
+        }
+    }
+
+    /// Aggregates the query events recorded so far. Returns `None` unless
+    /// the profiler was created with `record_query_summary`.
+    pub fn query_summary(&self) -> Option<QuerySummary> {
+        self.summary.as_ref().map(|summary| summary.summarize())
+    }
+}
+
+/// The start of a query event that is recorded for the query summary.
+struct SummaryStart<'a> {
+    recorder: &'a QuerySummaryRecorder,
+    kind: QueryEventKind,
+    thread_id: u32,
+    start: Instant,
 }
 
 #[must_use]
-pub struct TimingGuard<'a>(Option<measureme::TimingGuard<'a>>);
+pub struct TimingGuard<'a> {
+    guard: Option<measureme::TimingGuard<'a>>,
+    summary: Option<SummaryStart<'a>>,
+}
 
 impl<'a> TimingGuard<'a> {
     #[inline]
@@ -527,23 +597,51 @@ pub fn start(
         let raw_profiler = &profiler.profiler;
         let timing_guard =
             raw_profiler.start_recording_interval_event(event_kind, event_id, thread_id);
-        TimingGuard(Some(timing_guard))
+        TimingGuard { guard: Some(timing_guard), summary: None }
+    }
+
+    /// Starts a query event, which is also recorded for the query summary if
+    /// that is enabled.
+    #[inline]
+    fn start_query(
+        profiler: &'a SelfProfiler,
+        event_kind: StringId,
+        summary_kind: QueryEventKind,
+    ) -> TimingGuard<'a> {
+        let mut guard = TimingGuard::start(profiler, event_kind, EventId::INVALID);
+        guard.summary = profiler.summary.as_ref().map(|recorder| SummaryStart {
+            recorder,
+            kind: summary_kind,
+            thread_id: std::thread::current().id().as_u64().get() as u32,
+            start: Instant::now(),
+        });
+        guard
     }
 
     #[inline]
     pub fn finish_with_query_invocation_id(self, query_invocation_id: QueryInvocationId) {
-        if let Some(guard) = self.0 {
+        let TimingGuard { guard, summary } = self;
+        if let Some(guard) = guard {
             cold_path(|| {
                 let event_id = StringId::new_virtual(query_invocation_id.0);
                 let event_id = EventId::from_virtual(event_id);
                 guard.finish_with_override_event_id(event_id);
+                if let Some(SummaryStart { recorder, kind, thread_id, start }) = summary {
+                    recorder.record_interval(
+                        kind,
+                        query_invocation_id.0,
+                        thread_id,
+                        start,
+                        Instant::now(),
+                    );
+                }
             });
         }
     }
 
     #[inline]
     pub fn none() -> TimingGuard<'a> {
-        TimingGuard(None)
+        TimingGuard { guard: None, summary: None }
     }
 
     #[inline(always)]
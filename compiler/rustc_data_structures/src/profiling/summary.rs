@@ -0,0 +1,272 @@
+//! In-process summary of the query events recorded by the self-profiler.
+//!
+//! With `-Z self-profile-summary`, the `SelfProfiler` keeps a copy of every
+//! query-related event in memory in addition to writing it to the `measureme`
+//! file. At the end of the compilation session the events are aggregated per
+//! query, which yields the same per-query table the `summarize` tool from the
+//! `measureme` project computes from the raw profile, without requiring any
+//! external tooling.
+
+use crate::fx::FxHashMap;
+
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use rustc_serialize::json::{Json, ToJson};
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+mod tests;
+
+/// The label used for events whose query invocation was never mapped to a
+/// query name, e.g. because its result wasn't kept in a query cache.
+const UNKNOWN_QUERY: &str = "<unknown>";
+
+/// The kinds of query events that contribute to the summary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum QueryEventKind {
+    Provider,
+    Blocked,
+    IncrementalLoad,
+    CacheHit,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct RawEvent {
+    kind: QueryEventKind,
+    invocation_id: u32,
+    thread_id: u32,
+    start: Instant,
+    end: Instant,
+}
+
+/// Collects query events while the compiler is running.
+pub(super) struct QuerySummaryRecorder {
+    events: Mutex<Vec<RawEvent>>,
+    query_names: Mutex<FxHashMap<u32, &'static str>>,
+}
+
+impl QuerySummaryRecorder {
+    pub(super) fn new() -> QuerySummaryRecorder {
+        QuerySummaryRecorder {
+            events: Mutex::new(Vec::new()),
+            query_names: Mutex::new(FxHashMap::default()),
+        }
+    }
+
+    pub(super) fn record_interval(
+        &self,
+        kind: QueryEventKind,
+        invocation_id: u32,
+        thread_id: u32,
+        start: Instant,
+        end: Instant,
+    ) {
+        self.events.lock().push(RawEvent { kind, invocation_id, thread_id, start, end });
+    }
+
+    pub(super) fn record_instant(&self, kind: QueryEventKind, invocation_id: u32, thread_id: u32) {
+        let now = Instant::now();
+        self.record_interval(kind, invocation_id, thread_id, now, now);
+    }
+
+    pub(super) fn map_query_invocation_ids(
+        &self,
+        query_name: &'static str,
+        ids: impl Iterator<Item = u32>,
+    ) {
+        let mut query_names = self.query_names.lock();
+        query_names.extend(ids.map(|id| (id, query_name)));
+    }
+
+    pub(super) fn summarize(&self) -> QuerySummary {
+        let events = self.events.lock();
+        let query_names = self.query_names.lock();
+        summarize(&events, &query_names)
+    }
+}
+
+/// The aggregated data of a single query.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryData {
+    pub label: &'static str,
+    /// The time spent executing the query provider, excluding the time spent
+    /// in nested queries.
+    pub self_time: Duration,
+    /// The time spent executing the query provider, including nested queries.
+    pub total_time: Duration,
+    /// How often the query provider was executed.
+    pub invocation_count: usize,
+    /// How often the query result was found in the in-memory cache.
+    pub cache_hits: usize,
+    /// The time spent waiting for the query to complete on another thread.
+    pub blocked_time: Duration,
+    /// The time spent loading results from the incremental compilation cache.
+    pub incremental_load_time: Duration,
+}
+
+impl QueryData {
+    fn new(label: &'static str) -> QueryData {
+        QueryData { label, ..Default::default() }
+    }
+}
+
+/// Per-query results of a compilation session, sorted by descending self time.
+#[derive(Clone, Debug, Default)]
+pub struct QuerySummary {
+    pub queries: Vec<QueryData>,
+}
+
+impl QuerySummary {
+    /// The self time of all queries combined.
+    pub fn total_self_time(&self) -> Duration {
+        self.queries.iter().map(|q| q.self_time).sum()
+    }
+
+    /// Renders the summary as a table, in the same layout as `summarize`.
+    pub fn to_table(&self) -> String {
+        let header = [
+            "Item",
+            "Self time",
+            "% of total time",
+            "Time",
+            "Item count",
+            "Cache hits",
+            "Blocked time",
+            "Incremental load time",
+        ];
+        let total = self.total_self_time().as_secs_f64();
+        let rows: Vec<[String; 8]> = self
+            .queries
+            .iter()
+            .map(|q| {
+                let percent =
+                    if total > 0.0 { q.self_time.as_secs_f64() * 100.0 / total } else { 0.0 };
+                [
+                    q.label.to_owned(),
+                    format!("{:.2?}", q.self_time),
+                    format!("{:.3}", percent),
+                    format!("{:.2?}", q.total_time),
+                    q.invocation_count.to_string(),
+                    q.cache_hits.to_string(),
+                    format!("{:.2?}", q.blocked_time),
+                    format!("{:.2?}", q.incremental_load_time),
+                ]
+            })
+            .collect();
+
+        let mut widths = [0; 8];
+        for (i, title) in header.iter().enumerate() {
+            widths[i] = rows.iter().map(|row| row[i].len()).chain(Some(title.len())).max().unwrap();
+        }
+
+        let separator = widths.iter().map(|w| "-".repeat(w + 2)).collect::<Vec<_>>().join("+");
+        let separator = format!("+{}+\n", separator);
+        let format_row = |cells: &[&str]| {
+            let cells: Vec<_> =
+                cells.iter().zip(&widths).map(|(c, w)| format!(" {:<w$} ", c, w = w)).collect();
+            format!("|{}|\n", cells.join("|"))
+        };
+
+        let mut table = separator.clone();
+        table.push_str(&format_row(&header));
+        table.push_str(&separator);
+        for row in &rows {
+            let cells: Vec<&str> = row.iter().map(|c| &c[..]).collect();
+            table.push_str(&format_row(&cells));
+        }
+        table.push_str(&separator);
+        table.push_str(&format!("\nTotal cpu time: {:?}\n", self.total_self_time()));
+        table
+    }
+}
+
+impl ToJson for QueryData {
+    fn to_json(&self) -> Json {
+        let mut d = BTreeMap::new();
+        d.insert("label".to_owned(), self.label.to_json());
+        d.insert("self_time_ns".to_owned(), (self.self_time.as_nanos() as u64).to_json());
+        d.insert("total_time_ns".to_owned(), (self.total_time.as_nanos() as u64).to_json());
+        d.insert("invocation_count".to_owned(), self.invocation_count.to_json());
+        d.insert("cache_hits".to_owned(), self.cache_hits.to_json());
+        d.insert("blocked_time_ns".to_owned(), (self.blocked_time.as_nanos() as u64).to_json());
+        d.insert(
+            "incremental_load_time_ns".to_owned(),
+            (self.incremental_load_time.as_nanos() as u64).to_json(),
+        );
+        Json::Object(d)
+    }
+}
+
+impl ToJson for QuerySummary {
+    fn to_json(&self) -> Json {
+        let mut d = BTreeMap::new();
+        d.insert(
+            "total_self_time_ns".to_owned(),
+            (self.total_self_time().as_nanos() as u64).to_json(),
+        );
+        d.insert("queries".to_owned(), self.queries.to_json());
+        Json::Object(d)
+    }
+}
+
+/// Aggregates the raw events per query.
+///
+/// Interval events on the same thread are properly nested, so the self time
+/// of an event is its duration minus the duration of the events directly
+/// nested within it.
+fn summarize(events: &[RawEvent], query_names: &FxHashMap<u32, &'static str>) -> QuerySummary {
+    let mut by_label: FxHashMap<&'static str, QueryData> = FxHashMap::default();
+    let label =
+        |event: &RawEvent| query_names.get(&event.invocation_id).copied().unwrap_or(UNKNOWN_QUERY);
+
+    let mut intervals: Vec<&RawEvent> =
+        events.iter().filter(|e| e.kind != QueryEventKind::CacheHit).collect();
+    intervals.sort_by(|a, b| {
+        (a.thread_id, a.start).cmp(&(b.thread_id, b.start)).then(b.end.cmp(&a.end))
+    });
+
+    // The stack of currently open events on the current thread, along with
+    // the time spent in events directly nested within them.
+    let mut stack: Vec<(&RawEvent, Duration)> = Vec::new();
+    let mut finish = |event: &RawEvent, nested: Duration| {
+        let duration = event.end - event.start;
+        let data = by_label.entry(label(event)).or_insert_with(|| QueryData::new(label(event)));
+        match event.kind {
+            QueryEventKind::Provider => {
+                data.invocation_count += 1;
+                data.total_time += duration;
+                data.self_time += duration.checked_sub(nested).unwrap_or_default();
+            }
+            QueryEventKind::Blocked => data.blocked_time += duration,
+            QueryEventKind::IncrementalLoad => data.incremental_load_time += duration,
+            QueryEventKind::CacheHit => unreachable!(),
+        }
+    };
+
+    for event in intervals {
+        while let Some(&(top, nested)) = stack.last() {
+            if top.thread_id == event.thread_id && event.start < top.end {
+                break;
+            }
+            stack.pop();
+            finish(top, nested);
+        }
+        if let Some((_, nested)) = stack.last_mut() {
+            *nested += event.end - event.start;
+        }
+        stack.push((event, Duration::default()));
+    }
+    while let Some((event, nested)) = stack.pop() {
+        finish(event, nested);
+    }
+
+    for event in events.iter().filter(|e| e.kind == QueryEventKind::CacheHit) {
+        by_label.entry(label(event)).or_insert_with(|| QueryData::new(label(event))).cache_hits +=
+            1;
+    }
+
+    let mut queries: Vec<_> = by_label.into_iter().map(|(_, data)| data).collect();
+    queries.sort_by(|a, b| b.self_time.cmp(&a.self_time).then(a.label.cmp(b.label)));
+    QuerySummary { queries }
+}
@@ -0,0 +1,121 @@
+use super::*;
+
+fn event(
+    base: Instant,
+    kind: QueryEventKind,
+    invocation_id: u32,
+    thread_id: u32,
+    start_ms: u64,
+    end_ms: u64,
+) -> RawEvent {
+    RawEvent {
+        kind,
+        invocation_id,
+        thread_id,
+        start: base + Duration::from_millis(start_ms),
+        end: base + Duration::from_millis(end_ms),
+    }
+}
+
+fn names() -> FxHashMap<u32, &'static str> {
+    let mut names = FxHashMap::default();
+    names.insert(0, "type_of");
+    names.insert(1, "typeck");
+    names.insert(2, "type_of");
+    names.insert(3, "mir_built");
+    names
+}
+
+fn query<'a>(summary: &'a QuerySummary, label: &str) -> &'a QueryData {
+    summary.queries.iter().find(|q| q.label == label).unwrap()
+}
+
+#[test]
+fn self_time_excludes_nested_queries() {
+    let base = Instant::now();
+    let events = vec![
+        // `typeck` runs for 10ms, during which `type_of` runs twice and
+        // `mir_built` runs once, nested in the second `type_of`.
+        event(base, QueryEventKind::Provider, 1, 0, 0, 10),
+        event(base, QueryEventKind::Provider, 0, 0, 1, 3),
+        event(base, QueryEventKind::Provider, 2, 0, 4, 8),
+        event(base, QueryEventKind::Provider, 3, 0, 5, 6),
+    ];
+    let summary = summarize(&events, &names());
+
+    let typeck = query(&summary, "typeck");
+    assert_eq!(typeck.invocation_count, 1);
+    assert_eq!(typeck.total_time, Duration::from_millis(10));
+    assert_eq!(typeck.self_time, Duration::from_millis(4));
+
+    let type_of = query(&summary, "type_of");
+    assert_eq!(type_of.invocation_count, 2);
+    assert_eq!(type_of.total_time, Duration::from_millis(6));
+    assert_eq!(type_of.self_time, Duration::from_millis(5));
+
+    let mir_built = query(&summary, "mir_built");
+    assert_eq!(mir_built.self_time, Duration::from_millis(1));
+
+    assert_eq!(summary.total_self_time(), Duration::from_millis(10));
+    let labels: Vec<_> = summary.queries.iter().map(|q| q.label).collect();
+    assert_eq!(labels, ["type_of", "typeck", "mir_built"]);
+}
+
+#[test]
+fn threads_are_nested_independently() {
+    let base = Instant::now();
+    let events = vec![
+        event(base, QueryEventKind::Provider, 1, 0, 0, 10),
+        // Overlaps with `typeck` in time, but runs on another thread.
+        event(base, QueryEventKind::Provider, 0, 1, 2, 5),
+        event(base, QueryEventKind::Blocked, 2, 0, 3, 4),
+    ];
+    let summary = summarize(&events, &names());
+
+    let typeck = query(&summary, "typeck");
+    assert_eq!(typeck.self_time, Duration::from_millis(9));
+
+    let type_of = query(&summary, "type_of");
+    assert_eq!(type_of.invocation_count, 1);
+    assert_eq!(type_of.self_time, Duration::from_millis(3));
+    assert_eq!(type_of.blocked_time, Duration::from_millis(1));
+}
+
+#[test]
+fn cache_hits_and_incremental_loads() {
+    let base = Instant::now();
+    let events = vec![
+        event(base, QueryEventKind::IncrementalLoad, 3, 0, 0, 2),
+        event(base, QueryEventKind::CacheHit, 3, 0, 3, 3),
+        event(base, QueryEventKind::CacheHit, 3, 0, 4, 4),
+        event(base, QueryEventKind::CacheHit, 7, 0, 5, 5),
+    ];
+    let summary = summarize(&events, &names());
+
+    let mir_built = query(&summary, "mir_built");
+    assert_eq!(mir_built.invocation_count, 0);
+    assert_eq!(mir_built.cache_hits, 2);
+    assert_eq!(mir_built.incremental_load_time, Duration::from_millis(2));
+    assert_eq!(mir_built.self_time, Duration::default());
+
+    assert_eq!(query(&summary, UNKNOWN_QUERY).cache_hits, 1);
+}
+
+#[test]
+fn table_and_json_output() {
+    let base = Instant::now();
+    let events = vec![event(base, QueryEventKind::Provider, 1, 0, 0, 2)];
+    let summary = summarize(&events, &names());
+
+    let table = summary.to_table();
+    let lines: Vec<_> = table.lines().collect();
+    assert!(lines[1].starts_with("| Item   | Self time |"));
+    assert!(lines[3].starts_with("| typeck | 2.00ms    | 100.000         |"));
+
+    let json = summary.to_json();
+    assert_eq!(json.find("total_self_time_ns").and_then(Json::as_u64), Some(2_000_000));
+    let queries = json.find("queries").and_then(Json::as_array).unwrap();
+    assert_eq!(queries[0].find("label").and_then(Json::as_string), Some("typeck"));
+    assert_eq!(queries[0].find("invocation_count").and_then(Json::as_u64), Some(1));
+    assert_eq!(queries[0].find("cache_hits").and_then(Json::as_u64), Some(0));
+}
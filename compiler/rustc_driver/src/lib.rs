@@ -18,6 +18,7 @@
 use rustc_codegen_ssa::{traits::CodegenBackend, CodegenResults};
 use rustc_data_structures::profiling::print_time_passes_entry;
 use rustc_data_structures::sync::SeqCst;
+use rustc_data_structures::OnDrop;
 use rustc_errors::registry::{InvalidErrorCode, Registry};
 use rustc_errors::{ErrorReported, PResult};
 use rustc_feature::find_gated_cfg;
@@ -317,6 +318,12 @@ fn run_compiler(
 
     interface::run_compiler(config, |compiler| {
         let sess = compiler.session();
+        // Print the summary even if compilation stops early or fails.
+        let _self_profile_summary = OnDrop(|| {
+            if sess.opts.debugging_opts.self_profile_summary.enabled() {
+                sess.print_self_profile_summary();
+            }
+        });
         let should_stop = RustcDefaultCalls::print_crate_info(
             &***compiler.codegen_backend(),
             sess,
@@ -1180,6 +1187,9 @@ pub fn catch_fatal_errors<F: FnOnce() -> R, R>(f: F) -> Result<R, ErrorReported>
 /// Variant of `catch_fatal_errors` for the `interface::Result` return type
 /// that also computes the exit code.
 pub fn catch_with_exit_code(f: impl FnOnce() -> interface::Result<()>) -> i32 {
+    // All the handlers of the process which emit SARIF to stderr share a single log, written
+    // once they are done, even if the compiler panics.
+    let _sarif_log = OnDrop(rustc_errors::sarif::write_stderr_log);
     let result = catch_fatal_errors(f).and_then(|result| result);
     match result {
         Ok(()) => EXIT_SUCCESS,
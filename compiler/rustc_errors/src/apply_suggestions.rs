@@ -0,0 +1,324 @@
+//! Applying machine-applicable suggestions to the source code (`-Z apply-suggestions`).
+//!
+//! The `SuggestionApplier` wraps the emitter selected by `--error-format` and
+//! forwards every diagnostic to it unchanged. Along the way it records all
+//! suggestions marked as `Applicability::MachineApplicable`. Once the emitter is
+//! dropped at the end of the session, the recorded suggestions are applied to
+//! copies of the affected source files, which are written to the output
+//! directory (the original files are never touched), and a note is emitted
+//! for every file that was rewritten. The copies are based on the source as it
+//! was compiled, so a byte order mark is dropped and line endings are
+//! normalized to `\n`.
+//!
+//! A source file is written to its path relative to the output directory,
+//! without any root, prefix or parent directory components. When two source
+//! files end up with the same output path, only the first one (in sorted
+//! order) is written and a warning is emitted for the other one.
+//!
+//! Suggestions are applied in a deterministic order that doesn't depend on the
+//! order in which diagnostics were emitted: they are sorted by file and
+//! position, and a suggestion that overlaps with one that was already accepted
+//! is skipped (and reported as such). All parts of a suggestion are applied
+//! together or not at all. Suggestions offering several alternative
+//! substitutions are ambiguous and are never applied.
+
+use crate::emitter::Emitter;
+use crate::{CodeSuggestion, Diagnostic, Level};
+
+use rustc_data_structures::sync::Lrc;
+use rustc_lint_defs::{Applicability, FutureBreakage};
+use rustc_span::source_map::SourceMap;
+use rustc_span::{FileName, SourceFile, Span};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+#[cfg(test)]
+mod tests;
+
+/// A single replacement in a source file, in terms of byte offsets into the
+/// source of the file as it was compiled.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Edit {
+    file: PathBuf,
+    lo: usize,
+    hi: usize,
+    snippet: String,
+}
+
+/// The edits of a single suggestion. They are sorted, so two fixes making the
+/// same change compare equal.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Fix {
+    edits: Vec<Edit>,
+    msg: String,
+}
+
+pub struct SuggestionApplier {
+    emitter: Box<dyn Emitter + Send>,
+    sm: Lrc<SourceMap>,
+    /// Directory the rewritten source files are written to.
+    out_dir: PathBuf,
+    fixes: Vec<Fix>,
+}
+
+impl SuggestionApplier {
+    pub fn new(
+        emitter: Box<dyn Emitter + Send>,
+        source_map: Lrc<SourceMap>,
+        out_dir: PathBuf,
+    ) -> SuggestionApplier {
+        SuggestionApplier { emitter, sm: source_map, out_dir, fixes: Vec::new() }
+    }
+
+    /// Converts a suggestion into a `Fix`, if it can be applied automatically.
+    fn fix(&self, suggestion: &CodeSuggestion) -> Option<Fix> {
+        if suggestion.applicability != Applicability::MachineApplicable {
+            return None;
+        }
+        let substitution = match &suggestion.substitutions[..] {
+            [substitution] => substitution,
+            _ => return None,
+        };
+
+        let mut edits = substitution
+            .parts
+            .iter()
+            .map(|part| self.edit(part.span, &part.snippet))
+            .collect::<Option<Vec<_>>>()?;
+        edits.sort();
+        Some(Fix { edits, msg: suggestion.msg.clone() })
+    }
+
+    fn edit(&self, span: Span, snippet: &str) -> Option<Edit> {
+        if span.is_dummy() {
+            return None;
+        }
+        let file = self.sm.lookup_source_file(span.lo());
+        // Only rewrite files that were read from disk while compiling this crate.
+        let path = match &file.name {
+            FileName::Real(name) if !file.is_imported() => name.local_path().to_path_buf(),
+            _ => return None,
+        };
+        Some(Edit {
+            file: path,
+            lo: (span.lo() - file.start_pos).0 as usize,
+            hi: (span.hi() - file.start_pos).0 as usize,
+            snippet: snippet.to_owned(),
+        })
+    }
+
+    /// Finds the source file compiled from `path`.
+    fn source_file(&self, path: &Path) -> Option<Lrc<SourceFile>> {
+        self.sm
+            .files()
+            .iter()
+            .find(|file| match &file.name {
+                FileName::Real(name) => !file.is_imported() && name.local_path() == path,
+                _ => false,
+            })
+            .cloned()
+    }
+
+    fn note(&mut self, msg: &str) {
+        self.emitter.emit_diagnostic(&Diagnostic::new(Level::Note, msg));
+    }
+
+    fn warn(&mut self, msg: &str) {
+        self.emitter.emit_diagnostic(&Diagnostic::new(Level::Warning, msg));
+    }
+
+    fn apply(&mut self) {
+        let (accepted, rejected) = select_fixes(std::mem::take(&mut self.fixes));
+
+        let mut files: BTreeMap<PathBuf, (usize, Vec<Edit>)> = BTreeMap::new();
+        for fix in accepted {
+            let mut touched = Vec::new();
+            for edit in fix.edits {
+                if !touched.contains(&edit.file) {
+                    touched.push(edit.file.clone());
+                }
+                files.entry(edit.file.clone()).or_default().1.push(edit);
+            }
+            for file in touched {
+                files.get_mut(&file).unwrap().0 += 1;
+            }
+        }
+
+        let paths: Vec<_> = files.keys().map(|path| path.as_path()).collect();
+        let out_paths = output_paths(&paths);
+        for ((path, &(count, ref edits)), out_path) in files.iter().zip(out_paths) {
+            let out_path = match out_path {
+                Ok(out_path) => self.out_dir.join(out_path),
+                Err(other) => {
+                    self.warn(&format!(
+                        "failed to apply suggestions to `{}`: it would be written to the same \
+                         path as `{}`",
+                        path.display(),
+                        other.display()
+                    ));
+                    continue;
+                }
+            };
+            let fixed = self
+                .source_file(path)
+                .and_then(|file| file.src.as_ref().and_then(|src| apply_edits(src, edits)))
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "source of the file is not available",
+                    )
+                });
+            let result = fixed.and_then(|fixed| {
+                if let Some(parent) = out_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&out_path, fixed)
+            });
+            match result {
+                Ok(()) => self.note(&format!(
+                    "applied {} {} to `{}`, written to `{}`",
+                    count,
+                    if count == 1 { "suggestion" } else { "suggestions" },
+                    path.display(),
+                    out_path.display()
+                )),
+                Err(err) => self.warn(&format!(
+                    "failed to apply suggestions to `{}`: {}",
+                    path.display(),
+                    err
+                )),
+            }
+        }
+
+        for fix in rejected {
+            let edit = &fix.edits[0];
+            self.note(&format!(
+                "skipped suggestion \"{}\" for `{}` because it overlaps with another suggestion",
+                fix.msg,
+                edit.file.display()
+            ));
+        }
+    }
+}
+
+impl Emitter for SuggestionApplier {
+    fn emit_diagnostic(&mut self, diag: &Diagnostic) {
+        let fixes: Vec<_> = diag.suggestions.iter().filter_map(|sugg| self.fix(sugg)).collect();
+        self.fixes.extend(fixes);
+        self.emitter.emit_diagnostic(diag);
+    }
+
+    fn emit_artifact_notification(&mut self, path: &Path, artifact_type: &str) {
+        self.emitter.emit_artifact_notification(path, artifact_type);
+    }
+
+    fn emit_future_breakage_report(&mut self, diags: Vec<(FutureBreakage, Diagnostic)>) {
+        self.emitter.emit_future_breakage_report(diags);
+    }
+
+    fn should_show_explain(&self) -> bool {
+        self.emitter.should_show_explain()
+    }
+
+    fn supports_color(&self) -> bool {
+        self.emitter.supports_color()
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        self.emitter.source_map()
+    }
+}
+
+impl Drop for SuggestionApplier {
+    fn drop(&mut self) {
+        self.apply();
+    }
+}
+
+/// Returns whether two edits to the same file can't both be applied.
+fn overlaps(a: &Edit, b: &Edit) -> bool {
+    if a.file != b.file {
+        return false;
+    }
+    if a.lo == a.hi && b.lo == b.hi {
+        // Two insertions at the same position would be applied in an arbitrary order.
+        return a.lo == b.lo;
+    }
+    a.lo < b.hi && b.lo < a.hi
+}
+
+/// Splits `fixes` into the ones that should be applied and the ones that are
+/// skipped because they overlap with a fix that was accepted before them.
+///
+/// Fixes are considered in sorted order, so the result only depends on the
+/// set of fixes, not on the order they were collected in. Identical fixes are
+/// applied once.
+fn select_fixes(mut fixes: Vec<Fix>) -> (Vec<Fix>, Vec<Fix>) {
+    fixes.sort();
+    fixes.dedup_by(|a, b| a.edits == b.edits);
+
+    let mut accepted: Vec<Fix> = Vec::new();
+    let mut rejected = Vec::new();
+    for fix in fixes {
+        let conflicts = accepted
+            .iter()
+            .flat_map(|other| &other.edits)
+            .any(|other| fix.edits.iter().any(|edit| overlaps(edit, other)));
+        if conflicts {
+            rejected.push(fix)
+        } else {
+            accepted.push(fix)
+        }
+    }
+    (accepted, rejected)
+}
+
+/// Applies non-overlapping `edits` to `src`. Returns `None` if an edit doesn't
+/// fit the source.
+fn apply_edits(src: &str, edits: &[Edit]) -> Option<String> {
+    let mut edits: Vec<_> = edits.iter().collect();
+    edits.sort_by_key(|edit| (edit.lo, edit.hi));
+
+    let mut fixed = String::with_capacity(src.len());
+    let mut pos = 0;
+    for edit in edits {
+        fixed.push_str(src.get(pos..edit.lo)?);
+        src.get(edit.lo..edit.hi)?;
+        fixed.push_str(&edit.snippet);
+        pos = edit.hi;
+    }
+    fixed.push_str(src.get(pos..)?);
+    Some(fixed)
+}
+
+/// Maps the path of a source file to a path relative to the output directory,
+/// dropping any root, prefix or parent directory components.
+fn relative_output_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Maps each of the source files in `paths` to its path relative to the output
+/// directory. A file whose output path is already used by a file before it
+/// maps to the path of that other file instead.
+fn output_paths<'a>(paths: &[&'a Path]) -> Vec<Result<PathBuf, &'a Path>> {
+    let mut used: BTreeMap<PathBuf, &Path> = BTreeMap::new();
+    paths
+        .iter()
+        .map(|&path| {
+            let out_path = relative_output_path(path);
+            match used.get(&out_path) {
+                Some(&other) => Err(other),
+                None => {
+                    used.insert(out_path.clone(), path);
+                    Ok(out_path)
+                }
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,81 @@
+use super::*;
+
+fn edit(file: &str, lo: usize, hi: usize, snippet: &str) -> Edit {
+    Edit { file: PathBuf::from(file), lo, hi, snippet: snippet.to_owned() }
+}
+
+fn fix(msg: &str, edits: Vec<Edit>) -> Fix {
+    Fix { edits, msg: msg.to_owned() }
+}
+
+#[test]
+fn apply_non_overlapping_edits() {
+    let src = "let x = foo;\nlet y = bar;\n";
+    let edits =
+        vec![edit("a.rs", 21, 24, "baz"), edit("a.rs", 8, 11, "quux"), edit("a.rs", 4, 4, "mut ")];
+    assert_eq!(apply_edits(src, &edits).unwrap(), "let mut x = quux;\nlet y = baz;\n");
+}
+
+#[test]
+fn apply_edits_out_of_bounds() {
+    assert_eq!(apply_edits("fn main() {}", &[edit("a.rs", 10, 20, "")]), None);
+}
+
+#[test]
+fn overlapping_fixes_are_skipped_deterministically() {
+    let first = fix("first", vec![edit("a.rs", 0, 5, "x")]);
+    let second = fix("second", vec![edit("a.rs", 3, 8, "y")]);
+    let third = fix("third", vec![edit("a.rs", 5, 8, "z")]);
+
+    let (accepted, rejected) = select_fixes(vec![third.clone(), second.clone(), first.clone()]);
+    assert_eq!(accepted, vec![first.clone(), third.clone()]);
+    assert_eq!(rejected, vec![second.clone()]);
+
+    // The emission order doesn't matter.
+    assert_eq!(select_fixes(vec![second, first, third]).0.len(), 2);
+}
+
+#[test]
+fn multipart_fixes_are_applied_atomically() {
+    let multipart = fix("wrap", vec![edit("a.rs", 0, 0, "("), edit("b.rs", 10, 12, ")")]);
+    let conflicting = fix("other", vec![edit("b.rs", 11, 11, "!")]);
+
+    let (accepted, rejected) = select_fixes(vec![conflicting.clone(), multipart.clone()]);
+    assert_eq!(accepted, vec![multipart]);
+    assert_eq!(rejected, vec![conflicting]);
+}
+
+#[test]
+fn identical_fixes_are_applied_once() {
+    let a = fix("remove this", vec![edit("a.rs", 2, 4, "")]);
+    let b = fix("remove that", vec![edit("a.rs", 2, 4, "")]);
+    let (accepted, rejected) = select_fixes(vec![a, b]);
+    assert_eq!(accepted.len(), 1);
+    assert!(rejected.is_empty());
+}
+
+#[test]
+fn insertions_at_the_same_position_overlap() {
+    assert!(overlaps(&edit("a.rs", 3, 3, "x"), &edit("a.rs", 3, 3, "y")));
+    assert!(!overlaps(&edit("a.rs", 3, 3, "x"), &edit("a.rs", 3, 5, "y")));
+    assert!(!overlaps(&edit("a.rs", 3, 3, "x"), &edit("b.rs", 3, 3, "y")));
+}
+
+#[test]
+fn output_paths_are_relative() {
+    assert_eq!(relative_output_path(Path::new("/src/lib.rs")), PathBuf::from("src/lib.rs"));
+    assert_eq!(relative_output_path(Path::new("./src/main.rs")), PathBuf::from("src/main.rs"));
+}
+
+#[test]
+fn colliding_output_paths_are_detected() {
+    let paths = [Path::new("/a/lib.rs"), Path::new("a/lib.rs"), Path::new("b/../a/x.rs")];
+    assert_eq!(
+        output_paths(&paths),
+        vec![
+            Ok(PathBuf::from("a/lib.rs")),
+            Err(Path::new("/a/lib.rs")),
+            Ok(PathBuf::from("b/a/x.rs"))
+        ]
+    );
+}
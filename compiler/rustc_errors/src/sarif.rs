@@ -0,0 +1,365 @@
+//! A SARIF 2.1.0 emitter for errors.
+//!
+//! Unlike the JSON emitter, which writes one object per diagnostic, a SARIF
+//! log is a single document. Diagnostics are therefore collected as they are
+//! emitted and the whole log is written out at the end. Every rustc invocation
+//! compiles a single crate, so the log always contains exactly one run.
+//!
+//! A process creates several handlers, e.g. for the early diagnostics about
+//! the command line options, and for each session. All the emitters writing
+//! to stderr share the same log, which is written once by
+//! [`write_stderr_log`] when the process is done. An emitter writing to
+//! another destination has a log of its own, which is written when it's
+//! dropped.
+//!
+//! Diagnostics with a primary span become `results`; their other spans and
+//! sub-diagnostics become `relatedLocations` and their suggestions become
+//! `fixes`. Diagnostics without any span (such as the final "aborting due to
+//! previous error" message) are reported as tool execution notifications of
+//! the run's invocation.
+//!
+//! See <https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html> for
+//! the format specification.
+
+use rustc_span::source_map::{FilePathMapping, SourceMap};
+
+use crate::emitter::Emitter;
+use crate::registry::Registry;
+use crate::{CodeSuggestion, DiagnosticId, Level};
+
+use rustc_data_structures::sync::Lrc;
+use rustc_serialize::json::{Json, ToJson};
+use rustc_span::{MultiSpan, Span, SpanLabel};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::lazy::SyncLazy;
+use std::sync::{Arc, Mutex};
+
+#[cfg(test)]
+mod tests;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+
+/// The log shared by the emitters writing to stderr, created by the first one of them.
+static STDERR_LOG: SyncLazy<Mutex<Option<Arc<Mutex<Log>>>>> = SyncLazy::new(|| Mutex::new(None));
+
+/// Writes the log of the emitters writing to stderr, if any of them was created. This is called
+/// once the process is done emitting diagnostics.
+pub fn write_stderr_log() {
+    let log = STDERR_LOG.lock().unwrap().take();
+    if let Some(log) = log {
+        log.lock().unwrap().write();
+    }
+}
+
+/// The diagnostics collected for a SARIF log, and where to write it.
+struct Log {
+    dst: Box<dyn Write + Send>,
+    /// The `result` objects of the run, in emission order.
+    results: Vec<Json>,
+    /// The `reportingDescriptor` objects of the run, keyed by rule id.
+    rules: BTreeMap<String, Json>,
+    /// Diagnostics without a span, reported as tool execution notifications.
+    notifications: Vec<Json>,
+    /// Whether an error-level diagnostic has been emitted.
+    has_errors: bool,
+}
+
+impl Log {
+    fn new(dst: Box<dyn Write + Send>) -> Log {
+        Log {
+            dst,
+            results: Vec::new(),
+            rules: BTreeMap::new(),
+            notifications: Vec::new(),
+            has_errors: false,
+        }
+    }
+
+    /// Builds the complete SARIF log from the diagnostics collected so far.
+    fn to_json(&self) -> Json {
+        let mut driver = object(vec![
+            ("name", "rustc".to_json()),
+            ("informationUri", "https://www.rust-lang.org/".to_json()),
+            ("rules", Json::Array(self.rules.values().cloned().collect())),
+        ]);
+        if let (Json::Object(driver), Some(version)) = (&mut driver, option_env!("CFG_VERSION")) {
+            driver.insert("version".to_owned(), version.to_json());
+        }
+
+        let invocation = object(vec![
+            ("executionSuccessful", (!self.has_errors).to_json()),
+            ("toolExecutionNotifications", Json::Array(self.notifications.clone())),
+        ]);
+
+        let run = object(vec![
+            ("tool", object(vec![("driver", driver)])),
+            ("invocations", Json::Array(vec![invocation])),
+            // Columns are computed from `CharPos`, not from UTF-16 code units.
+            ("columnKind", "unicodeCodePoints".to_json()),
+            ("results", Json::Array(self.results.clone())),
+        ]);
+
+        object(vec![
+            ("$schema", SARIF_SCHEMA.to_json()),
+            ("version", SARIF_VERSION.to_json()),
+            ("runs", Json::Array(vec![run])),
+        ])
+    }
+
+    fn write(&mut self) {
+        let log = self.to_json();
+        let result = writeln!(&mut self.dst, "{}", log.pretty()).and_then(|_| self.dst.flush());
+        if let Err(e) = result {
+            panic!("failed to print diagnostics: {:?}", e);
+        }
+    }
+}
+
+pub struct SarifEmitter {
+    log: Arc<Mutex<Log>>,
+    /// Whether the log is only used by this emitter, and written when it's dropped.
+    owns_log: bool,
+    registry: Option<Registry>,
+    sm: Lrc<SourceMap>,
+}
+
+impl SarifEmitter {
+    pub fn stderr(registry: Option<Registry>, source_map: Lrc<SourceMap>) -> SarifEmitter {
+        let log = STDERR_LOG
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| {
+                let dst = Box::new(io::BufWriter::new(io::stderr()));
+                Arc::new(Mutex::new(Log::new(dst)))
+            })
+            .clone();
+        SarifEmitter { log, owns_log: false, registry, sm: source_map }
+    }
+
+    pub fn basic() -> SarifEmitter {
+        let file_path_mapping = FilePathMapping::empty();
+        SarifEmitter::stderr(None, Lrc::new(SourceMap::new(file_path_mapping)))
+    }
+
+    pub fn new(
+        dst: Box<dyn Write + Send>,
+        registry: Option<Registry>,
+        source_map: Lrc<SourceMap>,
+    ) -> SarifEmitter {
+        let log = Arc::new(Mutex::new(Log::new(dst)));
+        SarifEmitter { log, owns_log: true, registry, sm: source_map }
+    }
+
+    fn rule_id(&self, rules: &mut BTreeMap<String, Json>, code: &DiagnosticId) -> String {
+        let id = match code {
+            DiagnosticId::Error(s) => s,
+            DiagnosticId::Lint { name, has_future_breakage: _ } => name,
+        };
+
+        if !rules.contains_key(id) {
+            let mut rule = vec![("id", id.to_json())];
+            if let DiagnosticId::Error(_) = code {
+                let explanation = self
+                    .registry
+                    .as_ref()
+                    .and_then(|registry| registry.try_find_description(id).ok().flatten());
+                if let Some(explanation) = explanation {
+                    rule.push(("fullDescription", message(explanation)));
+                    let uri = format!("https://doc.rust-lang.org/error-index.html#{}", id);
+                    rule.push(("helpUri", uri.to_json()));
+                }
+            }
+            rules.insert(id.clone(), object(rule));
+        }
+
+        id.clone()
+    }
+
+    fn result(&self, rules: &mut BTreeMap<String, Json>, diag: &crate::Diagnostic) -> Json {
+        let mut text = diag.message();
+        let mut locations = Vec::new();
+        let mut related_locations = Vec::new();
+
+        for span_label in self.span_labels(&diag.span) {
+            let label = span_label.label.as_deref();
+            let location = self.location(span_label.span, label);
+            if span_label.is_primary {
+                locations.push(location);
+            } else {
+                related_locations.push(location);
+            }
+        }
+
+        for child in &diag.children {
+            let child_message = format!("{}: {}", child.level.to_str(), child.message());
+            let span = child.render_span.as_ref().unwrap_or(&child.span);
+            let spans = self.span_labels(span);
+            if spans.is_empty() {
+                // Notes without a location are part of the main message, the
+                // same way they're rendered as `= note: ...` by the human emitter.
+                text.push('\n');
+                text.push_str(&child_message);
+            }
+            for span_label in spans {
+                related_locations.push(self.location(span_label.span, Some(&child_message)));
+            }
+        }
+
+        let mut result = vec![
+            ("level", level(diag.level).to_json()),
+            ("message", message(&text)),
+            ("locations", Json::Array(locations)),
+        ];
+        if let Some(code) = &diag.code {
+            result.push(("ruleId", self.rule_id(rules, code).to_json()));
+        }
+        if !related_locations.is_empty() {
+            result.push(("relatedLocations", Json::Array(related_locations)));
+        }
+        let fixes: Vec<_> = diag.suggestions.iter().flat_map(|sugg| self.fixes(sugg)).collect();
+        if !fixes.is_empty() {
+            result.push(("fixes", Json::Array(fixes)));
+        }
+        object(result)
+    }
+
+    fn notification(&self, diag: &crate::Diagnostic) -> Json {
+        let mut text = diag.message();
+        for child in &diag.children {
+            text.push_str(&format!("\n{}: {}", child.level.to_str(), child.message()));
+        }
+        object(vec![("level", level(diag.level).to_json()), ("message", message(&text))])
+    }
+
+    /// Converts a suggestion into one SARIF `fix` per alternative substitution.
+    fn fixes(&self, suggestion: &CodeSuggestion) -> Vec<Json> {
+        suggestion
+            .substitutions
+            .iter()
+            .map(|substitution| {
+                // SARIF groups replacements by the file they apply to.
+                let mut changes: BTreeMap<String, Vec<Json>> = BTreeMap::new();
+                for part in &substitution.parts {
+                    let replacement = object(vec![
+                        ("deletedRegion", self.region(part.span)),
+                        ("insertedContent", object(vec![("text", part.snippet.to_json())])),
+                    ]);
+                    changes.entry(self.uri(part.span)).or_default().push(replacement);
+                }
+                let artifact_changes = changes
+                    .into_iter()
+                    .map(|(uri, replacements)| {
+                        object(vec![
+                            ("artifactLocation", object(vec![("uri", uri.to_json())])),
+                            ("replacements", Json::Array(replacements)),
+                        ])
+                    })
+                    .collect();
+                let applicability = format!("{:?}", suggestion.applicability);
+                object(vec![
+                    ("description", message(&suggestion.msg)),
+                    ("artifactChanges", Json::Array(artifact_changes)),
+                    ("properties", object(vec![("applicability", applicability.to_json())])),
+                ])
+            })
+            .collect()
+    }
+
+    fn span_labels(&self, msp: &MultiSpan) -> Vec<SpanLabel> {
+        msp.span_labels().into_iter().filter(|span_label| !span_label.span.is_dummy()).collect()
+    }
+
+    fn location(&self, span: Span, label: Option<&str>) -> Json {
+        let physical_location = object(vec![
+            ("artifactLocation", object(vec![("uri", self.uri(span).to_json())])),
+            ("region", self.region(span)),
+        ]);
+        let mut location = vec![("physicalLocation", physical_location)];
+        if let Some(label) = label {
+            location.push(("message", message(label)));
+        }
+        object(location)
+    }
+
+    fn uri(&self, span: Span) -> String {
+        let file = self.sm.lookup_source_file(span.lo());
+        // SARIF uris always use forward slashes as the path separator.
+        file.name.to_string().replace('\\', "/")
+    }
+
+    fn region(&self, span: Span) -> Json {
+        let start = self.sm.lookup_char_pos(span.lo());
+        let end = self.sm.lookup_char_pos(span.hi());
+        let byte_start = start.file.original_relative_byte_pos(span.lo()).0;
+        let byte_end = start.file.original_relative_byte_pos(span.hi()).0;
+        let mut region = vec![
+            ("startLine", start.line.to_json()),
+            ("startColumn", (start.col.0 + 1).to_json()),
+            ("endLine", end.line.to_json()),
+            ("endColumn", (end.col.0 + 1).to_json()),
+            ("byteOffset", byte_start.to_json()),
+            ("byteLength", (byte_end - byte_start).to_json()),
+        ];
+        if let Ok(snippet) = self.sm.span_to_snippet(span) {
+            region.push(("snippet", object(vec![("text", snippet.to_json())])));
+        }
+        object(region)
+    }
+}
+
+impl Emitter for SarifEmitter {
+    fn emit_diagnostic(&mut self, diag: &crate::Diagnostic) {
+        match diag.level {
+            // Failure notes only point at `--explain` and carry no information
+            // of their own.
+            Level::FailureNote | Level::Cancelled | Level::Allow => return,
+            Level::Bug | Level::Fatal | Level::Error => self.log.lock().unwrap().has_errors = true,
+            Level::Warning | Level::Note | Level::Help => {}
+        }
+
+        let mut log = self.log.lock().unwrap();
+        if self.span_labels(&diag.span).is_empty() {
+            let notification = self.notification(diag);
+            log.notifications.push(notification);
+        } else {
+            let result = self.result(&mut log.rules, diag);
+            log.results.push(result);
+        }
+    }
+
+    fn source_map(&self) -> Option<&Lrc<SourceMap>> {
+        Some(&self.sm)
+    }
+
+    fn should_show_explain(&self) -> bool {
+        false
+    }
+}
+
+impl Drop for SarifEmitter {
+    fn drop(&mut self) {
+        if self.owns_log {
+            self.log.lock().unwrap().write();
+        }
+    }
+}
+
+fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(k, v)| (k.to_owned(), v)).collect())
+}
+
+fn message(text: &str) -> Json {
+    object(vec![("text", text.to_json())])
+}
+
+/// Maps a diagnostic level onto the SARIF `level` of a result or notification.
+fn level(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note | Level::Help | Level::FailureNote => "note",
+        Level::Cancelled | Level::Allow => "none",
+    }
+}
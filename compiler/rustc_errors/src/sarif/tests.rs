@@ -0,0 +1,107 @@
+use super::*;
+
+use crate::{Applicability, Handler};
+use rustc_serialize::json::from_str;
+use rustc_span::{BytePos, Span};
+
+use std::path::Path;
+use std::str;
+use std::sync::{Arc, Mutex};
+
+struct Shared<T> {
+    data: Arc<Mutex<T>>,
+}
+
+impl<T: Write> Write for Shared<T> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.data.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.data.lock().unwrap().flush()
+    }
+}
+
+fn with_default_session_globals(f: impl FnOnce()) {
+    let session_globals = rustc_span::SessionGlobals::new(rustc_span::edition::DEFAULT_EDITION);
+    rustc_span::SESSION_GLOBALS.set(&session_globals, f);
+}
+
+/// Emits the diagnostics produced by `f` for `code` and returns the parsed SARIF log.
+fn emit_sarif(code: &str, f: impl FnOnce(&Handler)) -> Json {
+    let output = Arc::new(Mutex::new(Vec::new()));
+
+    with_default_session_globals(|| {
+        let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+        sm.new_source_file(Path::new("test.rs").to_owned().into(), code.to_owned());
+
+        let se = SarifEmitter::new(Box::new(Shared { data: output.clone() }), None, sm);
+        let handler = Handler::with_emitter(true, None, Box::new(se));
+        f(&handler);
+        // The log is only written once the emitter is dropped.
+        drop(handler);
+    });
+
+    let bytes = output.lock().unwrap();
+    from_str(str::from_utf8(&bytes).unwrap()).unwrap()
+}
+
+#[test]
+fn single_run_without_diagnostics() {
+    let log = emit_sarif("fn main() {}", |_| {});
+
+    assert_eq!(log.find("version").and_then(|v| v.as_string()), Some("2.1.0"));
+    let runs = log.find("runs").and_then(|r| r.as_array()).unwrap();
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].find("results").and_then(|r| r.as_array()).map(|r| r.len()), Some(0));
+}
+
+#[test]
+fn result_with_fix() {
+    let log = emit_sarif("let x = foo;\n", |handler| {
+        let span = Span::with_root_ctxt(BytePos(8), BytePos(11));
+        handler
+            .struct_span_err(span, "cannot find value `foo`")
+            .span_suggestion(
+                span,
+                "a local variable with a similar name exists",
+                "bar".to_string(),
+                Applicability::MachineApplicable,
+            )
+            .emit();
+    });
+
+    let run = &log.find("runs").and_then(|r| r.as_array()).unwrap()[0];
+    let results = run.find("results").and_then(|r| r.as_array()).unwrap();
+    assert_eq!(results.len(), 1);
+
+    let result = &results[0];
+    assert_eq!(result.find("level").and_then(|l| l.as_string()), Some("error"));
+    assert_eq!(
+        result.find_path(&["message", "text"]).and_then(|t| t.as_string()),
+        Some("cannot find value `foo`")
+    );
+
+    let location = &result.find("locations").and_then(|l| l.as_array()).unwrap()[0];
+    let region = location.find_path(&["physicalLocation", "region"]).unwrap();
+    assert_eq!(region.find("startLine").and_then(|l| l.as_u64()), Some(1));
+    assert_eq!(region.find("startColumn").and_then(|c| c.as_u64()), Some(9));
+    assert_eq!(region.find("endColumn").and_then(|c| c.as_u64()), Some(12));
+    assert_eq!(region.find_path(&["snippet", "text"]).and_then(|t| t.as_string()), Some("foo"));
+
+    let fix = &result.find("fixes").and_then(|f| f.as_array()).unwrap()[0];
+    let change = &fix.find("artifactChanges").and_then(|c| c.as_array()).unwrap()[0];
+    assert_eq!(
+        change.find_path(&["artifactLocation", "uri"]).and_then(|u| u.as_string()),
+        Some("test.rs")
+    );
+    let replacement = &change.find("replacements").and_then(|r| r.as_array()).unwrap()[0];
+    assert_eq!(
+        replacement.find_path(&["insertedContent", "text"]).and_then(|t| t.as_string()),
+        Some("bar")
+    );
+    assert_eq!(
+        replacement.find_path(&["deletedRegion", "byteOffset"]).and_then(|o| o.as_u64()),
+        Some(8)
+    );
+}
@@ -629,6 +629,9 @@ pub fn set(&self, features: &mut Features, span: Span) {
     /// Allows references to types with interior mutability within constants
     (active, const_refs_to_cell, "1.51.0", Some(80384), None),
 
+    /// Allows `#[test_params]` on tests, and `#[test_setup]` and `#[test_teardown]` hooks.
+    (active, test_fixtures, "1.51.0", None, None),
+
     // -------------------------------------------------------------------------
     // feature-group-end: actual feature gates
     // -------------------------------------------------------------------------
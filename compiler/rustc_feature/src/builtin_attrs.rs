@@ -318,6 +318,10 @@ macro_rules! experimental {
 
     // Testing:
     gated!(allow_fail, Normal, template!(Word), experimental!(allow_fail)),
+    gated!(
+        test_params, Normal, template!(List: "case1, case2, ..."), test_fixtures,
+        "parametrized tests are an unstable feature",
+    ),
     gated!(
         test_runner, CrateLevel, template!(List: "path"), custom_test_frameworks,
         "custom test frameworks are an unstable feature",
@@ -533,6 +537,10 @@ macro_rules! experimental {
         rustc_test_marker, Normal, template!(Word),
         "the `#[rustc_test_marker]` attribute is used internally to track tests",
     ),
+    rustc_attr!(
+        rustc_test_hook_marker, Normal, template!(Word),
+        "the `#[rustc_test_hook_marker]` attribute is used internally to track test hooks",
+    ),
     rustc_attr!(
         rustc_unsafe_specialization_marker, Normal, template!(Word),
         "the `#[rustc_unsafe_specialization_marker]` attribute is used to check specializations"
@@ -0,0 +1,63 @@
+//! Reports why codegen units could not be reused from the incremental cache,
+//! for `-Z incremental-explain`.
+//!
+//! When the `CompileCodegenUnit` node of a codegen unit cannot be marked
+//! green, the dep-graph records which dependency prevented it, and so on for
+//! each of the dependencies that had to be re-executed. Following these
+//! records from the codegen unit leads back to the input that changed, e.g.
+//! the HIR of an item or the metadata of an upstream crate.
+
+use rustc_middle::dep_graph::DepNode;
+use rustc_middle::ty::TyCtxt;
+
+/// Prints why the codegen unit `cgu_name` has to be codegened again.
+/// `dep_node` is its `CompileCodegenUnit` node, or `None` if there was no
+/// cached version of the codegen unit to begin with.
+pub fn explain_cgu_codegen(tcx: TyCtxt<'_>, cgu_name: &str, dep_node: Option<&DepNode>) {
+    let dep_node = match dep_node {
+        Some(dep_node) => dep_node,
+        None => {
+            println!(
+                "[incremental] codegen unit `{}` was codegened because there is no cached \
+                 version of it from a previous session",
+                cgu_name
+            );
+            return;
+        }
+    };
+
+    let chain = match tcx.dep_graph.invalidation_chain(dep_node) {
+        Some(chain) => chain,
+        None => {
+            println!(
+                "[incremental] codegen unit `{}` was codegened because it did not exist in \
+                 the previous session",
+                cgu_name
+            );
+            return;
+        }
+    };
+
+    if chain.is_empty() {
+        println!(
+            "[incremental] codegen unit `{}` was codegened again, but no changed dependency \
+             was recorded",
+            cgu_name
+        );
+        return;
+    }
+
+    println!("[incremental] codegen unit `{}` was codegened again because of:", cgu_name);
+    println!("[incremental]     {:?}", dep_node);
+    let last = chain.len() - 1;
+    for (i, step) in chain.iter().enumerate() {
+        let what = if step.could_not_force {
+            "could not be recomputed"
+        } else if i == last {
+            "changed (input)"
+        } else {
+            "changed"
+        };
+        println!("[incremental]     <- {:?} {}", step.node, what);
+    }
+}
@@ -12,9 +12,11 @@
 
 mod assert_dep_graph;
 pub mod assert_module_sources;
+mod explain;
 mod persist;
 
 pub use assert_dep_graph::assert_dep_graph;
+pub use explain::explain_cgu_codegen;
 pub use persist::copy_cgu_workproduct_to_incr_comp_cache_dir;
 pub use persist::delete_workproduct_files;
 pub use persist::dep_graph_tcx_init;
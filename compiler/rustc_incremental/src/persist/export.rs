@@ -0,0 +1,84 @@
+//! Exports the dependency graph of the current session as JSON lines, for
+//! `-Z dump-dep-graph-json`.
+//!
+//! Unlike the graphviz output of `-Z dump-dep-graph`, this does not require
+//! `-Z query-dep-graph` and is meant to be consumed by scripts. The first line
+//! is a header object with the format version and some totals. Each of the
+//! following lines is an object describing a single node:
+//!
+//! - `kind`, `label` and `hash` identify the node. The `label` is the
+//!   human-readable form of the node (e.g. `typeck(foo::bar)`), while the
+//!   `hash` is stable across sessions and can be used to match up nodes of
+//!   different exports.
+//! - `fingerprint` is the fingerprint of the node's result.
+//! - `status` says how the node relates to the previous session: `new`, `red`,
+//!   `light-green` or `dark-green` (see `DepNodeOrigin`).
+//! - `index` and `edges` are the node's index in the serialized graph and the
+//!   indices of the nodes it depends on.
+//!
+//! Nodes of the previous session that did not make it into the current graph
+//! are listed after all other nodes. They don't have an `index` or `edges`, and
+//! their `status` is either `red`, if they were marked red, or `unused`.
+
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_middle::dep_graph::{DepNode, SerializedDepGraph};
+use rustc_middle::ty::TyCtxt;
+use rustc_serialize::json::{Json, ToJson};
+use rustc_span::def_id::LOCAL_CRATE;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+const FORMAT_VERSION: u64 = 1;
+
+pub fn export_dep_graph(tcx: TyCtxt<'_>, graph: &SerializedDepGraph, path: &Path) {
+    if let Err(err) = write_dep_graph(tcx, graph, path) {
+        tcx.sess.err(&format!("failed to write dep-graph to `{}`: {}", path.display(), err));
+    }
+}
+
+fn write_dep_graph(tcx: TyCtxt<'_>, graph: &SerializedDepGraph, path: &Path) -> io::Result<()> {
+    let origins = tcx.dep_graph.node_origins();
+    let unused = tcx.dep_graph.unused_previous_nodes();
+    let mut file = BufWriter::new(File::create(path)?);
+
+    let mut header = BTreeMap::new();
+    header.insert("version".to_owned(), FORMAT_VERSION.to_json());
+    header.insert("crate".to_owned(), tcx.crate_name(LOCAL_CRATE).to_string().to_json());
+    header.insert("nodes".to_owned(), graph.nodes.len().to_json());
+    header.insert("edges".to_owned(), graph.edge_list_data.len().to_json());
+    header.insert("unused_previous_nodes".to_owned(), unused.len().to_json());
+    writeln!(file, "{}", Json::Object(header))?;
+
+    for (index, &node) in graph.nodes.iter_enumerated() {
+        let mut object = node_object(node, graph.fingerprints[index], origins[index].as_str());
+        let edges: Vec<_> = graph.edge_targets_from(index).iter().map(|i| i.as_u32()).collect();
+        object.insert("index".to_owned(), index.as_u32().to_json());
+        object.insert("edges".to_owned(), edges.to_json());
+        writeln!(file, "{}", Json::Object(object))?;
+    }
+
+    for (node, fingerprint, marked_red) in unused {
+        let status = if marked_red { "red" } else { "unused" };
+        writeln!(file, "{}", Json::Object(node_object(node, fingerprint, status)))?;
+    }
+
+    file.flush()
+}
+
+fn node_object(node: DepNode, fingerprint: Fingerprint, status: &str) -> BTreeMap<String, Json> {
+    let mut object = BTreeMap::new();
+    object.insert("kind".to_owned(), format!("{:?}", node.kind).to_json());
+    object.insert("label".to_owned(), format!("{:?}", node).to_json());
+    object.insert("hash".to_owned(), hex(node.hash.into()).to_json());
+    object.insert("fingerprint".to_owned(), hex(fingerprint).to_json());
+    object.insert("status".to_owned(), status.to_json());
+    object
+}
+
+/// Formats a fingerprint as a fixed-width hexadecimal string.
+fn hex(fingerprint: Fingerprint) -> String {
+    let (hi, lo) = fingerprint.as_value();
+    format!("{:016x}{:016x}", hi, lo)
+}
@@ -4,6 +4,7 @@
 
 mod data;
 mod dirty_clean;
+mod export;
 mod file_format;
 mod fs;
 mod load;
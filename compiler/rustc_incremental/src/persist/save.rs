@@ -10,6 +10,7 @@
 
 use super::data::*;
 use super::dirty_clean;
+use super::export;
 use super::file_format;
 use super::fs::*;
 use super::work_product;
@@ -19,6 +20,9 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
     tcx.dep_graph.with_ignore(|| {
         let sess = tcx.sess;
         if sess.opts.incremental.is_none() {
+            if sess.opts.debugging_opts.dump_dep_graph_json.is_some() {
+                sess.warn("`-Z dump-dep-graph-json` has no effect without `-C incremental`");
+            }
             return;
         }
         // This is going to be deleted in finalize_session_directory, so let's not create it
@@ -214,6 +218,12 @@ struct Stat {
         println!("[incremental]");
     }
 
+    if let Some(ref path) = tcx.sess.opts.debugging_opts.dump_dep_graph_json {
+        tcx.sess.time("incr_comp_export_dep_graph", || {
+            export::export_dep_graph(tcx, &serialized_graph, path)
+        });
+    }
+
     tcx.sess.time("incr_comp_encode_serialized_dep_graph", || {
         serialized_graph.encode(encoder).unwrap();
     });
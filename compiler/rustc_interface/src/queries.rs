@@ -6,6 +6,7 @@
 use rustc_data_structures::steal::Steal;
 use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::{Lrc, OnceCell, WorkerLocal};
+use rustc_data_structures::OnDrop;
 use rustc_errors::ErrorReported;
 use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_hir::Crate;
@@ -415,6 +416,18 @@ pub fn enter<F, T>(&self, f: F) -> T
     {
         let mut _timer = None;
         let queries = Queries::new(&self);
+        // The query summary needs the names of the queries, which are otherwise only
+        // allocated after codegen. Map them before the global context is freed, even
+        // if compilation stopped early or failed.
+        let _map_query_names = OnDrop(|| {
+            if self.session().opts.debugging_opts.self_profile_summary.enabled() {
+                if let Ok(mut result) = queries.global_ctxt.result.try_borrow_mut() {
+                    if let Some(Ok(gcx)) = &mut *result {
+                        gcx.enter(|tcx| tcx.map_self_profile_query_names());
+                    }
+                }
+            }
+        });
         let ret = f(&queries);
 
         if self.session().opts.debugging_opts.query_stats {
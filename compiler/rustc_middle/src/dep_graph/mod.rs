@@ -11,8 +11,8 @@
 
 pub(crate) use rustc_query_system::dep_graph::DepNodeParams;
 pub use rustc_query_system::dep_graph::{
-    debug, hash_result, DepContext, DepNodeColor, DepNodeIndex, SerializedDepNodeIndex,
-    WorkProduct, WorkProductId,
+    debug, hash_result, DepContext, DepNodeColor, DepNodeIndex, DepNodeOrigin,
+    SerializedDepNodeIndex, WorkProduct, WorkProductId,
 };
 
 pub use dep_node::{label_strs, DepConstructor, DepKind, DepNode, DepNodeExt};
@@ -20,6 +20,7 @@
 pub type DepGraph = rustc_query_system::dep_graph::DepGraph<DepKind>;
 pub type TaskDeps = rustc_query_system::dep_graph::TaskDeps<DepKind>;
 pub type DepGraphQuery = rustc_query_system::dep_graph::DepGraphQuery<DepKind>;
+pub type InvalidatedDepNode = rustc_query_system::dep_graph::InvalidatedDepNode<DepKind>;
 pub type PreviousDepGraph = rustc_query_system::dep_graph::PreviousDepGraph<DepKind>;
 pub type SerializedDepGraph = rustc_query_system::dep_graph::SerializedDepGraph<DepKind>;
 
@@ -109,6 +110,10 @@ fn debug_dep_node(&self) -> bool {
             || self.sess.opts.debugging_opts.query_dep_graph
     }
 
+    fn explain_incremental(&self) -> bool {
+        self.sess.opts.debugging_opts.incremental_explain
+    }
+
     fn try_force_from_dep_node(&self, dep_node: &DepNode) -> bool {
         // FIXME: This match is just a workaround for incremental bugs and should
         // be removed. https://github.com/rust-lang/rust/issues/62649 is one such
@@ -476,6 +476,33 @@ pub fn alloc_self_profile_query_strings(self) {
                     );
                 })*
             }
+
+            /// Maps the invocations of all queries to the name of their query,
+            /// for the summary of `-Z self-profile-summary`.
+            ///
+            /// Unlike `alloc_self_profile_query_strings`, this doesn't depend
+            /// on codegen, so it can be called wherever compilation stops.
+            pub fn map_self_profile_query_names(self) {
+                use crate::ty::query::profiling_support::{
+                    map_self_profile_query_names_for_query_cache,
+                };
+
+                let summary_enabled = self
+                    .prof
+                    .get_self_profiler()
+                    .map_or(false, |profiler| profiler.query_summary_enabled());
+                if !summary_enabled {
+                    return;
+                }
+
+                $({
+                    map_self_profile_query_names_for_query_cache(
+                        self,
+                        stringify!($name),
+                        &self.queries.$name,
+                    );
+                })*
+            }
         }
 
         impl TyCtxtAt<$tcx> {
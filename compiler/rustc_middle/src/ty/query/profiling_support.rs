@@ -287,3 +287,21 @@ pub(super) fn alloc_self_profile_query_strings_for_query_cache<'tcx, C>(
         }
     });
 }
+
+/// Maps the query invocations of a single query cache to the name of the
+/// query, for the query summary of `-Z self-profile-summary`. This method is
+/// called from `map_self_profile_query_names`, which knows all the queries.
+pub(super) fn map_self_profile_query_names_for_query_cache<'tcx, C>(
+    tcx: TyCtxt<'tcx>,
+    query_name: &'static str,
+    query_state: &QueryState<crate::dep_graph::DepKind, <TyCtxt<'tcx> as QueryContext>::Query, C>,
+) where
+    C: QueryCache,
+{
+    tcx.prof.with_profiler(|profiler| {
+        query_state.iter_results(|results| {
+            profiler
+                .map_query_invocation_ids_to_query_name(results.map(|v| v.2.into()), query_name);
+        });
+    });
+}
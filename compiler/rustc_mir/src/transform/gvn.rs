@@ -0,0 +1,492 @@
+//! See the docs for [`GlobalValueNumbering`].
+
+use std::fmt;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::traversal;
+use rustc_middle::mir::visit::{MutVisitor, NonUseContext, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_span::DUMMY_SP;
+
+use crate::dataflow::fmt::DebugWithContext;
+use crate::dataflow::lattice::Dual;
+use crate::dataflow::{Analysis, AnalysisDomain, GenKill, GenKillAnalysis};
+use crate::transform::MirPass;
+
+/// This pass finds the rvalues which compute a value that is already stored in a local, and
+/// replaces them with a copy of that local. For example, the bounds checks of `s[i] + s[i]`
+/// compute the length of `s` and compare `i` to it twice, and the second computations become
+/// copies of the first ones.
+///
+/// Only the rvalues without side effects and which don't read memory through pointers are
+/// considered: arithmetic and comparisons, casts, and the length, discriminant and fields of
+/// locals. Two rvalues are equivalent when they apply the same operation to the same operands. To
+/// see through the copies and temporaries introduced by MIR building, the locals which are
+/// assigned once are first numbered by their value: two of these locals have the same number when
+/// they are copies of each other, or when they are computed by equivalent rvalues of such locals.
+///
+/// The locals holding the value of each rvalue are then tracked by the `AvailableValues` dataflow
+/// analysis. A value is available at a statement when it was computed on all the paths reaching
+/// it, and neither the local holding it nor the operands of the rvalue were modified since.
+pub struct GlobalValueNumbering;
+
+impl<'tcx> MirPass<'tcx> for GlobalValueNumbering {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if !tcx.sess.opts.debugging_opts.mir_gvn {
+            return;
+        }
+
+        let def_id = body.source.def_id();
+        if !tcx.consider_optimizing(|| format!("GlobalValueNumbering {:?}", def_id)) {
+            return;
+        }
+
+        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        let values = Values::new(tcx, param_env, body);
+        if values.values.is_empty() {
+            return;
+        }
+
+        let replacements = find_replacements(tcx, body, &values);
+        if replacements.is_empty() {
+            return;
+        }
+
+        debug!("reusing values in {:?}: {:?}", def_id, replacements);
+        let mut reused = BitSet::new_empty(body.local_decls.len());
+        let basic_blocks = body.basic_blocks_mut();
+        for (location, local) in replacements {
+            let statement = &mut basic_blocks[location.block].statements[location.statement_index];
+            if let StatementKind::Assign(box (_, rvalue)) = &mut statement.kind {
+                *rvalue = Rvalue::Use(Operand::Copy(Place::from(local)));
+                reused.insert(local);
+            }
+        }
+
+        // The reused locals are read after their last use, which must not move out of them.
+        MoveToCopy { tcx, reused }.visit_body(body);
+    }
+}
+
+/// An operand of an `Expr`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Atom<'tcx> {
+    Place(Place<'tcx>),
+    Const(&'tcx ty::Const<'tcx>),
+}
+
+/// An rvalue whose operands are replaced by the locals of the same number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Expr<'tcx> {
+    /// A field of a local.
+    Use(Place<'tcx>),
+    BinaryOp(BinOp, Atom<'tcx>, Atom<'tcx>),
+    CheckedBinaryOp(BinOp, Atom<'tcx>, Atom<'tcx>),
+    UnaryOp(UnOp, Atom<'tcx>),
+    Cast(CastKind, Atom<'tcx>, Ty<'tcx>),
+    Len(Place<'tcx>),
+    Discriminant(Place<'tcx>),
+}
+
+impl<'tcx> Expr<'tcx> {
+    /// The locals the value of the expression depends on.
+    fn locals(&self) -> impl Iterator<Item = Local> {
+        let local = |atom| match atom {
+            Atom::Place(place) => Some(place.local),
+            Atom::Const(_) => None,
+        };
+        let (a, b) = match *self {
+            Expr::Use(place) | Expr::Len(place) | Expr::Discriminant(place) => {
+                (Some(place.local), None)
+            }
+            Expr::BinaryOp(_, lhs, rhs) | Expr::CheckedBinaryOp(_, lhs, rhs) => {
+                (local(lhs), local(rhs))
+            }
+            Expr::UnaryOp(_, operand) | Expr::Cast(_, operand, _) => (local(operand), None),
+        };
+        a.into_iter().chain(b)
+    }
+}
+
+rustc_index::newtype_index! {
+    struct ValueIndex {
+        DEBUG_FORMAT = "v{}"
+    }
+}
+
+/// The value of `expr`, stored in `local`.
+#[derive(Debug)]
+struct Value<'tcx> {
+    local: Local,
+    expr: Expr<'tcx>,
+}
+
+struct Values<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    uses: LocalUses,
+    /// The local with the same number as each local.
+    numbers: IndexVec<Local, Local>,
+    /// The values computed by the body, in the order of its basic blocks and statements.
+    values: IndexVec<ValueIndex, Value<'tcx>>,
+    /// The value computed by each location, if any.
+    value_at: FxHashMap<Location, ValueIndex>,
+    /// The values of each expression.
+    by_expr: FxHashMap<Expr<'tcx>, Vec<ValueIndex>>,
+    /// The values stored in each local, which are lost when it is modified.
+    held_in: IndexVec<Local, Vec<ValueIndex>>,
+    /// The values which depend on each local, which are lost when it is modified.
+    depending_on: IndexVec<Local, Vec<ValueIndex>>,
+    /// The assignments whose rvalue is an expression, with their destination.
+    candidates: Vec<(Location, Place<'tcx>, Expr<'tcx>)>,
+}
+
+impl<'tcx> Values<'tcx> {
+    fn new(tcx: TyCtxt<'tcx>, param_env: ParamEnv<'tcx>, body: &Body<'tcx>) -> Values<'tcx> {
+        let mut uses = LocalUses {
+            borrowed: BitSet::new_empty(body.local_decls.len()),
+            mutations: IndexVec::from_elem(0, &body.local_decls),
+            arg_count: body.arg_count,
+        };
+        uses.visit_body(body);
+
+        let mut values = Values {
+            tcx,
+            uses,
+            numbers: body.local_decls.indices().collect(),
+            values: IndexVec::new(),
+            value_at: FxHashMap::default(),
+            by_expr: FxHashMap::default(),
+            held_in: IndexVec::from_elem(Vec::new(), &body.local_decls),
+            depending_on: IndexVec::from_elem(Vec::new(), &body.local_decls),
+            candidates: Vec::new(),
+        };
+        values.number_locals(body);
+
+        for (block, data) in body.basic_blocks().iter_enumerated() {
+            for (statement_index, statement) in data.statements.iter().enumerate() {
+                let location = Location { block, statement_index };
+                if let StatementKind::Assign(box (place, rvalue)) = &statement.kind {
+                    if let Some(expr) = values.expr(rvalue) {
+                        values.candidates.push((location, *place, expr));
+                        values.add_value(body, param_env, *place, expr, location);
+                    }
+                }
+            }
+        }
+
+        values
+    }
+
+    /// Gives the locals which are assigned once the number of the first local assigned the same
+    /// value. The definition of such a local dominates its uses, so it holds that value wherever
+    /// it is used, and so do the locals it is computed from.
+    fn number_locals(&mut self, body: &Body<'tcx>) {
+        let mut numbered: FxHashMap<Expr<'tcx>, Local> = FxHashMap::default();
+        for (_, data) in traversal::reverse_postorder(body) {
+            for statement in &data.statements {
+                let (local, rvalue) = match &statement.kind {
+                    StatementKind::Assign(box (place, rvalue)) => match place.as_local() {
+                        Some(local) if self.uses.is_ssa(local) => (local, rvalue),
+                        _ => continue,
+                    },
+                    _ => continue,
+                };
+
+                if let Rvalue::Use(Operand::Copy(source) | Operand::Move(source)) = rvalue {
+                    if let Some(source) = source.as_local() {
+                        if self.uses.is_ssa(source) {
+                            self.numbers[local] = self.numbers[source];
+                        }
+                        continue;
+                    }
+                }
+
+                if let Some(expr) = self.expr(rvalue) {
+                    if expr.locals().all(|local| self.uses.is_ssa(local)) {
+                        self.numbers[local] = *numbered.entry(expr).or_insert(local);
+                    }
+                }
+            }
+        }
+    }
+
+    fn add_value(
+        &mut self,
+        body: &Body<'tcx>,
+        param_env: ParamEnv<'tcx>,
+        place: Place<'tcx>,
+        expr: Expr<'tcx>,
+        location: Location,
+    ) {
+        let local = match place.as_local() {
+            Some(local) => local,
+            None => return,
+        };
+        // Borrowed locals may be modified through pointers, and a value depending on its own local
+        // is lost as soon as it is computed. Reusing the value copies it.
+        if self.uses.borrowed.contains(local)
+            || expr.locals().any(|l| l == local)
+            || !body.local_decls[local].ty.is_copy_modulo_regions(self.tcx.at(DUMMY_SP), param_env)
+        {
+            return;
+        }
+
+        let value = self.values.push(Value { local, expr });
+        self.value_at.insert(location, value);
+        self.by_expr.entry(expr).or_default().push(value);
+        self.held_in[local].push(value);
+        for l in expr.locals() {
+            self.depending_on[l].push(value);
+        }
+    }
+
+    fn expr(&self, rvalue: &Rvalue<'tcx>) -> Option<Expr<'tcx>> {
+        Some(match *rvalue {
+            // Copies of whole locals are already handled by their numbers.
+            Rvalue::Use(Operand::Copy(place) | Operand::Move(place))
+                if !place.projection.is_empty() =>
+            {
+                Expr::Use(self.place(place, false)?)
+            }
+            Rvalue::BinaryOp(op, ref lhs, ref rhs) => {
+                Expr::BinaryOp(op, self.atom(lhs)?, self.atom(rhs)?)
+            }
+            Rvalue::CheckedBinaryOp(op, ref lhs, ref rhs) => {
+                Expr::CheckedBinaryOp(op, self.atom(lhs)?, self.atom(rhs)?)
+            }
+            Rvalue::UnaryOp(op, ref operand) => Expr::UnaryOp(op, self.atom(operand)?),
+            Rvalue::Cast(kind, ref operand, ty) => Expr::Cast(kind, self.atom(operand)?, ty),
+            // The length of a slice only depends on the pointer to it.
+            Rvalue::Len(place) => Expr::Len(self.place(place, true)?),
+            Rvalue::Discriminant(place) => Expr::Discriminant(self.place(place, false)?),
+            _ => return None,
+        })
+    }
+
+    fn atom(&self, operand: &Operand<'tcx>) -> Option<Atom<'tcx>> {
+        match *operand {
+            Operand::Copy(place) | Operand::Move(place) => {
+                Some(Atom::Place(self.place(place, false)?))
+            }
+            Operand::Constant(ref constant) => Some(Atom::Const(constant.literal)),
+        }
+    }
+
+    /// Replaces the local of `place` by the local of the same number. Only the places which can't
+    /// be modified behind our back are supported: the fields of locals whose address is never
+    /// taken, and the pointee of such a local if `allow_deref` is set.
+    fn place(&self, place: Place<'tcx>, allow_deref: bool) -> Option<Place<'tcx>> {
+        if self.uses.borrowed.contains(place.local) {
+            return None;
+        }
+
+        for (i, elem) in place.projection.iter().enumerate() {
+            match elem {
+                ProjectionElem::Field(..) | ProjectionElem::Downcast(..) => {}
+                ProjectionElem::Deref if allow_deref && i == 0 => {}
+                _ => return None,
+            }
+        }
+
+        let local =
+            if self.uses.is_ssa(place.local) { self.numbers[place.local] } else { place.local };
+        Some(Place { local, projection: place.projection })
+    }
+}
+
+/// How the locals of the body are used.
+struct LocalUses {
+    /// The locals whose address is taken, which may be modified through pointers.
+    borrowed: BitSet<Local>,
+    /// The number of statements and terminators modifying each local.
+    mutations: IndexVec<Local, u32>,
+    arg_count: usize,
+}
+
+impl LocalUses {
+    /// Whether the local is only assigned once, or never if it is an argument.
+    fn is_ssa(&self, local: Local) -> bool {
+        let assignments = if (1..=self.arg_count).contains(&local.index()) { 0 } else { 1 };
+        local != RETURN_PLACE
+            && !self.borrowed.contains(local)
+            && self.mutations[local] == assignments
+    }
+}
+
+impl<'tcx> Visitor<'tcx> for LocalUses {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        self.super_rvalue(rvalue, location);
+
+        if let Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) = *rvalue {
+            if !place.is_indirect() {
+                self.borrowed.insert(place.local);
+            }
+        }
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        if context.is_mutating_use() {
+            self.mutations[local] += 1;
+        }
+    }
+}
+
+fn find_replacements<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &Body<'tcx>,
+    values: &Values<'tcx>,
+) -> Vec<(Location, Local)> {
+    let mut cursor = AvailableValues { values }
+        .into_engine(tcx, body)
+        .pass_name("gvn")
+        .iterate_to_fixpoint()
+        .into_results_cursor(body);
+
+    let mut replacements = Vec::new();
+    for &(location, place, expr) in &values.candidates {
+        cursor.seek_before_primary_effect(location);
+        let available = &cursor.get().0;
+        let reused = values.by_expr.get(&expr).and_then(|exprs| {
+            exprs
+                .iter()
+                .map(|&value| (value, values.values[value].local))
+                .find(|&(value, local)| available.contains(value) && local != place.local)
+        });
+        if let Some((_, local)) = reused {
+            replacements.push((location, local));
+        }
+    }
+    replacements
+}
+
+/// The values which are computed on all the paths reaching a location, and which are still held
+/// in their local.
+struct AvailableValues<'a, 'tcx> {
+    values: &'a Values<'tcx>,
+}
+
+impl AvailableValues<'_, '_> {
+    /// Forgets the values depending on `local`, and the values held in it if `held` is set.
+    fn kill_local(&self, trans: &mut impl GenKill<ValueIndex>, local: Local, held: bool) {
+        trans.kill_all(self.values.depending_on[local].iter().copied());
+        if held {
+            trans.kill_all(self.values.held_in[local].iter().copied());
+        }
+    }
+}
+
+impl<'tcx> AnalysisDomain<'tcx> for AvailableValues<'_, 'tcx> {
+    /// Use set intersection as the join operator.
+    type Domain = Dual<BitSet<ValueIndex>>;
+
+    const NAME: &'static str = "available_values";
+
+    fn bottom_value(&self, _: &Body<'tcx>) -> Self::Domain {
+        // bottom = available (no value is available at the start block)
+        Dual(BitSet::new_filled(self.values.values.len()))
+    }
+
+    fn initialize_start_block(&self, _: &Body<'tcx>, state: &mut Self::Domain) {
+        state.0.clear();
+    }
+}
+
+impl<'tcx> GenKillAnalysis<'tcx> for AvailableValues<'_, 'tcx> {
+    type Idx = ValueIndex;
+
+    fn statement_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        statement: &Statement<'tcx>,
+        location: Location,
+    ) {
+        TransferFunction { analysis: self, trans: &mut *trans }
+            .visit_statement(statement, location);
+
+        if let Some(&value) = self.values.value_at.get(&location) {
+            trans.gen(value);
+        }
+    }
+
+    fn terminator_effect(
+        &self,
+        trans: &mut impl GenKill<Self::Idx>,
+        terminator: &Terminator<'tcx>,
+        location: Location,
+    ) {
+        // Arguments which are moved into a call may be passed by reference, and be modified in
+        // place by the callee.
+        if let TerminatorKind::Call { args, .. } = &terminator.kind {
+            for arg in args {
+                if let Operand::Move(place) = arg {
+                    self.kill_local(trans, place.local, false);
+                }
+            }
+        }
+
+        TransferFunction { analysis: self, trans }.visit_terminator(terminator, location);
+    }
+
+    fn call_return_effect(
+        &self,
+        _trans: &mut impl GenKill<Self::Idx>,
+        _block: BasicBlock,
+        _func: &Operand<'tcx>,
+        _args: &[Operand<'tcx>],
+        _return_place: Place<'tcx>,
+    ) {
+        // The destination is already killed by `terminator_effect`, on all edges.
+    }
+}
+
+struct TransferFunction<'a, 'b, 'tcx, T> {
+    analysis: &'a AvailableValues<'b, 'tcx>,
+    trans: &'a mut T,
+}
+
+impl<'tcx, T> Visitor<'tcx> for TransferFunction<'_, '_, 'tcx, T>
+where
+    T: GenKill<ValueIndex>,
+{
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        match context {
+            PlaceContext::MutatingUse(_)
+            | PlaceContext::NonUse(NonUseContext::StorageLive | NonUseContext::StorageDead) => {
+                self.analysis.kill_local(self.trans, local, true)
+            }
+            PlaceContext::NonMutatingUse(_) | PlaceContext::NonUse(_) => {}
+        }
+    }
+}
+
+impl DebugWithContext<AvailableValues<'_, '_>> for ValueIndex {
+    fn fmt_with(&self, ctxt: &AvailableValues<'_, '_>, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = &ctxt.values.values[*self];
+        write!(f, "{:?} = {:?}", value.local, value.expr)
+    }
+}
+
+/// Turns the moves out of the reused locals into copies.
+struct MoveToCopy<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    reused: BitSet<Local>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for MoveToCopy<'tcx> {
+    fn tcx<'a>(&'a self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand<'tcx>, location: Location) {
+        if let Operand::Move(place) = *operand {
+            if self.reused.contains(place.local) {
+                *operand = Operand::Copy(place);
+            }
+        }
+
+        self.super_operand(operand, location);
+    }
+}
@@ -0,0 +1,346 @@
+//! See the docs for [`JumpThreading`].
+
+use rustc_index::bit_set::BitSet;
+use rustc_middle::mir::visit::Visitor;
+use rustc_middle::mir::*;
+use rustc_middle::ty::{ParamEnv, Ty, TyCtxt};
+
+use crate::transform::MirPass;
+
+/// This pass redirects the edges entering a block that ends with a `SwitchInt`, when the value of
+/// the operand of that `SwitchInt` is known on the edge. For example, in
+///
+/// ```rust
+/// fn rematch(r: Result<u8, i32>) -> u8 {
+///     let o = match r {
+///         Ok(x) => Some(x),
+///         Err(_) => None,
+///     };
+///     match o {
+///         Some(x) => x,
+///         None => 0,
+///     }
+/// }
+/// ```
+///
+/// each arm of the first `match` sets the discriminant of `o` before jumping to the second
+/// `match`, and can jump directly to the right arm of the second one instead.
+///
+/// The value of the operand is known on an edge when the predecessor assigns it a constant, or
+/// sets the discriminant it is read from, or when the predecessor is itself a `SwitchInt` on the
+/// same value and the edge is taken for a single value. The statements of the block which precede
+/// the `SwitchInt` are duplicated into a new block for each redirected edge, so this is only done
+/// for blocks whose statements are cheap enough.
+pub struct JumpThreading;
+
+/// The maximum cost of the statements duplicated to redirect an edge. The statements of a block
+/// with a single predecessor can always be duplicated, as the block becomes unreachable.
+const MAX_DUPLICATION_COST: usize = 8;
+
+impl<'tcx> MirPass<'tcx> for JumpThreading {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if !tcx.sess.opts.debugging_opts.mir_jump_threading {
+            return;
+        }
+
+        let def_id = body.source.def_id();
+        if !tcx.consider_optimizing(|| format!("JumpThreading {:?}", def_id)) {
+            return;
+        }
+
+        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        let mut borrowed = BorrowedLocals(BitSet::new_empty(body.local_decls.len()));
+        borrowed.visit_body(body);
+
+        // Redirected edges can create new opportunities, and each one may add a block. Their
+        // number is bounded by the initial number of blocks, which guarantees termination.
+        let mut budget = body.basic_blocks().len();
+        while budget > 0 {
+            let finder = ThreadFinder { tcx, param_env, body, borrowed: &borrowed.0 };
+            let mut threads = finder.find_threads();
+            if threads.is_empty() {
+                break;
+            }
+
+            threads.truncate(budget);
+            budget -= threads.len();
+            debug!("threading {:?} in {:?}", threads, def_id);
+            for thread in threads {
+                thread.apply(body);
+            }
+        }
+    }
+}
+
+/// A value that a `SwitchInt` can branch on, at some point of a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Condition<'tcx> {
+    /// The value of a local.
+    Local(Local),
+    /// The discriminant of a place, which doesn't go through a pointer.
+    Discriminant(Place<'tcx>),
+}
+
+impl Condition<'_> {
+    fn local(self) -> Local {
+        match self {
+            Condition::Local(local) => local,
+            Condition::Discriminant(place) => place.local,
+        }
+    }
+}
+
+/// What is known about a condition at the end of a sequence of statements.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Resolved<'tcx> {
+    /// The condition always has this value.
+    Known(u128),
+    /// The condition has the value of this other condition at the start of the statements.
+    Equal(Condition<'tcx>),
+    Unknown,
+}
+
+/// An edge from `pred` to `block`, which is redirected to `target`.
+#[derive(Debug)]
+struct Thread {
+    pred: BasicBlock,
+    block: BasicBlock,
+    target: BasicBlock,
+}
+
+impl Thread {
+    fn apply(self, body: &mut Body<'_>) {
+        let data = &body.basic_blocks()[self.block];
+        let target = if data.statements.is_empty() {
+            self.target
+        } else {
+            let duplicate = BasicBlockData {
+                statements: data.statements.clone(),
+                terminator: Some(Terminator {
+                    source_info: data.terminator().source_info,
+                    kind: TerminatorKind::Goto { target: self.target },
+                }),
+                is_cleanup: data.is_cleanup,
+            };
+            body.basic_blocks_mut().push(duplicate)
+        };
+
+        let terminator = body.basic_blocks_mut()[self.pred].terminator_mut();
+        for successor in terminator.successors_mut() {
+            if *successor == self.block {
+                *successor = target;
+            }
+        }
+    }
+}
+
+struct ThreadFinder<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &'a Body<'tcx>,
+    borrowed: &'a BitSet<Local>,
+}
+
+impl<'a, 'tcx> ThreadFinder<'a, 'tcx> {
+    fn find_threads(&self) -> Vec<Thread> {
+        let predecessors = self.body.predecessors();
+        let mut threads = Vec::new();
+        for (block, data) in self.body.basic_blocks().iter_enumerated() {
+            let (condition, switch_ty, targets) = match &data.terminator().kind {
+                TerminatorKind::SwitchInt {
+                    discr: Operand::Copy(place) | Operand::Move(place),
+                    switch_ty,
+                    targets,
+                } => match place.as_local() {
+                    Some(local) if !self.borrowed.contains(local) => {
+                        (Condition::Local(local), switch_ty, targets)
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+
+            // The `SwitchInt`s on a known value are simplified by `SimplifyBranches`.
+            let condition = match self.resolve(&data.statements, condition, switch_ty) {
+                Resolved::Equal(condition) => condition,
+                Resolved::Known(_) | Resolved::Unknown => continue,
+            };
+
+            let preds = &predecessors[block];
+            match duplication_cost(&data.statements) {
+                Some(cost) if cost <= MAX_DUPLICATION_COST || preds.len() == 1 => {}
+                _ => continue,
+            }
+
+            for &pred in preds {
+                if pred == block {
+                    continue;
+                }
+
+                let value = match self.edge_value(pred, block, condition, switch_ty) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                let target = targets
+                    .iter()
+                    .find(|&(v, _)| v == value)
+                    .map_or_else(|| targets.otherwise(), |(_, target)| target);
+                if target != block {
+                    threads.push(Thread { pred, block, target });
+                }
+            }
+        }
+        threads
+    }
+
+    /// Returns the value of `condition` at the end of `pred`, when the edge from `pred` to
+    /// `block` is taken.
+    fn edge_value(
+        &self,
+        pred: BasicBlock,
+        block: BasicBlock,
+        condition: Condition<'tcx>,
+        switch_ty: Ty<'tcx>,
+    ) -> Option<u128> {
+        let data = &self.body.basic_blocks()[pred];
+        let terminator = data.terminator();
+        if terminator.successors().filter(|&&successor| successor == block).count() != 1 {
+            return None;
+        }
+
+        match &terminator.kind {
+            TerminatorKind::Goto { .. } => {
+                match self.resolve(&data.statements, condition, switch_ty) {
+                    Resolved::Known(value) => Some(value),
+                    Resolved::Equal(_) | Resolved::Unknown => None,
+                }
+            }
+            TerminatorKind::SwitchInt {
+                discr: Operand::Copy(place) | Operand::Move(place),
+                switch_ty: pred_switch_ty,
+                targets,
+            } => {
+                let local = place.as_local().filter(|&local| !self.borrowed.contains(local))?;
+                let discr = self.resolve(&data.statements, Condition::Local(local), pred_switch_ty);
+                let resolved = self.resolve(&data.statements, condition, switch_ty);
+                match (discr, resolved) {
+                    (Resolved::Equal(discr), Resolved::Equal(resolved)) if discr == resolved => {}
+                    _ => return None,
+                }
+
+                let mut values = targets.iter().filter(|&(_, target)| target == block);
+                match (values.next(), values.next()) {
+                    (Some((value, _)), None) if targets.otherwise() != block => Some(value),
+                    // The `otherwise` edge of a switch on a `bool` is taken for the other value.
+                    (None, _) if pred_switch_ty.is_bool() => {
+                        let mut values = targets.iter();
+                        match (values.next(), values.next()) {
+                            (Some((value, _)), None) if value <= 1 => Some(1 - value),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Finds what is known about `condition` at the end of `statements`, by following the
+    /// assignments of its local backwards.
+    fn resolve(
+        &self,
+        statements: &[Statement<'tcx>],
+        mut condition: Condition<'tcx>,
+        switch_ty: Ty<'tcx>,
+    ) -> Resolved<'tcx> {
+        for statement in statements.iter().rev() {
+            let local = condition.local();
+            if self.borrowed.contains(local) {
+                return Resolved::Unknown;
+            }
+
+            match &statement.kind {
+                StatementKind::Assign(box (place, rvalue)) if place.local == local => {
+                    if !place.projection.is_empty() {
+                        return Resolved::Unknown;
+                    }
+                    if let Condition::Discriminant(_) = condition {
+                        return Resolved::Unknown;
+                    }
+
+                    condition = match rvalue {
+                        Rvalue::Use(Operand::Copy(source) | Operand::Move(source)) => {
+                            match source.as_local() {
+                                Some(source) => Condition::Local(source),
+                                None => return Resolved::Unknown,
+                            }
+                        }
+                        Rvalue::Use(Operand::Constant(constant)) => {
+                            return constant
+                                .literal
+                                .try_eval_bits(self.tcx, self.param_env, switch_ty)
+                                .map_or(Resolved::Unknown, Resolved::Known);
+                        }
+                        Rvalue::Discriminant(place) if !place.is_indirect() => {
+                            Condition::Discriminant(*place)
+                        }
+                        _ => return Resolved::Unknown,
+                    };
+                }
+                StatementKind::SetDiscriminant { place, variant_index } if place.local == local => {
+                    if condition != Condition::Discriminant(**place) {
+                        return Resolved::Unknown;
+                    }
+
+                    let ty = place.ty(self.body, self.tcx).ty;
+                    return ty
+                        .discriminant_for_variant(self.tcx, *variant_index)
+                        .map_or(Resolved::Unknown, |discr| Resolved::Known(discr.val));
+                }
+                StatementKind::StorageLive(l) | StatementKind::StorageDead(l) if *l == local => {
+                    return Resolved::Unknown;
+                }
+                StatementKind::LlvmInlineAsm(..) => return Resolved::Unknown,
+                _ => {}
+            }
+        }
+
+        if self.borrowed.contains(condition.local()) {
+            Resolved::Unknown
+        } else {
+            Resolved::Equal(condition)
+        }
+    }
+}
+
+/// Returns the cost of duplicating `statements`, or `None` if they can't be duplicated.
+fn duplication_cost(statements: &[Statement<'_>]) -> Option<usize> {
+    statements.iter().try_fold(0, |cost, statement| match statement.kind {
+        StatementKind::Assign(..) | StatementKind::SetDiscriminant { .. } => Some(cost + 1),
+        StatementKind::FakeRead(..)
+        | StatementKind::StorageLive(..)
+        | StatementKind::StorageDead(..)
+        | StatementKind::Retag(..)
+        | StatementKind::AscribeUserType(..)
+        | StatementKind::Nop => Some(cost),
+        // Inline assembly can't always be emitted twice, e.g. when it defines symbols, and the
+        // coverage counters must stay attached to a single block.
+        StatementKind::LlvmInlineAsm(..) | StatementKind::Coverage(..) => None,
+    })
+}
+
+/// The locals whose address is taken, which may be modified through pointers.
+struct BorrowedLocals(BitSet<Local>);
+
+impl<'tcx> Visitor<'tcx> for BorrowedLocals {
+    fn visit_rvalue(&mut self, rvalue: &Rvalue<'tcx>, location: Location) {
+        self.super_rvalue(rvalue, location);
+
+        if let Rvalue::Ref(_, _, place) | Rvalue::AddressOf(_, place) = *rvalue {
+            if !place.is_indirect() {
+                self.0.insert(place.local);
+            }
+        }
+    }
+}
@@ -31,8 +31,10 @@
 pub mod elaborate_drops;
 pub mod function_item_references;
 pub mod generator;
+pub mod gvn;
 pub mod inline;
 pub mod instcombine;
+pub mod jump_threading;
 pub mod lower_intrinsics;
 pub mod match_branches;
 pub mod multiple_return_terminators;
@@ -47,6 +49,7 @@
 pub mod simplify_branches;
 pub mod simplify_comparison_integral;
 pub mod simplify_try;
+pub mod sroa;
 pub mod uninhabited_enum_branching;
 pub mod unreachable_prop;
 pub mod validate;
@@ -398,8 +401,12 @@ fn run_optimization_passes<'tcx>(tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
         // inst combine is after MatchBranchSimplification to clean up Ne(_1, false)
         &multiple_return_terminators::MultipleReturnTerminators,
         &instcombine::InstCombine,
+        // Split aggregates before const prop, so that it sees their fields as scalar locals.
+        &sroa::ScalarReplacementOfAggregates,
         &const_prop::ConstProp,
         &simplify_branches::SimplifyBranches::new("after-const-prop"),
+        &gvn::GlobalValueNumbering,
+        &jump_threading::JumpThreading,
         &early_otherwise_branch::EarlyOtherwiseBranch,
         &simplify_comparison_integral::SimplifyComparisonIntegral,
         &simplify_try::SimplifyArmIdentity,
@@ -0,0 +1,264 @@
+//! See the docs for [`ScalarReplacementOfAggregates`].
+
+use rustc_index::bit_set::BitSet;
+use rustc_index::vec::IndexVec;
+use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
+use rustc_middle::mir::*;
+use rustc_middle::ty::{self, ParamEnv, Ty, TyCtxt};
+use rustc_session::config::DebugInfo;
+
+use crate::transform::MirPass;
+
+/// This pass splits the locals of struct and tuple types into one local per field, when the
+/// locals are only ever accessed through their fields. For example, in
+///
+/// ```rust
+/// fn sum(a: u32, b: u32) -> u32 {
+///     let p = (a, b);
+///     p.0 + p.1
+/// }
+/// ```
+///
+/// the tuple `p` is replaced by two `u32` locals. Accesses to `p.0` and `p.1` become accesses to
+/// these locals, and the tuple itself is never allocated.
+///
+/// Locals are split when their address is never taken, and when they are only used as a whole in
+/// assignments from or to another local, which are split into one assignment per field. Borrows
+/// of the fields themselves are fine, as the fields become independent locals. Codegen backends
+/// can then keep the fields in registers, which LLVM often fails to do for the big allocas of
+/// iterator adapters at low optimization levels, and which cranelift doesn't do at all.
+///
+/// The fields of the split locals may be aggregates themselves, so the pass repeats until no
+/// locals are left to split.
+pub struct ScalarReplacementOfAggregates;
+
+impl<'tcx> MirPass<'tcx> for ScalarReplacementOfAggregates {
+    fn run_pass(&self, tcx: TyCtxt<'tcx>, body: &mut Body<'tcx>) {
+        if !tcx.sess.opts.debugging_opts.mir_sroa {
+            return;
+        }
+
+        let def_id = body.source.def_id();
+        if !tcx.consider_optimizing(|| format!("ScalarReplacementOfAggregates {:?}", def_id)) {
+            return;
+        }
+
+        let param_env = tcx.param_env_reveal_all_normalized(def_id);
+        loop {
+            let candidates = find_candidates(tcx, param_env, body);
+            if candidates.is_empty() {
+                break;
+            }
+
+            debug!("splitting {:?} in {:?}", candidates, def_id);
+            replace_flattened_locals(tcx, body, candidates);
+        }
+    }
+}
+
+/// Returns the types of the fields of `ty`, if the locals of that type can be split.
+fn field_types<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    ty: Ty<'tcx>,
+) -> Option<Vec<Ty<'tcx>>> {
+    match ty.kind() {
+        ty::Tuple(substs) => Some(substs.types().collect()),
+        // The layout of packed and SIMD structs matters even when their address isn't taken,
+        // and the fields of structs with a destructor can't be dropped separately.
+        ty::Adt(adt, substs)
+            if adt.is_struct() && !adt.repr.packed() && !adt.repr.simd() && !adt.has_dtor(tcx) =>
+        {
+            let fields = &adt.non_enum_variant().fields;
+            Some(
+                fields
+                    .iter()
+                    .map(|field| tcx.normalize_erasing_regions(param_env, field.ty(tcx, substs)))
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+/// Returns the locals to split, with the types of their fields.
+fn find_candidates<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    param_env: ParamEnv<'tcx>,
+    body: &Body<'tcx>,
+) -> Vec<(Local, Vec<Ty<'tcx>>)> {
+    let mut visitor = CandidateVisitor {
+        referenced: BitSet::new_empty(body.local_decls.len()),
+        escaping: BitSet::new_empty(body.local_decls.len()),
+        keep_debuginfo: tcx.sess.opts.debuginfo == DebugInfo::Full,
+    };
+    visitor.visit_body(body);
+
+    body.local_decls
+        .iter_enumerated()
+        // The arguments and the return place are part of the signature.
+        .skip(body.arg_count + 1)
+        .filter(|&(local, _)| visitor.referenced.contains(local))
+        .filter(|&(local, _)| !visitor.escaping.contains(local))
+        .filter_map(|(local, decl)| Some((local, field_types(tcx, param_env, decl.ty)?)))
+        .collect()
+}
+
+struct CandidateVisitor {
+    /// The locals which appear in the body. Split locals don't, which ends the pass.
+    referenced: BitSet<Local>,
+    /// The locals which are used as a whole, and so can't be split.
+    escaping: BitSet<Local>,
+    /// Whether the debuginfo of variables is emitted. It can only describe variables stored in a
+    /// single local.
+    keep_debuginfo: bool,
+}
+
+impl<'tcx> Visitor<'tcx> for CandidateVisitor {
+    fn visit_assign(&mut self, place: &Place<'tcx>, rvalue: &Rvalue<'tcx>, location: Location) {
+        // Assignments between two locals are split into one assignment per field.
+        if let Rvalue::Use(Operand::Copy(source) | Operand::Move(source)) = rvalue {
+            if let (Some(dest), Some(source)) = (place.as_local(), source.as_local()) {
+                self.referenced.insert(dest);
+                self.referenced.insert(source);
+                return;
+            }
+        }
+
+        self.super_assign(place, rvalue, location);
+    }
+
+    fn visit_place(&mut self, place: &Place<'tcx>, context: PlaceContext, location: Location) {
+        if let Some(ProjectionElem::Field(..)) = place.projection.first() {
+            self.referenced.insert(place.local);
+            return;
+        }
+
+        self.super_place(place, context, location);
+    }
+
+    fn visit_var_debug_info(&mut self, var_debug_info: &VarDebugInfo<'tcx>) {
+        if let VarDebugInfoContents::Place(place) = var_debug_info.value {
+            if self.keep_debuginfo && place.projection.is_empty() {
+                self.escaping.insert(place.local);
+            }
+        }
+
+        self.super_var_debug_info(var_debug_info);
+    }
+
+    fn visit_local(&mut self, &local: &Local, context: PlaceContext, _: Location) {
+        self.referenced.insert(local);
+        if context.is_use() {
+            self.escaping.insert(local);
+        }
+    }
+}
+
+fn replace_flattened_locals<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    body: &mut Body<'tcx>,
+    candidates: Vec<(Local, Vec<Ty<'tcx>>)>,
+) {
+    let mut split = Vec::with_capacity(candidates.len());
+    for (local, field_tys) in candidates {
+        let source_info = body.local_decls[local].source_info;
+        let field_locals: Vec<Local> = field_tys
+            .into_iter()
+            .map(|ty| body.local_decls.push(LocalDecl::with_source_info(ty, source_info)))
+            .collect();
+        split.push((local, field_locals));
+    }
+    let mut fields = IndexVec::from_elem(None, &body.local_decls);
+    for (local, field_locals) in split {
+        fields[local] = Some(field_locals);
+    }
+
+    // Without debuginfo, the variables stored in the split locals are simply dropped.
+    body.var_debug_info.retain(|var_debug_info| match var_debug_info.value {
+        VarDebugInfoContents::Place(place) => {
+            !place.projection.is_empty() || fields[place.local].is_none()
+        }
+        VarDebugInfoContents::Const(_) => true,
+    });
+
+    let is_split = |place: Place<'_>| place.as_local().map_or(false, |l| fields[l].is_some());
+    let (basic_blocks, local_decls) = body.basic_blocks_and_local_decls_mut();
+    for data in basic_blocks {
+        for statement in std::mem::take(&mut data.statements) {
+            let source_info = statement.source_info;
+            match statement.kind {
+                StatementKind::StorageLive(local) if fields[local].is_some() => {
+                    data.statements.extend(fields[local].as_ref().unwrap().iter().map(
+                        |&field_local| Statement {
+                            source_info,
+                            kind: StatementKind::StorageLive(field_local),
+                        },
+                    ));
+                }
+                StatementKind::StorageDead(local) if fields[local].is_some() => {
+                    data.statements.extend(fields[local].as_ref().unwrap().iter().map(
+                        |&field_local| Statement {
+                            source_info,
+                            kind: StatementKind::StorageDead(field_local),
+                        },
+                    ));
+                }
+                StatementKind::Assign(box (dest, Rvalue::Use(ref operand)))
+                    if operand.place().map_or(false, |source| {
+                        dest.as_local().is_some()
+                            && source.as_local().is_some()
+                            && (is_split(dest) || is_split(source))
+                    }) =>
+                {
+                    let source = operand.place().unwrap();
+                    let split = fields[dest.local].as_ref().or(fields[source.local].as_ref());
+                    for (i, &field_local) in split.unwrap().iter().enumerate() {
+                        let field = Field::new(i);
+                        let ty = local_decls[field_local].ty;
+                        let field_source = tcx.mk_place_field(source, field, ty);
+                        let operand = match operand {
+                            Operand::Copy(_) => Operand::Copy(field_source),
+                            _ => Operand::Move(field_source),
+                        };
+                        data.statements.push(Statement {
+                            source_info,
+                            kind: StatementKind::Assign(box (
+                                tcx.mk_place_field(dest, field, ty),
+                                Rvalue::Use(operand),
+                            )),
+                        });
+                    }
+                }
+                _ => data.statements.push(statement),
+            }
+        }
+    }
+
+    FieldReplacer { tcx, fields }.visit_body(body);
+}
+
+/// Replaces the fields of the split locals by their own locals.
+struct FieldReplacer<'tcx> {
+    tcx: TyCtxt<'tcx>,
+    fields: IndexVec<Local, Option<Vec<Local>>>,
+}
+
+impl<'tcx> MutVisitor<'tcx> for FieldReplacer<'tcx> {
+    fn tcx<'a>(&'a self) -> TyCtxt<'tcx> {
+        self.tcx
+    }
+
+    fn visit_place(&mut self, place: &mut Place<'tcx>, context: PlaceContext, location: Location) {
+        if let Some(fields) = &self.fields[place.local] {
+            if let Some(&ProjectionElem::Field(field, _)) = place.projection.first() {
+                let projection = self.tcx.intern_place_elems(&place.projection[1..]);
+                *place = Place { local: fields[field.index()], projection };
+            } else {
+                bug!("use of split local {:?} at {:?}", place, location);
+            }
+        }
+
+        self.super_place(place, context, location);
+    }
+}
@@ -20,8 +20,9 @@ pub fn check_meta(sess: &ParseSess, attr: &Attribute) {
 
     // Check input tokens for built-in and key-value attributes.
     match attr_info {
-        // `rustc_dummy` doesn't have any restrictions specific to built-in attributes.
-        Some((name, _, template, _)) if name != sym::rustc_dummy => {
+        // `rustc_dummy` doesn't have any restrictions specific to built-in attributes, and the
+        // cases of `test_params` are expressions, which are parsed by `#[test]`.
+        Some((name, _, template, _)) if name != sym::rustc_dummy && name != sym::test_params => {
             check_builtin_attribute(sess, attr, name, template)
         }
         _ => {
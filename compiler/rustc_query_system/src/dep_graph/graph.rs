@@ -65,6 +65,34 @@ pub fn is_green(self) -> bool {
     }
 }
 
+/// How a node of the current dependency graph relates to the dependency
+/// graph of the previous compilation session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DepNodeOrigin {
+    /// The node did not exist in the previous session.
+    New,
+    /// The node existed in the previous session and was re-executed, with a
+    /// different result.
+    Red,
+    /// The node existed in the previous session and was re-executed, with the
+    /// same result.
+    LightGreen,
+    /// The node existed in the previous session and was marked green without
+    /// being re-executed, because all of its dependencies were green.
+    DarkGreen,
+}
+
+impl DepNodeOrigin {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DepNodeOrigin::New => "new",
+            DepNodeOrigin::Red => "red",
+            DepNodeOrigin::LightGreen => "light-green",
+            DepNodeOrigin::DarkGreen => "dark-green",
+        }
+    }
+}
+
 struct DepGraphData<K: DepKind> {
     /// The new encoding of the dependency graph, optimized for red/green
     /// tracking. The `current` field is the dependency graph of only the
@@ -91,6 +119,46 @@ struct DepGraphData<K: DepKind> {
     previous_work_products: FxHashMap<WorkProductId, WorkProduct>,
 
     dep_node_debug: Lock<FxHashMap<DepNode<K>, String>>,
+
+    /// For nodes of the previous session that could not be marked green, the
+    /// dependency that prevented it. Only recorded for `-Z incremental-explain`.
+    invalidations: Lock<FxHashMap<SerializedDepNodeIndex, Invalidation>>,
+}
+
+/// The reason a node of the previous session could not be marked green.
+#[derive(Clone, Copy)]
+struct Invalidation {
+    dependency: SerializedDepNodeIndex,
+    /// Whether the dependency could not be forced, as opposed to being red.
+    could_not_force: bool,
+}
+
+/// A step in the chain returned by `DepGraph::invalidation_chain`.
+#[derive(Clone, Copy, Debug)]
+pub struct InvalidatedDepNode<K> {
+    pub node: DepNode<K>,
+    /// Whether the node could not be recomputed to check if it changed. If
+    /// this is `false`, the node was recomputed and its result changed.
+    pub could_not_force: bool,
+}
+
+impl<K: DepKind> DepGraphData<K> {
+    #[inline]
+    fn record_invalidation<Ctxt: DepContext<DepKind = K>>(
+        &self,
+        tcx: Ctxt,
+        prev_index: SerializedDepNodeIndex,
+        dependency: SerializedDepNodeIndex,
+        could_not_force: bool,
+    ) {
+        if unlikely!(tcx.explain_incremental()) {
+            // Keep the first reason, in case of concurrent attempts.
+            self.invalidations
+                .lock()
+                .entry(prev_index)
+                .or_insert(Invalidation { dependency, could_not_force });
+        }
+    }
 }
 
 pub fn hash_result<HashCtxt, R>(hcx: &mut HashCtxt, result: &R) -> Option<Fingerprint>
@@ -119,6 +187,7 @@ pub fn new(
                 emitting_diagnostics_cond_var: Condvar::new(),
                 previous: prev_graph,
                 colors: DepNodeColorMap::new(prev_graph_node_count),
+                invalidations: Default::default(),
             })),
             virtual_dep_node_index: Lrc::new(AtomicU32::new(0)),
         }
@@ -657,6 +726,63 @@ pub fn serialize(&self) -> SerializedDepGraph<K> {
         SerializedDepGraph { nodes, fingerprints, edge_list_indices, edge_list_data }
     }
 
+    /// Returns the origin of every node of the current dependency graph, in
+    /// the same order as the nodes of the graph returned by `serialize`.
+    pub fn node_origins(&self) -> IndexVec<SerializedDepNodeIndex, DepNodeOrigin> {
+        let data = self.data.as_ref().unwrap().current.data.lock();
+        data.hybrid_indices
+            .iter()
+            .map(|&hybrid_index| match hybrid_index.into() {
+                HybridIndex::New(_) => DepNodeOrigin::New,
+                HybridIndex::Red(_) => DepNodeOrigin::Red,
+                HybridIndex::LightGreen(_) => DepNodeOrigin::LightGreen,
+                HybridIndex::DarkGreen(_) => DepNodeOrigin::DarkGreen,
+            })
+            .collect()
+    }
+
+    /// Returns the nodes of the previous dependency graph that did not make it
+    /// into the current one, together with their fingerprint from the previous
+    /// session and whether they were marked red. Nodes that were not marked
+    /// at all were simply not needed during this session.
+    pub fn unused_previous_nodes(&self) -> Vec<(DepNode<K>, Fingerprint, bool)> {
+        let data = self.data.as_ref().unwrap();
+        let previous = &data.previous;
+        let prev_index_to_index = data.current.prev_index_to_index.lock();
+        prev_index_to_index
+            .iter_enumerated()
+            .filter(|(_, index)| index.is_none())
+            .map(|(prev_index, _)| {
+                let marked_red = data.colors.get(prev_index) == Some(DepNodeColor::Red);
+                (
+                    previous.index_to_node(prev_index),
+                    previous.fingerprint_by_index(prev_index),
+                    marked_red,
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the chain of dependencies that prevented `dep_node` from being
+    /// marked green, starting with its direct dependency and ending with the
+    /// input that changed. Returns `None` if the node did not exist in the
+    /// previous session. Only available with `-Z incremental-explain`.
+    pub fn invalidation_chain(&self, dep_node: &DepNode<K>) -> Option<Vec<InvalidatedDepNode<K>>> {
+        let data = self.data.as_ref()?;
+        let mut prev_index = data.previous.node_to_index_opt(dep_node)?;
+        let invalidations = data.invalidations.lock();
+
+        let mut chain = Vec::new();
+        while let Some(invalidation) = invalidations.get(&prev_index) {
+            chain.push(InvalidatedDepNode {
+                node: data.previous.index_to_node(invalidation.dependency),
+                could_not_force: invalidation.could_not_force,
+            });
+            prev_index = invalidation.dependency;
+        }
+        Some(chain)
+    }
+
     pub fn node_color(&self, dep_node: &DepNode<K>) -> Option<DepNodeColor> {
         if let Some(ref data) = self.data {
             if let Some(prev_index) = data.previous.node_to_index_opt(dep_node) {
@@ -763,6 +889,7 @@ fn try_mark_previous_green<Ctxt: DepContext<DepKind = K>>(
                         dep_node,
                         data.previous.index_to_node(dep_dep_node_index)
                     );
+                    data.record_invalidation(tcx, prev_dep_node_index, dep_dep_node_index, false);
                     return None;
                 }
                 None => {
@@ -816,6 +943,12 @@ fn try_mark_previous_green<Ctxt: DepContext<DepKind = K>>(
                                         dependency {:?} was red after forcing",
                                     dep_node, dep_dep_node
                                 );
+                                data.record_invalidation(
+                                    tcx,
+                                    prev_dep_node_index,
+                                    dep_dep_node_index,
+                                    false,
+                                );
                                 return None;
                             }
                             None => {
@@ -851,6 +984,12 @@ fn try_mark_previous_green<Ctxt: DepContext<DepKind = K>>(
                                 could not be forced",
                             dep_node, dep_dep_node
                         );
+                        data.record_invalidation(
+                            tcx,
+                            prev_dep_node_index,
+                            dep_dep_node_index,
+                            true,
+                        );
                         return None;
                     }
                 }
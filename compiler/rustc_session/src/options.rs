@@ -846,6 +846,9 @@ fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
         "only allow the listed language features to be enabled in code (space separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    apply_suggestions: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "apply all machine-applicable suggestions and write the rewritten source files \
+        into the given directory"),
     asm_comments: bool = (false, parse_bool, [TRACKED],
         "generate comments into the assembly (may change behavior) (default: no)"),
     ast_json: bool = (false, parse_bool, [UNTRACKED],
@@ -887,6 +890,9 @@ fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
     dump_dep_graph: bool = (false, parse_bool, [UNTRACKED],
         "dump the dependency graph to $RUST_DEP_GRAPH (default: /tmp/dep_graph.gv) \
         (default: no)"),
+    dump_dep_graph_json: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the dependency graph of the current session to the given file as JSON lines, \
+        including the red/green status of each node (requires `-C incremental`)"),
     dump_mir: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "dump MIR state to file.
         `val` is used to select which passes and functions to dump. For example:
@@ -937,6 +943,9 @@ fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
         "generate human-readable, predictable names for codegen units (default: no)"),
     identify_regions: bool = (false, parse_bool, [UNTRACKED],
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
+    incremental_explain: bool = (false, parse_bool, [UNTRACKED],
+        "print why each codegen unit could not be reused from the incremental cache \
+        (default: no)"),
     incremental_ignore_spans: bool = (false, parse_bool, [UNTRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
@@ -984,8 +993,17 @@ fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
     mir_emit_retag: bool = (false, parse_bool, [TRACKED],
         "emit Retagging MIR statements, interpreted e.g., by miri; implies -Zmir-opt-level=0 \
         (default: no)"),
+    mir_gvn: bool = (false, parse_bool, [TRACKED],
+        "replace the computations of values which are already stored in a local by copies of \
+        that local (default: no)"),
+    mir_jump_threading: bool = (false, parse_bool, [TRACKED],
+        "duplicate small blocks to bypass the branches whose outcome is known on some of their \
+        incoming edges (default: no)"),
     mir_opt_level: usize = (1, parse_uint, [TRACKED],
         "MIR optimization level (0-3; default: 1)"),
+    mir_sroa: bool = (false, parse_bool, [TRACKED],
+        "split the locals of structs and tuples which are only accessed through their fields \
+        into one local per field (default: no)"),
     mutable_noalias: bool = (false, parse_bool, [TRACKED],
         "emit noalias metadata for mutable references (default: no)"),
     new_llvm_pass_manager: bool = (false, parse_bool, [TRACKED],
@@ -1089,6 +1107,10 @@ fn parse_target_feature(slot: &mut String, v: Option<&str>) -> bool {
         for example: `-Z self-profile-events=default,query-keys`
         all options: none, all, default, generic-activity, query-provider, query-cache-hit
                      query-blocked, incr-cache-load, query-keys, function-args, args, llvm"),
+    self_profile_summary: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "print a per-query summary of the self profile at exit, or write it as JSON to the \
+        given file (requires `-Z self-profile`)"),
     share_generics: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "make the current crate share its generic instantiations"),
     show_span: Option<String> = (None, parse_opt_string, [TRACKED],
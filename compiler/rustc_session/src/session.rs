@@ -18,11 +18,14 @@
     self, AtomicU64, AtomicUsize, Lock, Lrc, OnceCell, OneThread, Ordering, Ordering::SeqCst,
 };
 use rustc_errors::annotate_snippet_emitter_writer::AnnotateSnippetEmitterWriter;
+use rustc_errors::apply_suggestions::SuggestionApplier;
 use rustc_errors::emitter::{Emitter, EmitterWriter, HumanReadableErrorType};
 use rustc_errors::json::JsonEmitter;
 use rustc_errors::registry::Registry;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_errors::{Applicability, Diagnostic, DiagnosticBuilder, DiagnosticId, ErrorReported};
 use rustc_lint_defs::FutureBreakage;
+use rustc_serialize::json::ToJson;
 use rustc_span::edition::Edition;
 use rustc_span::source_map::{FileLoader, MultiSpan, RealFileLoader, SourceMap, Span};
 use rustc_span::{sym, SourceFileHashAlgorithm, Symbol};
@@ -961,6 +964,29 @@ pub fn print_perf_stats(&self) {
         );
     }
 
+    /// Prints the per-query summary requested with `-Z self-profile-summary`,
+    /// or writes it as JSON if a path was given.
+    pub fn print_self_profile_summary(&self) {
+        let summary = match self.prof.get_self_profiler().and_then(|p| p.query_summary()) {
+            Some(summary) => summary,
+            None => return,
+        };
+        match self.opts.debugging_opts.self_profile_summary {
+            SwitchWithOptPath::Enabled(Some(ref path)) => {
+                let json = summary.to_json().pretty().to_string();
+                if let Err(e) = std::fs::write(path, json) {
+                    self.err(&format!(
+                        "failed to write self-profile summary to `{}`: {}",
+                        path.display(),
+                        e
+                    ));
+                }
+            }
+            SwitchWithOptPath::Enabled(None) => print!("{}", summary.to_table()),
+            SwitchWithOptPath::Disabled => {}
+        }
+    }
+
     /// We want to know if we're allowed to do an optimization for crate foo from -z fuel=foo=n.
     /// This expends fuel if applicable, and records fuel if applicable.
     pub fn consider_optimizing<T: Fn() -> String>(&self, crate_name: &str, msg: T) -> bool {
@@ -1246,6 +1272,12 @@ fn default_emitter(
             )
             .ui_testing(sopts.debugging_opts.ui_testing),
         ),
+        (config::ErrorOutputType::Sarif, None) => {
+            Box::new(SarifEmitter::stderr(Some(registry), source_map))
+        }
+        (config::ErrorOutputType::Sarif, Some(dst)) => {
+            Box::new(SarifEmitter::new(dst, Some(registry), source_map))
+        }
     }
 }
 
@@ -1300,7 +1332,10 @@ pub fn build_session(
         sopts.file_path_mapping(),
         hash_kind,
     ));
-    let emitter = default_emitter(&sopts, registry, source_map.clone(), write_dest);
+    let mut emitter = default_emitter(&sopts, registry, source_map.clone(), write_dest);
+    if let Some(ref out_dir) = sopts.debugging_opts.apply_suggestions {
+        emitter = Box::new(SuggestionApplier::new(emitter, source_map.clone(), out_dir.clone()));
+    }
 
     let span_diagnostic = rustc_errors::Handler::with_emitter_and_flags(
         emitter,
@@ -1316,6 +1351,7 @@ pub fn build_session(
             directory,
             sopts.crate_name.as_deref(),
             &sopts.debugging_opts.self_profile_events,
+            sopts.debugging_opts.self_profile_summary.enabled(),
         );
         match profiler {
             Ok(profiler) => Some(Arc::new(profiler)),
@@ -1325,6 +1361,12 @@ pub fn build_session(
             }
         }
     } else {
+        if sopts.debugging_opts.self_profile_summary.enabled() {
+            early_warn(
+                sopts.error_format,
+                "`-Z self-profile-summary` has no effect without `-Z self-profile`",
+            );
+        }
         None
     };
 
@@ -1601,6 +1643,7 @@ pub fn early_error(output: config::ErrorOutputType, msg: &str) -> ! {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, None, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_fatal(msg).emit();
@@ -1616,6 +1659,7 @@ pub fn early_warn(output: config::ErrorOutputType, msg: &str) {
         config::ErrorOutputType::Json { pretty, json_rendered } => {
             Box::new(JsonEmitter::basic(pretty, json_rendered, None, false))
         }
+        config::ErrorOutputType::Sarif => Box::new(SarifEmitter::basic()),
     };
     let handler = rustc_errors::Handler::with_emitter(true, None, emitter);
     handler.struct_warn(msg).emit();
@@ -987,6 +987,7 @@
         rustc_std_internal_symbol,
         rustc_symbol_name,
         rustc_synthetic,
+        rustc_test_hook_marker,
         rustc_test_marker,
         rustc_then_this_would_need,
         rustc_unsafe_specialization_marker,
@@ -1141,8 +1142,12 @@
         test_2018_feature,
         test_accepted_feature,
         test_case,
+        test_fixtures,
+        test_params,
         test_removed_feature,
         test_runner,
+        test_setup,
+        test_teardown,
         then_with,
         thread,
         thread_local,
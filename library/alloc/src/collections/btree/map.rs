@@ -5,7 +5,7 @@
 use core::iter::{FromIterator, FusedIterator};
 use core::marker::PhantomData;
 use core::mem::{self, ManuallyDrop};
-use core::ops::{Index, RangeBounds};
+use core::ops::{Bound, Index, RangeBounds};
 use core::ptr;
 
 use super::borrow::DormantMutRef;
@@ -13,6 +13,8 @@
 use super::search::{self, SearchResult::*};
 use super::unwrap_unchecked;
 
+mod cursor;
+pub use cursor::{Cursor, CursorMut};
 mod entry;
 pub use entry::{Entry, OccupiedEntry, VacantEntry};
 use Entry::*;
@@ -1025,6 +1027,136 @@ pub fn range_mut<T: ?Sized, R>(&mut self, range: R) -> RangeMut<'_, K, V>
         }
     }
 
+    /// Returns a cursor pointing at the gap right before the first element
+    /// that lies above the given bound, i.e., the first element whose key is
+    /// at least (for `Included`) or greater than (for `Excluded`) the bound.
+    /// With an `Unbounded` bound, the cursor points before the first element.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let map: BTreeMap<_, _> = vec![(1, "a"), (2, "b"), (3, "c"), (4, "d")].into_iter().collect();
+    /// let cursor = map.lower_bound(Bound::Included(&2));
+    /// assert_eq!(cursor.peek_prev(), Some((&1, &"a")));
+    /// assert_eq!(cursor.peek_next(), Some((&2, &"b")));
+    /// let cursor = map.lower_bound(Bound::Excluded(&2));
+    /// assert_eq!(cursor.peek_prev(), Some((&2, &"b")));
+    /// assert_eq!(cursor.peek_next(), Some((&3, &"c")));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn lower_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let current = self.root.as_ref().map(|root| root.reborrow().lower_bound(bound));
+        Cursor { current }
+    }
+
+    /// Returns a mutable cursor pointing at the gap right before the first
+    /// element that lies above the given bound. See [`lower_bound`] for how
+    /// the bound is interpreted.
+    ///
+    /// [`lower_bound`]: BTreeMap::lower_bound
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: BTreeMap<_, _> = vec![(1, "a"), (3, "c"), (4, "d")].into_iter().collect();
+    /// let mut cursor = map.lower_bound_mut(Bound::Included(&2));
+    /// cursor.insert_after(2, "b");
+    /// assert_eq!(cursor.remove_prev(), Some((1, "a")));
+    /// assert_eq!(cursor.next(), Some((&2, &mut "b")));
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(2, "b"), (3, "c"), (4, "d")]);
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn lower_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let current = map.root.as_mut().map(|root| root.borrow_mut().lower_bound(bound));
+        CursorMut { current, dormant_map: Some(dormant_map), _marker: PhantomData }
+    }
+
+    /// Returns a cursor pointing at the gap right after the last element that
+    /// lies below the given bound, i.e., the last element whose key is at
+    /// most (for `Included`) or less than (for `Excluded`) the bound.
+    /// With an `Unbounded` bound, the cursor points after the last element.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let map: BTreeMap<_, _> = vec![(1, "a"), (2, "b"), (3, "c"), (4, "d")].into_iter().collect();
+    /// let cursor = map.upper_bound(Bound::Included(&3));
+    /// assert_eq!(cursor.peek_prev(), Some((&3, &"c")));
+    /// assert_eq!(cursor.peek_next(), Some((&4, &"d")));
+    /// let cursor = map.upper_bound(Bound::Excluded(&3));
+    /// assert_eq!(cursor.peek_prev(), Some((&2, &"b")));
+    /// assert_eq!(cursor.peek_next(), Some((&3, &"c")));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn upper_bound<Q: ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let current = self.root.as_ref().map(|root| root.reborrow().upper_bound(bound));
+        Cursor { current }
+    }
+
+    /// Returns a mutable cursor pointing at the gap right after the last
+    /// element that lies below the given bound. See [`upper_bound`] for how
+    /// the bound is interpreted.
+    ///
+    /// [`upper_bound`]: BTreeMap::upper_bound
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeMap;
+    /// use std::ops::Bound;
+    ///
+    /// let mut map: BTreeMap<_, _> = vec![(1, "a"), (2, "b"), (3, "c")].into_iter().collect();
+    /// let mut cursor = map.upper_bound_mut(Bound::Unbounded);
+    /// cursor.insert_before(4, "d");
+    /// assert_eq!(cursor.prev(), Some((&4, &mut "d")));
+    /// assert_eq!(cursor.remove_prev(), Some((3, "c")));
+    /// assert_eq!(map.into_iter().collect::<Vec<_>>(), [(1, "a"), (2, "b"), (4, "d")]);
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn upper_bound_mut<Q: ?Sized>(&mut self, bound: Bound<&Q>) -> CursorMut<'_, K, V>
+    where
+        K: Borrow<Q>,
+        Q: Ord,
+    {
+        let (map, dormant_map) = DormantMutRef::new(self);
+        let current = map.root.as_mut().map(|root| root.borrow_mut().upper_bound(bound));
+        CursorMut { current, dormant_map: Some(dormant_map), _marker: PhantomData }
+    }
+
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
     ///
     /// # Examples
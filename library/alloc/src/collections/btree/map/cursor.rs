@@ -0,0 +1,306 @@
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+use core::ops::Bound;
+
+use super::super::borrow::DormantMutRef;
+use super::super::node::{marker, Handle, InsertResult::*, NodeRef, Root};
+use super::BTreeMap;
+
+/// A cursor over a `BTreeMap`.
+///
+/// A cursor always points at a gap between two elements of the map, or before
+/// the first or after the last element, and can be moved in both directions.
+/// [`peek_next`] and [`peek_prev`] return the elements right after and right
+/// before the cursor, without moving it.
+///
+/// This `struct` is constructed from the [`lower_bound`] and [`upper_bound`]
+/// methods on [`BTreeMap`].
+///
+/// [`peek_next`]: Cursor::peek_next
+/// [`peek_prev`]: Cursor::peek_prev
+/// [`lower_bound`]: BTreeMap::lower_bound
+/// [`upper_bound`]: BTreeMap::upper_bound
+#[unstable(feature = "btree_cursors", issue = "none")]
+pub struct Cursor<'a, K: 'a, V: 'a> {
+    // `None` only if the map has no root node.
+    pub(super) current:
+        Option<Handle<NodeRef<marker::Immut<'a>, K, V, marker::Leaf>, marker::Edge>>,
+}
+
+#[unstable(feature = "btree_cursors", issue = "none")]
+impl<K, V> Clone for Cursor<'_, K, V> {
+    fn clone(&self) -> Self {
+        Cursor { current: self.current }
+    }
+}
+
+#[unstable(feature = "btree_cursors", issue = "none")]
+impl<K: Debug, V: Debug> Debug for Cursor<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("prev", &self.peek_prev())
+            .field("next", &self.peek_next())
+            .finish()
+    }
+}
+
+/// A cursor over a `BTreeMap` that can insert and remove elements.
+///
+/// Like a [`Cursor`], a `CursorMut` points at a gap between two elements of
+/// the map. New elements are inserted into that gap, and the elements on
+/// either side of it can be removed, without searching the map again.
+///
+/// This `struct` is constructed from the [`lower_bound_mut`] and
+/// [`upper_bound_mut`] methods on [`BTreeMap`].
+///
+/// [`lower_bound_mut`]: BTreeMap::lower_bound_mut
+/// [`upper_bound_mut`]: BTreeMap::upper_bound_mut
+#[unstable(feature = "btree_cursors", issue = "none")]
+pub struct CursorMut<'a, K: 'a, V: 'a> {
+    // `None` only if the map has no root node.
+    pub(super) current: Option<Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, marker::Edge>>,
+    // `None` only while the map is awake, i.e., while an update is going on
+    // and `current` has been taken out.
+    pub(super) dormant_map: Option<DormantMutRef<'a, BTreeMap<K, V>>>,
+
+    // Be invariant in `K` and `V`
+    pub(super) _marker: PhantomData<&'a mut (K, V)>,
+}
+
+#[unstable(feature = "btree_cursors", issue = "none")]
+impl<K: Debug, V: Debug> Debug for CursorMut<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cursor = self.as_cursor();
+        f.debug_struct("CursorMut")
+            .field("prev", &cursor.peek_prev())
+            .field("next", &cursor.peek_next())
+            .finish()
+    }
+}
+
+impl<'a, K, V> Cursor<'a, K, V> {
+    /// Moves the cursor past the next element and returns that element.
+    ///
+    /// If the cursor is already after the last element of the map, this
+    /// returns `None` and leaves the cursor where it is.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn next(&mut self) -> Option<(&'a K, &'a V)> {
+        let kv = self.current?.next_kv().ok()?;
+        self.current = Some(kv.next_leaf_edge());
+        Some(kv.into_kv())
+    }
+
+    /// Moves the cursor before the previous element and returns that element.
+    ///
+    /// If the cursor is already before the first element of the map, this
+    /// returns `None` and leaves the cursor where it is.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn prev(&mut self) -> Option<(&'a K, &'a V)> {
+        let kv = self.current?.next_back_kv().ok()?;
+        self.current = Some(kv.next_back_leaf_edge());
+        Some(kv.into_kv())
+    }
+
+    /// Returns the element right after the cursor, without moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_next(&self) -> Option<(&'a K, &'a V)> {
+        self.current?.next_kv().ok().map(|kv| kv.into_kv())
+    }
+
+    /// Returns the element right before the cursor, without moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_prev(&self) -> Option<(&'a K, &'a V)> {
+        self.current?.next_back_kv().ok().map(|kv| kv.into_kv())
+    }
+}
+
+impl<'a, K, V> CursorMut<'a, K, V> {
+    /// Moves the cursor past the next element and returns that element.
+    ///
+    /// If the cursor is already after the last element of the map, this
+    /// returns `None` and leaves the cursor where it is.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn next(&mut self) -> Option<(&K, &mut V)> {
+        let current = self.current.take()?;
+        match current.next_kv() {
+            Ok(kv) => {
+                self.current = Some(kv.next_leaf_edge());
+                self.peek_prev()
+            }
+            Err(root) => {
+                self.current = Some(root.last_leaf_edge());
+                None
+            }
+        }
+    }
+
+    /// Moves the cursor before the previous element and returns that element.
+    ///
+    /// If the cursor is already before the first element of the map, this
+    /// returns `None` and leaves the cursor where it is.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn prev(&mut self) -> Option<(&K, &mut V)> {
+        let current = self.current.take()?;
+        match current.next_back_kv() {
+            Ok(kv) => {
+                self.current = Some(kv.next_back_leaf_edge());
+                self.peek_next()
+            }
+            Err(root) => {
+                self.current = Some(root.first_leaf_edge());
+                None
+            }
+        }
+    }
+
+    /// Returns the element right after the cursor, without moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_next(&mut self) -> Option<(&K, &mut V)> {
+        let current = self.current.as_mut()?;
+        let kv = unsafe { current.reborrow_mut() }.next_kv().ok()?;
+        let (k, v) = kv.into_kv_mut();
+        Some((&*k, v))
+    }
+
+    /// Returns the element right before the cursor, without moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_prev(&mut self) -> Option<(&K, &mut V)> {
+        let current = self.current.as_mut()?;
+        let kv = unsafe { current.reborrow_mut() }.next_back_kv().ok()?;
+        let (k, v) = kv.into_kv_mut();
+        Some((&*k, v))
+    }
+
+    /// Returns a read-only cursor at the current position.
+    ///
+    /// The returned cursor cannot outlive the `CursorMut`, which remains
+    /// borrowed until the returned cursor is dropped.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn as_cursor(&self) -> Cursor<'_, K, V> {
+        Cursor { current: self.current.as_ref().map(|current| current.reborrow()) }
+    }
+}
+
+impl<'a, K: Ord, V> CursorMut<'a, K, V> {
+    /// Removes the element right after the cursor and returns it.
+    /// The cursor stays in place, i.e., in between the elements that were
+    /// adjacent to the removed element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn remove_next(&mut self) -> Option<(K, V)> {
+        let current = self.current.take()?;
+        match current.next_kv() {
+            Ok(kv) => Some(self.remove_kv(kv)),
+            Err(root) => {
+                self.current = Some(root.last_leaf_edge());
+                None
+            }
+        }
+    }
+
+    /// Removes the element right before the cursor and returns it.
+    /// The cursor stays in place, i.e., in between the elements that were
+    /// adjacent to the removed element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn remove_prev(&mut self) -> Option<(K, V)> {
+        let current = self.current.take()?;
+        match current.next_back_kv() {
+            Ok(kv) => Some(self.remove_kv(kv)),
+            Err(root) => {
+                self.current = Some(root.first_leaf_edge());
+                None
+            }
+        }
+    }
+
+    // Body of `remove_next` and `remove_prev`, moving the cursor to the gap
+    // left behind by the removed element.
+    fn remove_kv(
+        &mut self,
+        kv: Handle<NodeRef<marker::Mut<'a>, K, V, marker::LeafOrInternal>, marker::KV>,
+    ) -> (K, V) {
+        let mut emptied_internal_root = false;
+        let (old_kv, _) = kv.remove_kv_tracking(|| emptied_internal_root = true);
+        // SAFETY: we have consumed the cursor's handle and the position returned.
+        let map = unsafe { self.dormant_map.take().unwrap().awaken() };
+        map.length -= 1;
+        if emptied_internal_root {
+            let root = map.root.as_mut().unwrap();
+            root.pop_internal_level();
+        }
+        self.sleep(map, &old_kv.0, true);
+        old_kv
+    }
+
+    /// Inserts a new element into the map in the gap that the cursor is
+    /// pointing to, leaving the cursor before the new element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not ordered strictly between the elements before
+    /// and after the cursor, i.e., if inserting it there would leave the map
+    /// out of order or with duplicate keys.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn insert_after(&mut self, key: K, value: V) {
+        self.insert_kv(key, value, true);
+    }
+
+    /// Inserts a new element into the map in the gap that the cursor is
+    /// pointing to, leaving the cursor after the new element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `key` is not ordered strictly between the elements before
+    /// and after the cursor, i.e., if inserting it there would leave the map
+    /// out of order or with duplicate keys.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn insert_before(&mut self, key: K, value: V) {
+        self.insert_kv(key, value, false);
+    }
+
+    // Body of `insert_after` and `insert_before`, leaving the cursor right
+    // before the inserted element if `before_key` is true, and right after it
+    // otherwise.
+    fn insert_kv(&mut self, key: K, value: V, before_key: bool) {
+        if let Some((prev, _)) = self.peek_prev() {
+            assert!(prev < &key, "key must be ordered above the element before the cursor");
+        }
+        if let Some((next, _)) = self.peek_next() {
+            assert!(&key < next, "key must be ordered below the element after the cursor");
+        }
+
+        let edge = match self.current.take() {
+            Some(edge) => edge,
+            None => {
+                // SAFETY: without a root node, there is no handle into the map.
+                let map = unsafe { self.dormant_map.take().unwrap().awaken() };
+                let (map, dormant_map) = DormantMutRef::new(map);
+                self.dormant_map = Some(dormant_map);
+                map.root.get_or_insert_with(Root::new).borrow_mut().first_leaf_edge()
+            }
+        };
+        let (result, key_ptr, _) = edge.insert_recursing(key, value);
+        // SAFETY: we have consumed the cursor's handle and the handle returned.
+        let map = unsafe { self.dormant_map.take().unwrap().awaken() };
+        if let Split(ins) = result {
+            drop(ins.left);
+            let root = map.root.as_mut().unwrap();
+            root.push_internal_level().push(ins.kv.0, ins.kv.1, ins.right);
+        }
+        map.length += 1;
+        // SAFETY: growing the tree above the leaf that the key was inserted
+        // into leaves the key in place.
+        self.sleep(map, unsafe { &*key_ptr }, before_key);
+    }
+
+    // Puts the map back to sleep once it has been updated, and re-derives the
+    // cursor's handle from the root of the map, placing the cursor right
+    // before `key` if `before_key` is true, and right after it otherwise.
+    fn sleep(&mut self, map: &'a mut BTreeMap<K, V>, key: &K, before_key: bool) {
+        let (map, dormant_map) = DormantMutRef::new(map);
+        self.dormant_map = Some(dormant_map);
+        let root = map.root.as_mut().unwrap().borrow_mut();
+        let bound = Bound::Included(key);
+        self.current =
+            Some(if before_key { root.lower_bound(bound) } else { root.upper_bound(bound) });
+    }
+}
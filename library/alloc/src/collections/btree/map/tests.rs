@@ -869,6 +869,216 @@ fn test_range_mut() {
     map.check();
 }
 
+#[test]
+fn test_cursor_bounds() {
+    for &size in &[0, 1, NODE_CAPACITY, MIN_INSERTS_HEIGHT_1, MIN_INSERTS_HEIGHT_2] {
+        // Only even keys, so that we also seek to keys that are not present.
+        let size = size as i32;
+        let map: BTreeMap<_, _> = (0..size).map(|i| (i * 2, i)).collect();
+        for key in -1..=size * 2 + 1 {
+            let below = map.range(..key).next_back().map(|(&k, &v)| (k, v));
+            let at_or_below = map.range(..=key).next_back().map(|(&k, &v)| (k, v));
+            let above = map.range((Excluded(key), Unbounded)).next().map(|(&k, &v)| (k, v));
+            let at_or_above = map.range(key..).next().map(|(&k, &v)| (k, v));
+
+            let peek = |cursor: Cursor<'_, i32, i32>| {
+                (
+                    cursor.peek_prev().map(|(&k, &v)| (k, v)),
+                    cursor.peek_next().map(|(&k, &v)| (k, v)),
+                )
+            };
+            assert_eq!(peek(map.lower_bound(Included(&key))), (below, at_or_above));
+            assert_eq!(peek(map.lower_bound(Excluded(&key))), (at_or_below, above));
+            assert_eq!(peek(map.upper_bound(Included(&key))), (at_or_below, above));
+            assert_eq!(peek(map.upper_bound(Excluded(&key))), (below, at_or_above));
+        }
+        let first = map.iter().next().map(|(&k, &v)| (k, v));
+        let last = map.iter().next_back().map(|(&k, &v)| (k, v));
+        assert_eq!(map.lower_bound(Unbounded).peek_prev(), None);
+        assert_eq!(map.lower_bound(Unbounded).peek_next().map(|(&k, &v)| (k, v)), first);
+        assert_eq!(map.upper_bound(Unbounded).peek_prev().map(|(&k, &v)| (k, v)), last);
+        assert_eq!(map.upper_bound(Unbounded).peek_next(), None);
+    }
+}
+
+#[test]
+fn test_cursor_walk() {
+    let size = MIN_INSERTS_HEIGHT_2 as i32;
+    let map: BTreeMap<_, _> = (0..size).map(|i| (i, i * 10)).collect();
+
+    let mut cursor = map.lower_bound(Unbounded);
+    assert_eq!(cursor.prev(), None);
+    for i in 0..size {
+        assert_eq!(cursor.next(), Some((&i, &(i * 10))));
+    }
+    assert_eq!(cursor.next(), None);
+    assert_eq!(cursor.next(), None);
+    for i in (0..size).rev() {
+        assert_eq!(cursor.prev(), Some((&i, &(i * 10))));
+    }
+    assert_eq!(cursor.prev(), None);
+    assert_eq!(cursor.peek_next(), Some((&0, &0)));
+
+    let mut cursor = map.upper_bound(Included(&40));
+    let mut copy = cursor.clone();
+    assert_eq!(cursor.next(), Some((&41, &410)));
+    assert_eq!(copy.prev(), Some((&40, &400)));
+}
+
+#[test]
+fn test_cursor_mut_walk() {
+    let size = MIN_INSERTS_HEIGHT_2 as i32;
+    let mut map: BTreeMap<_, _> = (0..size).map(|i| (i, i)).collect();
+
+    let mut cursor = map.lower_bound_mut(Unbounded);
+    assert_eq!(cursor.prev(), None);
+    while let Some((_, v)) = cursor.next() {
+        *v *= 10;
+    }
+    assert_eq!(cursor.peek_prev(), Some((&(size - 1), &mut ((size - 1) * 10))));
+    assert_eq!(cursor.as_cursor().peek_next(), None);
+    while let Some((_, v)) = cursor.prev() {
+        *v += 1;
+    }
+    assert_eq!(cursor.peek_next(), Some((&0, &mut 1)));
+    map.check();
+    assert!(map.iter().all(|(&k, &v)| v == k * 10 + 1));
+}
+
+#[test]
+fn test_cursor_mut_insert() {
+    let size = MIN_INSERTS_HEIGHT_2 * 2;
+
+    // Build a map in ascending order, from an empty map without a root.
+    let mut map = BTreeMap::new();
+    let mut cursor = map.lower_bound_mut(Unbounded);
+    for i in 0..size {
+        cursor.insert_before(i, i);
+        assert_eq!(cursor.peek_prev(), Some((&i, &mut i.clone())));
+        assert_eq!(cursor.peek_next(), None);
+    }
+    map.check();
+    assert_eq!(map.len(), size);
+    assert_eq!(map.height(), Some(2));
+    assert!(map.iter().map(|(&k, _)| k).eq(0..size));
+
+    // Build a map in descending order.
+    let mut map = BTreeMap::new();
+    let mut cursor = map.upper_bound_mut(Unbounded);
+    for i in (0..size).rev() {
+        cursor.insert_after(i, i);
+        assert_eq!(cursor.peek_prev(), None);
+        assert_eq!(cursor.peek_next(), Some((&i, &mut i.clone())));
+    }
+    map.check();
+    assert_eq!(map.len(), size);
+    assert!(map.iter().map(|(&k, _)| k).eq(0..size));
+
+    // Fill in the gaps of a map with only the even keys, causing splits all over.
+    let mut map: BTreeMap<_, _> = (0..size).map(|i| (i * 2, ())).collect();
+    let mut cursor = map.lower_bound_mut(Unbounded);
+    while let Some((&k, _)) = cursor.next() {
+        cursor.insert_before(k + 1, ());
+        assert_eq!(cursor.peek_prev().map(|(&k, _)| k), Some(k + 1));
+    }
+    map.check();
+    assert!(map.keys().cloned().eq(0..size * 2));
+}
+
+#[test]
+#[should_panic(expected = "key must be ordered above the element before the cursor")]
+fn test_cursor_mut_insert_below_prev() {
+    let mut map: BTreeMap<_, _> = (0..10).map(|i| (i, i)).collect();
+    map.lower_bound_mut(Included(&5)).insert_after(4, 4);
+}
+
+#[test]
+#[should_panic(expected = "key must be ordered below the element after the cursor")]
+fn test_cursor_mut_insert_above_next() {
+    let mut map: BTreeMap<_, _> = (0..10).map(|i| (i, i)).collect();
+    map.lower_bound_mut(Included(&5)).insert_before(5, 5);
+}
+
+#[test]
+fn test_cursor_mut_remove() {
+    let size = MIN_INSERTS_HEIGHT_2 * 2;
+
+    // Remove everything from the front.
+    let mut map: BTreeMap<_, _> = (0..size).map(|i| (i, i)).collect();
+    let mut cursor = map.lower_bound_mut(Unbounded);
+    for i in 0..size {
+        assert_eq!(cursor.remove_next(), Some((i, i)));
+        assert_eq!(cursor.peek_prev(), None);
+    }
+    assert_eq!(cursor.remove_next(), None);
+    assert_eq!(cursor.remove_prev(), None);
+    map.check();
+    assert_eq!(map.len(), 0);
+
+    // Remove everything from the back.
+    let mut map: BTreeMap<_, _> = (0..size).map(|i| (i, i)).collect();
+    let mut cursor = map.upper_bound_mut(Unbounded);
+    for i in (0..size).rev() {
+        assert_eq!(cursor.remove_prev(), Some((i, i)));
+        assert_eq!(cursor.peek_next(), None);
+    }
+    map.check();
+    assert_eq!(map.len(), 0);
+
+    // Remove every other key, starting in the middle and going both ways,
+    // so that we hit both leaf and internal key-value pairs.
+    let mut map: BTreeMap<_, _> = (0..size).map(|i| (i, i)).collect();
+    let middle = size / 2;
+    let mut cursor = map.lower_bound_mut(Included(&middle));
+    while cursor.remove_next().is_some() {
+        cursor.next();
+    }
+    let mut cursor = map.lower_bound_mut(Included(&middle));
+    while cursor.prev().is_some() {
+        cursor.remove_prev();
+    }
+    map.check();
+    assert!(map.keys().cloned().eq((0..size).filter(|i| i % 2 != middle % 2)));
+}
+
+#[test]
+fn test_cursor_mut_mixed() {
+    let mut rng = DeterministicRng::new();
+    let mut map = BTreeMap::new();
+    let mut cursor = map.lower_bound_mut(Unbounded);
+    for _ in 0..if cfg!(miri) { 200 } else { 10000 } {
+        let r = rng.next();
+        match r % 6 {
+            0 => {
+                cursor.next();
+            }
+            1 => {
+                cursor.prev();
+            }
+            2 => {
+                cursor.remove_next();
+            }
+            3 => {
+                cursor.remove_prev();
+            }
+            _ => {
+                // Insert a key in between the neighbours, if there is room.
+                let lo = cursor.peek_prev().map_or(0, |(&k, _)| k + 1);
+                let hi = cursor.peek_next().map_or(u32::MAX, |(&k, _)| k);
+                if lo < hi {
+                    let key = lo + r % (hi - lo);
+                    if r % 2 == 0 {
+                        cursor.insert_before(key, r);
+                    } else {
+                        cursor.insert_after(key, r);
+                    }
+                }
+            }
+        }
+    }
+    map.check();
+}
+
 #[test]
 fn test_retain() {
     let mut map: BTreeMap<i32, i32> = (0..100).map(|x| (x, x * 10)).collect();
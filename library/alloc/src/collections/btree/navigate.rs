@@ -1,6 +1,6 @@
 use core::borrow::Borrow;
 use core::cmp::Ordering;
-use core::ops::Bound::{Excluded, Included, Unbounded};
+use core::ops::Bound::{self, Excluded, Included, Unbounded};
 use core::ops::RangeBounds;
 use core::ptr;
 
@@ -416,6 +416,60 @@ pub unsafe fn next_back_unchecked(&mut self) -> (K, V) {
 }
 
 impl<BorrowType, K, V> NodeRef<BorrowType, K, V, marker::LeafOrInternal> {
+    /// Returns the leaf edge in or underneath this node that precedes the first
+    /// KV lying above the lower bound `bound`, i.e., the leaf edge that a cursor
+    /// seeking to that bound is placed on.
+    ///
+    /// The result is meaningful only if the tree is ordered by key, like the tree
+    /// in a `BTreeMap` is.
+    pub fn lower_bound<Q: ?Sized>(
+        self,
+        bound: Bound<&Q>,
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>
+    where
+        Q: Ord,
+        K: Borrow<Q>,
+    {
+        match bound {
+            Included(key) => match search::search_tree(self, key) {
+                SearchResult::Found(kv) => kv.next_back_leaf_edge(),
+                SearchResult::GoDown(edge) => edge,
+            },
+            Excluded(key) => match search::search_tree(self, key) {
+                SearchResult::Found(kv) => kv.next_leaf_edge(),
+                SearchResult::GoDown(edge) => edge,
+            },
+            Unbounded => self.first_leaf_edge(),
+        }
+    }
+
+    /// Returns the leaf edge in or underneath this node that follows the last
+    /// KV lying below the upper bound `bound`, i.e., the leaf edge that a cursor
+    /// seeking to that bound is placed on.
+    ///
+    /// The result is meaningful only if the tree is ordered by key, like the tree
+    /// in a `BTreeMap` is.
+    pub fn upper_bound<Q: ?Sized>(
+        self,
+        bound: Bound<&Q>,
+    ) -> Handle<NodeRef<BorrowType, K, V, marker::Leaf>, marker::Edge>
+    where
+        Q: Ord,
+        K: Borrow<Q>,
+    {
+        match bound {
+            Included(key) => match search::search_tree(self, key) {
+                SearchResult::Found(kv) => kv.next_leaf_edge(),
+                SearchResult::GoDown(edge) => edge,
+            },
+            Excluded(key) => match search::search_tree(self, key) {
+                SearchResult::Found(kv) => kv.next_back_leaf_edge(),
+                SearchResult::GoDown(edge) => edge,
+            },
+            Unbounded => self.last_leaf_edge(),
+        }
+    }
+
     /// Returns the leftmost leaf edge in or underneath a node - in other words, the edge
     /// you need first when navigating forward (or last when navigating backward).
     #[inline]
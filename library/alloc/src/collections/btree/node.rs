@@ -913,8 +913,8 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     /// this edge. This method assumes that there is enough space in the node for the new
     /// pair to fit.
     ///
-    /// The returned pointer points to the inserted value.
-    fn insert_fit(&mut self, key: K, val: V) -> *mut V {
+    /// The returned pointers point to the inserted key and value.
+    fn insert_fit(&mut self, key: K, val: V) -> (*const K, *mut V) {
         debug_assert!(self.node.len() < CAPACITY);
         let new_len = self.node.len() + 1;
 
@@ -923,7 +923,10 @@ fn insert_fit(&mut self, key: K, val: V) -> *mut V {
             slice_insert(self.node.val_area_mut(..new_len), self.idx, val);
             *self.node.len_mut() = new_len as u16;
 
-            self.node.val_area_mut(self.idx).assume_init_mut()
+            let leaf = self.node.as_leaf_mut();
+            let key_ptr = leaf.keys.get_unchecked(self.idx).as_ptr();
+            let val_ptr: *mut V = leaf.vals.get_unchecked_mut(self.idx).assume_init_mut();
+            (key_ptr, val_ptr)
         }
     }
 }
@@ -932,12 +935,16 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     /// Inserts a new key-value pair between the key-value pairs to the right and left of
     /// this edge. This method splits the node if there isn't enough room.
     ///
-    /// The returned pointer points to the inserted value.
-    fn insert(mut self, key: K, val: V) -> (InsertResult<'a, K, V, marker::Leaf>, *mut V) {
+    /// The returned pointers point to the inserted key and value.
+    fn insert(
+        mut self,
+        key: K,
+        val: V,
+    ) -> (InsertResult<'a, K, V, marker::Leaf>, *const K, *mut V) {
         if self.node.len() < CAPACITY {
-            let val_ptr = self.insert_fit(key, val);
+            let (key_ptr, val_ptr) = self.insert_fit(key, val);
             let kv = unsafe { Handle::new_kv(self.node, self.idx) };
-            (InsertResult::Fit(kv), val_ptr)
+            (InsertResult::Fit(kv), key_ptr, val_ptr)
         } else {
             let (middle_kv_idx, insertion) = splitpoint(self.idx);
             let middle = unsafe { Handle::new_kv(self.node, middle_kv_idx) };
@@ -950,8 +957,8 @@ fn insert(mut self, key: K, val: V) -> (InsertResult<'a, K, V, marker::Leaf>, *m
                     Handle::new_edge(result.right.borrow_mut(), insert_idx)
                 },
             };
-            let val_ptr = insertion_edge.insert_fit(key, val);
-            (InsertResult::Split(result), val_ptr)
+            let (key_ptr, val_ptr) = insertion_edge.insert_fit(key, val);
+            (InsertResult::Split(result), key_ptr, val_ptr)
         }
     }
 }
@@ -1027,29 +1034,32 @@ impl<'a, K: 'a, V: 'a> Handle<NodeRef<marker::Mut<'a>, K, V, marker::Leaf>, mark
     ///
     /// If the returned result is a `Fit`, its handle's node can be this edge's node or an ancestor.
     /// If the returned result is a `Split`, the `left` field will be the root node.
-    /// The returned pointer points to the inserted value.
+    /// The returned pointers point to the inserted key and value.
     pub fn insert_recursing(
         self,
         key: K,
         value: V,
-    ) -> (InsertResult<'a, K, V, marker::LeafOrInternal>, *mut V) {
-        let (mut split, val_ptr) = match self.insert(key, value) {
-            (InsertResult::Fit(handle), ptr) => {
-                return (InsertResult::Fit(handle.forget_node_type()), ptr);
+    ) -> (InsertResult<'a, K, V, marker::LeafOrInternal>, *const K, *mut V) {
+        let (mut split, key_ptr, val_ptr) = match self.insert(key, value) {
+            (InsertResult::Fit(handle), key_ptr, val_ptr) => {
+                return (InsertResult::Fit(handle.forget_node_type()), key_ptr, val_ptr);
+            }
+            (InsertResult::Split(split), key_ptr, val_ptr) => {
+                (split.forget_node_type(), key_ptr, val_ptr)
             }
-            (InsertResult::Split(split), val_ptr) => (split.forget_node_type(), val_ptr),
         };
 
         loop {
             split = match split.left.ascend() {
                 Ok(parent) => match parent.insert(split.kv.0, split.kv.1, split.right) {
                     InsertResult::Fit(handle) => {
-                        return (InsertResult::Fit(handle.forget_node_type()), val_ptr);
+                        return (InsertResult::Fit(handle.forget_node_type()), key_ptr, val_ptr);
                     }
                     InsertResult::Split(split) => split.forget_node_type(),
                 },
                 Err(root) => {
-                    return (InsertResult::Split(SplitResult { left: root, ..split }), val_ptr);
+                    let split = SplitResult { left: root, ..split };
+                    return (InsertResult::Split(split), key_ptr, val_ptr);
                 }
             };
         }
@@ -1092,6 +1102,13 @@ pub fn into_val_mut(self) -> &'a mut V {
         let leaf = self.node.into_leaf_mut();
         unsafe { leaf.vals.get_unchecked_mut(self.idx).assume_init_mut() }
     }
+
+    pub fn into_kv_mut(self) -> (&'a mut K, &'a mut V) {
+        let leaf = self.node.into_leaf_mut();
+        let k = unsafe { leaf.keys.get_unchecked_mut(self.idx).assume_init_mut() };
+        let v = unsafe { leaf.vals.get_unchecked_mut(self.idx).assume_init_mut() };
+        (k, v)
+    }
 }
 
 impl<'a, K, V, NodeType> Handle<NodeRef<marker::ValMut<'a>, K, V, NodeType>, marker::KV> {
@@ -6,7 +6,7 @@
 use core::cmp::{max, min};
 use core::fmt::{self, Debug};
 use core::iter::{FromIterator, FusedIterator, Peekable};
-use core::ops::{BitAnd, BitOr, BitXor, RangeBounds, Sub};
+use core::ops::{BitAnd, BitOr, BitXor, Bound, RangeBounds, Sub};
 
 use super::map::{BTreeMap, Keys};
 use super::merge_iter::MergeIterInner;
@@ -115,6 +115,39 @@ pub struct Range<'a, T: 'a> {
     iter: super::map::Range<'a, T, ()>,
 }
 
+/// A cursor over a `BTreeSet`.
+///
+/// A cursor points at a gap between two elements of the set, or before the
+/// first or after the last element. See [`btree_map::Cursor`] for more.
+///
+/// This `struct` is created by the [`lower_bound`] and [`upper_bound`]
+/// methods on [`BTreeSet`].
+///
+/// [`btree_map::Cursor`]: super::map::Cursor
+/// [`lower_bound`]: BTreeSet::lower_bound
+/// [`upper_bound`]: BTreeSet::upper_bound
+#[derive(Debug)]
+#[unstable(feature = "btree_cursors", issue = "none")]
+pub struct Cursor<'a, T: 'a> {
+    inner: super::map::Cursor<'a, T, ()>,
+}
+
+/// A cursor over a `BTreeSet` that can insert and remove elements.
+///
+/// See [`btree_map::CursorMut`] for more.
+///
+/// This `struct` is created by the [`lower_bound_mut`] and
+/// [`upper_bound_mut`] methods on [`BTreeSet`].
+///
+/// [`btree_map::CursorMut`]: super::map::CursorMut
+/// [`lower_bound_mut`]: BTreeSet::lower_bound_mut
+/// [`upper_bound_mut`]: BTreeSet::upper_bound_mut
+#[derive(Debug)]
+#[unstable(feature = "btree_cursors", issue = "none")]
+pub struct CursorMut<'a, T: 'a> {
+    inner: super::map::CursorMut<'a, T, ()>,
+}
+
 /// A lazy iterator producing elements in the difference of `BTreeSet`s.
 ///
 /// This `struct` is created by the [`difference`] method on [`BTreeSet`].
@@ -270,6 +303,110 @@ pub fn range<K: ?Sized, R>(&self, range: R) -> Range<'_, T>
         Range { iter: self.map.range(range) }
     }
 
+    /// Returns a cursor pointing at the gap right before the first element
+    /// that lies above the given bound. See [`BTreeMap::lower_bound`] for how
+    /// the bound is interpreted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeSet;
+    /// use std::ops::Bound;
+    ///
+    /// let set: BTreeSet<_> = [1, 2, 3, 4].iter().cloned().collect();
+    /// let cursor = set.lower_bound(Bound::Excluded(&2));
+    /// assert_eq!(cursor.peek_prev(), Some(&2));
+    /// assert_eq!(cursor.peek_next(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn lower_bound<K: ?Sized>(&self, bound: Bound<&K>) -> Cursor<'_, T>
+    where
+        K: Ord,
+        T: Borrow<K>,
+    {
+        Cursor { inner: self.map.lower_bound(bound) }
+    }
+
+    /// Returns a mutable cursor pointing at the gap right before the first
+    /// element that lies above the given bound. See [`BTreeMap::lower_bound`]
+    /// for how the bound is interpreted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeSet;
+    /// use std::ops::Bound;
+    ///
+    /// let mut set: BTreeSet<_> = [1, 3, 4].iter().cloned().collect();
+    /// let mut cursor = set.lower_bound_mut(Bound::Included(&2));
+    /// cursor.insert_after(2);
+    /// assert_eq!(cursor.remove_next(), Some(2));
+    /// assert_eq!(cursor.remove_next(), Some(3));
+    /// assert_eq!(set.into_iter().collect::<Vec<_>>(), [1, 4]);
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn lower_bound_mut<K: ?Sized>(&mut self, bound: Bound<&K>) -> CursorMut<'_, T>
+    where
+        K: Ord,
+        T: Borrow<K>,
+    {
+        CursorMut { inner: self.map.lower_bound_mut(bound) }
+    }
+
+    /// Returns a cursor pointing at the gap right after the last element
+    /// that lies below the given bound. See [`BTreeMap::upper_bound`] for how
+    /// the bound is interpreted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeSet;
+    /// use std::ops::Bound;
+    ///
+    /// let set: BTreeSet<_> = [1, 2, 3, 4].iter().cloned().collect();
+    /// let cursor = set.upper_bound(Bound::Excluded(&3));
+    /// assert_eq!(cursor.peek_prev(), Some(&2));
+    /// assert_eq!(cursor.peek_next(), Some(&3));
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn upper_bound<K: ?Sized>(&self, bound: Bound<&K>) -> Cursor<'_, T>
+    where
+        K: Ord,
+        T: Borrow<K>,
+    {
+        Cursor { inner: self.map.upper_bound(bound) }
+    }
+
+    /// Returns a mutable cursor pointing at the gap right after the last
+    /// element that lies below the given bound. See [`BTreeMap::upper_bound`]
+    /// for how the bound is interpreted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(btree_cursors)]
+    /// use std::collections::BTreeSet;
+    /// use std::ops::Bound;
+    ///
+    /// let mut set: BTreeSet<_> = [1, 2, 3].iter().cloned().collect();
+    /// let mut cursor = set.upper_bound_mut(Bound::Unbounded);
+    /// cursor.insert_before(4);
+    /// assert_eq!(cursor.prev(), Some(&4));
+    /// assert_eq!(cursor.remove_prev(), Some(3));
+    /// assert_eq!(set.into_iter().collect::<Vec<_>>(), [1, 2, 4]);
+    /// ```
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn upper_bound_mut<K: ?Sized>(&mut self, bound: Bound<&K>) -> CursorMut<'_, T>
+    where
+        K: Ord,
+        T: Borrow<K>,
+    {
+        CursorMut { inner: self.map.upper_bound_mut(bound) }
+    }
+
     /// Visits the values representing the difference,
     /// i.e., the values that are in `self` but not in `other`,
     /// in ascending order.
@@ -1341,6 +1478,109 @@ fn next_back(&mut self) -> Option<&'a T> {
 #[stable(feature = "fused", since = "1.26.0")]
 impl<T> FusedIterator for Range<'_, T> {}
 
+#[unstable(feature = "btree_cursors", issue = "none")]
+impl<T> Clone for Cursor<'_, T> {
+    fn clone(&self) -> Self {
+        Cursor { inner: self.inner.clone() }
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    /// Moves the cursor past the next element and returns that element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn next(&mut self) -> Option<&'a T> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    /// Moves the cursor before the previous element and returns that element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn prev(&mut self) -> Option<&'a T> {
+        self.inner.prev().map(|(k, _)| k)
+    }
+
+    /// Returns the element right after the cursor, without moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_next(&self) -> Option<&'a T> {
+        self.inner.peek_next().map(|(k, _)| k)
+    }
+
+    /// Returns the element right before the cursor, without moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        self.inner.peek_prev().map(|(k, _)| k)
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Moves the cursor past the next element and returns that element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn next(&mut self) -> Option<&T> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    /// Moves the cursor before the previous element and returns that element.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn prev(&mut self) -> Option<&T> {
+        self.inner.prev().map(|(k, _)| k)
+    }
+
+    /// Returns the element right after the cursor, without moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_next(&mut self) -> Option<&T> {
+        self.inner.peek_next().map(|(k, _)| k)
+    }
+
+    /// Returns the element right before the cursor, without moving the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn peek_prev(&mut self) -> Option<&T> {
+        self.inner.peek_prev().map(|(k, _)| k)
+    }
+
+    /// Returns a read-only cursor at the current position.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor { inner: self.inner.as_cursor() }
+    }
+}
+
+impl<'a, T: Ord> CursorMut<'a, T> {
+    /// Removes the element right after the cursor and returns it.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn remove_next(&mut self) -> Option<T> {
+        self.inner.remove_next().map(|(k, _)| k)
+    }
+
+    /// Removes the element right before the cursor and returns it.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn remove_prev(&mut self) -> Option<T> {
+        self.inner.remove_prev().map(|(k, _)| k)
+    }
+
+    /// Inserts a new element into the set in the gap that the cursor is
+    /// pointing to, leaving the cursor before the new element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not ordered strictly between the elements before
+    /// and after the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn insert_after(&mut self, value: T) {
+        self.inner.insert_after(value, ())
+    }
+
+    /// Inserts a new element into the set in the gap that the cursor is
+    /// pointing to, leaving the cursor after the new element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `value` is not ordered strictly between the elements before
+    /// and after the cursor.
+    #[unstable(feature = "btree_cursors", issue = "none")]
+    pub fn insert_before(&mut self, value: T) {
+        self.inner.insert_before(value, ())
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<T> Clone for Difference<'_, T> {
     fn clone(&self) -> Self {
@@ -3,6 +3,7 @@
 use crate::vec::Vec;
 use std::cmp::Ordering;
 use std::iter::FromIterator;
+use std::ops::Bound;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::atomic::{AtomicU32, Ordering::SeqCst};
 
@@ -325,6 +326,28 @@ fn is_subset(a: &[i32], b: &[i32]) -> bool {
     assert_eq!(is_subset(&[99, 100], &large), false);
 }
 
+#[test]
+fn test_cursor() {
+    let mut set: BTreeSet<_> = (0..100).filter(|i| i % 10 != 5).collect();
+
+    let cursor = set.lower_bound(Bound::Included(&15));
+    assert_eq!((cursor.peek_prev(), cursor.peek_next()), (Some(&14), Some(&16)));
+    let mut cursor = set.upper_bound(Bound::Excluded(&20));
+    assert_eq!(cursor.prev(), Some(&19));
+    assert_eq!(cursor.clone().next(), Some(&19));
+
+    let mut cursor = set.lower_bound_mut(Bound::Unbounded);
+    while let Some(&i) = cursor.next() {
+        if i % 10 == 4 {
+            cursor.insert_before(i + 1);
+        } else if i % 10 == 9 {
+            assert_eq!(cursor.remove_prev(), Some(i));
+        }
+    }
+    assert_eq!(cursor.as_cursor().peek_prev(), Some(&98));
+    assert!(set.iter().cloned().eq((0..100).filter(|i| i % 10 != 9)));
+}
+
 #[test]
 fn test_retain() {
     let xs = [1, 2, 3, 4, 5, 6];
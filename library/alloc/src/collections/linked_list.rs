@@ -236,6 +236,43 @@ unsafe fn unlink_node(&mut self, mut node: NonNull<Node<T>>) {
         self.len -= 1;
     }
 
+    /// Unlinks a series of nodes, from `start` to `end` inclusive, from the current list,
+    /// and returns them as a new list.
+    ///
+    /// Warning: this will not check that the provided nodes belong to the current list,
+    /// nor that `len` is the number of nodes from `start` to `end`.
+    ///
+    /// This method takes care not to create mutable references to `element`, to
+    /// maintain validity of aliasing pointers.
+    #[inline]
+    unsafe fn unlink_nodes(
+        &mut self,
+        start: NonNull<Node<T>>,
+        end: NonNull<Node<T>>,
+        len: usize,
+    ) -> Self {
+        unsafe {
+            let prev = (*start.as_ptr()).prev.take();
+            let next = (*end.as_ptr()).next.take();
+
+            // Not creating new mutable (unique!) references overlapping `element`.
+            match prev {
+                Some(prev) => (*prev.as_ptr()).next = next,
+                // the first unlinked node is the head node
+                None => self.head = next,
+            };
+
+            match next {
+                Some(next) => (*next.as_ptr()).prev = prev,
+                // the last unlinked node is the tail node
+                None => self.tail = prev,
+            };
+        }
+
+        self.len -= len;
+        LinkedList { head: Some(start), tail: Some(end), len, marker: PhantomData }
+    }
+
     /// Splices a series of nodes between two existing nodes.
     ///
     /// Warning: this will not check that the provided node belongs to the two existing lists.
@@ -1442,6 +1479,67 @@ pub fn remove_current_as_list(&mut self) -> Option<LinkedList<T>> {
         }
     }
 
+    /// Removes the elements from the current one up to, but not including, the
+    /// one at the position `end`, without deallocating their list nodes.
+    ///
+    /// `end` is the position of a cursor at the end of the range, as returned
+    /// by [`index`]. `None` stands for the "ghost" non-element, in which case
+    /// all the elements from the current one to the back of the list are
+    /// removed.
+    ///
+    /// The removed nodes are returned as a new `LinkedList`, in their original
+    /// order. The cursor is moved to point to the element that followed them.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no
+    /// elements are removed and an empty list is returned.
+    ///
+    /// [`index`]: CursorMut::index
+    ///
+    /// # Panics
+    ///
+    /// Panics if `end` is before the current position of the cursor, or past
+    /// the end of the list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(linked_list_cursors)]
+    /// use std::collections::LinkedList;
+    ///
+    /// let mut list: LinkedList<u32> = (1..=6).collect();
+    /// let end = {
+    ///     let mut cursor = list.cursor_back();
+    ///     cursor.move_prev();
+    ///     cursor.index()
+    /// };
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.move_next();
+    /// let removed = cursor.remove_range(end);
+    /// assert_eq!(cursor.current(), Some(&mut 5));
+    /// assert_eq!(removed.into_iter().collect::<Vec<_>>(), [2, 3, 4]);
+    /// assert_eq!(list.into_iter().collect::<Vec<_>>(), [1, 5, 6]);
+    /// ```
+    #[unstable(feature = "linked_list_cursors", issue = "58533")]
+    pub fn remove_range(&mut self, end: Option<usize>) -> LinkedList<T> {
+        let end = end.unwrap_or(self.list.len);
+        assert!(self.index <= end && end <= self.list.len, "range end out of bounds");
+        let start = match self.current {
+            Some(start) if end > self.index => start,
+            _ => return LinkedList::new(),
+        };
+        let len = end - self.index;
+        unsafe {
+            let mut last = start;
+            for _ in 1..len {
+                last = last.as_ref().next.unwrap();
+            }
+            // The index of the cursor doesn't change, even if it ends up
+            // pointing at the "ghost" non-element.
+            self.current = last.as_ref().next;
+            self.list.unlink_nodes(start, last, len)
+        }
+    }
+
     /// Inserts the elements from the given `LinkedList` after the current one.
     ///
     /// If the cursor is pointing at the "ghost" non-element then the new elements are
@@ -1485,6 +1583,40 @@ pub fn splice_before(&mut self, list: LinkedList<T>) {
         }
     }
 
+    /// Inserts the elements of the given iterator after the current one, in
+    /// the order in which the iterator yields them.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the start of the `LinkedList`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(linked_list_cursors)]
+    /// use std::collections::LinkedList;
+    ///
+    /// let mut list: LinkedList<u32> = (1..=3).collect();
+    /// let mut cursor = list.cursor_front_mut();
+    /// cursor.extend_after(vec![10, 11]);
+    /// cursor.extend_before(vec![0]);
+    /// assert_eq!(cursor.current(), Some(&mut 1));
+    /// assert_eq!(list.into_iter().collect::<Vec<_>>(), [0, 1, 10, 11, 2, 3]);
+    /// ```
+    #[unstable(feature = "linked_list_cursors", issue = "58533")]
+    pub fn extend_after<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.splice_after(iter.into_iter().collect())
+    }
+
+    /// Inserts the elements of the given iterator before the current one, in
+    /// the order in which the iterator yields them.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the end of the `LinkedList`.
+    #[unstable(feature = "linked_list_cursors", issue = "58533")]
+    pub fn extend_before<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.splice_before(iter.into_iter().collect())
+    }
+
     /// Splits the list into two after the current element. This will return a
     /// new list consisting of everything after the cursor, with the original
     /// list retaining everything before.
@@ -428,3 +428,56 @@ fn test_cursor_mut_insert() {
     check_links(&m);
     assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[200, 201, 202, 203, 1, 100, 101]);
 }
+
+#[test]
+fn test_cursor_mut_remove_range_extend() {
+    let mut m: LinkedList<u32> = LinkedList::new();
+    m.extend(&[1, 2, 3, 4, 5, 6]);
+    let mut cursor = m.cursor_front_mut();
+    assert_eq!(cursor.remove_range(Some(0)).into_iter().collect::<Vec<_>>(), &[]);
+    cursor.move_next();
+    let removed = cursor.remove_range(Some(3));
+    assert_eq!(cursor.current(), Some(&mut 4));
+    assert_eq!(cursor.index(), Some(1));
+    check_links(&removed);
+    assert_eq!(removed.into_iter().collect::<Vec<_>>(), &[2, 3]);
+    check_links(&m);
+    assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[1, 4, 5, 6]);
+
+    let mut cursor = m.cursor_front_mut();
+    cursor.extend_before(vec![7, 8]);
+    cursor.extend_after(vec![9]);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert_eq!(cursor.index(), Some(2));
+    check_links(&m);
+    assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[7, 8, 1, 9, 4, 5, 6]);
+
+    let mut cursor = m.cursor_back_mut();
+    cursor.move_prev();
+    let removed = cursor.remove_range(None);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.index(), None);
+    check_links(&removed);
+    assert_eq!(removed.into_iter().collect::<Vec<_>>(), &[5, 6]);
+    cursor.extend_after(vec![10]);
+    cursor.extend_before(vec![11]);
+    assert_eq!(cursor.remove_range(None).into_iter().collect::<Vec<_>>(), &[]);
+    check_links(&m);
+    assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &[10, 7, 8, 1, 9, 4, 11]);
+
+    let mut cursor = m.cursor_front_mut();
+    let removed = cursor.remove_range(None);
+    assert_eq!(cursor.current(), None);
+    check_links(&removed);
+    assert_eq!(removed.len(), 7);
+    check_links(&m);
+    assert!(m.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "range end out of bounds")]
+fn test_cursor_mut_remove_range_past_end() {
+    let mut m: LinkedList<u32> = (1..=6).collect();
+    let mut cursor = m.cursor_front_mut();
+    cursor.remove_range(Some(7));
+}
@@ -0,0 +1,393 @@
+use core::fmt;
+use core::mem;
+
+use super::VecDeque;
+
+/// A cursor over a `VecDeque`.
+///
+/// A `Cursor` is like an iterator, except that it can freely seek back-and-forth.
+///
+/// Like the cursors of [`LinkedList`], cursors always rest between two elements in the
+/// deque, and index in a logically circular way. To accommodate this, there is a "ghost"
+/// non-element that yields `None` between the front and back of the deque.
+///
+/// This `struct` is created by the [`cursor_front`] and [`cursor_back`] methods on
+/// [`VecDeque`].
+///
+/// [`LinkedList`]: crate::collections::LinkedList
+/// [`cursor_front`]: VecDeque::cursor_front
+/// [`cursor_back`]: VecDeque::cursor_back
+#[unstable(feature = "vec_deque_cursors", issue = "none")]
+pub struct Cursor<'a, T: 'a> {
+    // Equal to `deque.len()` when pointing to the "ghost" non-element.
+    index: usize,
+    deque: &'a VecDeque<T>,
+}
+
+#[unstable(feature = "vec_deque_cursors", issue = "none")]
+impl<T> Clone for Cursor<'_, T> {
+    fn clone(&self) -> Self {
+        let Cursor { index, deque } = *self;
+        Cursor { index, deque }
+    }
+}
+
+#[unstable(feature = "vec_deque_cursors", issue = "none")]
+impl<T: fmt::Debug> fmt::Debug for Cursor<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Cursor").field(&self.deque).field(&self.index()).finish()
+    }
+}
+
+/// A cursor over a `VecDeque` with editing operations.
+///
+/// A `CursorMut` is like a [`Cursor`], except that it can also insert and remove
+/// elements at its position. Inserting or removing an element takes time linear in the
+/// distance to the closer end of the deque, like [`VecDeque::insert`] and
+/// [`VecDeque::remove`] do.
+///
+/// This `struct` is created by the [`cursor_front_mut`] and [`cursor_back_mut`] methods
+/// on [`VecDeque`].
+///
+/// [`cursor_front_mut`]: VecDeque::cursor_front_mut
+/// [`cursor_back_mut`]: VecDeque::cursor_back_mut
+#[unstable(feature = "vec_deque_cursors", issue = "none")]
+pub struct CursorMut<'a, T: 'a> {
+    // Equal to `deque.len()` when pointing to the "ghost" non-element.
+    index: usize,
+    deque: &'a mut VecDeque<T>,
+}
+
+#[unstable(feature = "vec_deque_cursors", issue = "none")]
+impl<T: fmt::Debug> fmt::Debug for CursorMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CursorMut").field(&self.deque).field(&self.index()).finish()
+    }
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub(super) fn new(deque: &'a VecDeque<T>, index: usize) -> Self {
+        Cursor { index, deque }
+    }
+
+    /// Returns the cursor position index within the `VecDeque`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn index(&self) -> Option<usize> {
+        if self.index < self.deque.len() { Some(self.index) } else { None }
+    }
+
+    /// Moves the cursor to the next element of the `VecDeque`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `VecDeque`. If it is pointing to the last
+    /// element of the `VecDeque` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn move_next(&mut self) {
+        self.index = next_index(self.index, self.deque.len());
+    }
+
+    /// Moves the cursor to the previous element of the `VecDeque`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `VecDeque`. If it is pointing to the first
+    /// element of the `VecDeque` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn move_prev(&mut self) {
+        self.index = self.index.checked_sub(1).unwrap_or_else(|| self.deque.len());
+    }
+
+    /// Returns a reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn current(&self) -> Option<&'a T> {
+        self.deque.get(self.index)
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `VecDeque`. If it is pointing to the last
+    /// element of the `VecDeque` then this returns `None`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn peek_next(&self) -> Option<&'a T> {
+        self.deque.get(next_index(self.index, self.deque.len()))
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `VecDeque`. If it is pointing to the first
+    /// element of the `VecDeque` then this returns `None`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        self.deque.get(self.index.checked_sub(1)?)
+    }
+}
+
+impl<'a, T> CursorMut<'a, T> {
+    pub(super) fn new(deque: &'a mut VecDeque<T>, index: usize) -> Self {
+        CursorMut { index, deque }
+    }
+
+    /// Returns the cursor position index within the `VecDeque`.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn index(&self) -> Option<usize> {
+        if self.index < self.deque.len() { Some(self.index) } else { None }
+    }
+
+    /// Moves the cursor to the next element of the `VecDeque`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the first element of the `VecDeque`. If it is pointing to the last
+    /// element of the `VecDeque` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn move_next(&mut self) {
+        self.index = next_index(self.index, self.deque.len());
+    }
+
+    /// Moves the cursor to the previous element of the `VecDeque`.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this will move it to
+    /// the last element of the `VecDeque`. If it is pointing to the first
+    /// element of the `VecDeque` then this will move it to the "ghost" non-element.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn move_prev(&mut self) {
+        self.index = self.index.checked_sub(1).unwrap_or_else(|| self.deque.len());
+    }
+
+    /// Returns a reference to the element that the cursor is currently
+    /// pointing to.
+    ///
+    /// This returns `None` if the cursor is currently pointing to the
+    /// "ghost" non-element.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.deque.get_mut(self.index)
+    }
+
+    /// Returns a reference to the next element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the first element of the `VecDeque`. If it is pointing to the last
+    /// element of the `VecDeque` then this returns `None`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        let next = next_index(self.index, self.deque.len());
+        self.deque.get_mut(next)
+    }
+
+    /// Returns a reference to the previous element.
+    ///
+    /// If the cursor is pointing to the "ghost" non-element then this returns
+    /// the last element of the `VecDeque`. If it is pointing to the first
+    /// element of the `VecDeque` then this returns `None`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        self.deque.get_mut(self.index.checked_sub(1)?)
+    }
+
+    /// Returns a read-only cursor pointing to the current element.
+    ///
+    /// The lifetime of the returned `Cursor` is bound to that of the
+    /// `CursorMut`, which means it cannot outlive the `CursorMut` and that the
+    /// `CursorMut` is frozen for the lifetime of the `Cursor`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn as_cursor(&self) -> Cursor<'_, T> {
+        Cursor { index: self.index, deque: self.deque }
+    }
+}
+
+// Now the deque editing operations
+
+impl<'a, T> CursorMut<'a, T> {
+    /// Inserts a new element into the `VecDeque` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the front of the `VecDeque`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn insert_after(&mut self, item: T) {
+        let is_ghost = self.index == self.deque.len();
+        self.deque.insert(next_index(self.index, self.deque.len()), item);
+        if is_ghost {
+            // The "ghost" non-element's index has changed.
+            self.index += 1;
+        }
+    }
+
+    /// Inserts a new element into the `VecDeque` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new element is
+    /// inserted at the end of the `VecDeque`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn insert_before(&mut self, item: T) {
+        self.deque.insert(self.index, item);
+        self.index += 1;
+    }
+
+    /// Removes the current element from the `VecDeque`.
+    ///
+    /// The element that was removed is returned, and the cursor is
+    /// moved to point to the next element in the `VecDeque`.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no element
+    /// is removed and `None` is returned.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.deque.remove(self.index)
+    }
+
+    /// Removes the elements from the current one up to, but not including, the
+    /// one at the position `end`.
+    ///
+    /// `end` is the position of a cursor at the end of the range, as returned
+    /// by [`index`]. `None` stands for the "ghost" non-element, in which case
+    /// all the elements from the current one to the back of the deque are
+    /// removed.
+    ///
+    /// The removed elements are returned as a new `VecDeque`, in their original
+    /// order. The cursor is moved to point to the element that followed them.
+    ///
+    /// If the cursor is currently pointing to the "ghost" non-element then no
+    /// elements are removed and an empty deque is returned.
+    ///
+    /// [`index`]: CursorMut::index
+    ///
+    /// # Panics
+    ///
+    /// Panics if `end` is before the current position of the cursor, or past
+    /// the end of the deque.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_deque_cursors)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut deque: VecDeque<u32> = (1..=6).collect();
+    /// let end = {
+    ///     let mut cursor = deque.cursor_back();
+    ///     cursor.move_prev();
+    ///     cursor.index()
+    /// };
+    /// let mut cursor = deque.cursor_front_mut();
+    /// cursor.move_next();
+    /// let removed = cursor.remove_range(end);
+    /// assert_eq!(cursor.current(), Some(&mut 5));
+    /// assert_eq!(removed, [2, 3, 4]);
+    /// assert_eq!(deque, [1, 5, 6]);
+    /// ```
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn remove_range(&mut self, end: Option<usize>) -> VecDeque<T> {
+        let end = end.unwrap_or(self.deque.len());
+        assert!(self.index <= end && end <= self.deque.len(), "range end out of bounds");
+        self.deque.drain(self.index..end).collect()
+    }
+
+    /// Inserts the elements from the given `VecDeque` after the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the start of the `VecDeque`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn splice_after(&mut self, mut deque: VecDeque<T>) {
+        let is_ghost = self.index == self.deque.len();
+        let mut tail = self.deque.split_off(next_index(self.index, self.deque.len()));
+        self.deque.append(&mut deque);
+        self.deque.append(&mut tail);
+        if is_ghost {
+            // The "ghost" non-element's index has changed.
+            self.index = self.deque.len();
+        }
+    }
+
+    /// Inserts the elements from the given `VecDeque` before the current one.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the end of the `VecDeque`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn splice_before(&mut self, mut deque: VecDeque<T>) {
+        let splice_len = deque.len();
+        let mut tail = self.deque.split_off(self.index);
+        self.deque.append(&mut deque);
+        self.deque.append(&mut tail);
+        self.index += splice_len;
+    }
+
+    /// Inserts the elements of the given iterator after the current one, in
+    /// the order in which the iterator yields them.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the start of the `VecDeque`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_deque_cursors)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let mut deque: VecDeque<u32> = (1..=3).collect();
+    /// let mut cursor = deque.cursor_front_mut();
+    /// cursor.extend_after(vec![10, 11]);
+    /// cursor.extend_before(vec![0]);
+    /// assert_eq!(cursor.current(), Some(&mut 1));
+    /// assert_eq!(deque, [0, 1, 10, 11, 2, 3]);
+    /// ```
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn extend_after<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.splice_after(iter.into_iter().collect())
+    }
+
+    /// Inserts the elements of the given iterator before the current one, in
+    /// the order in which the iterator yields them.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the new elements are
+    /// inserted at the end of the `VecDeque`.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn extend_before<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.splice_before(iter.into_iter().collect())
+    }
+
+    /// Splits the deque into two after the current element. This will return a
+    /// new deque consisting of everything after the cursor, with the original
+    /// deque retaining everything before.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire contents
+    /// of the `VecDeque` are moved.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn split_after(&mut self) -> VecDeque<T> {
+        if self.index == self.deque.len() {
+            // The "ghost" non-element's index has changed to 0.
+            self.index = 0;
+            mem::take(self.deque)
+        } else {
+            self.deque.split_off(self.index + 1)
+        }
+    }
+
+    /// Splits the deque into two before the current element. This will return a
+    /// new deque consisting of everything before the cursor, with the original
+    /// deque retaining everything after.
+    ///
+    /// If the cursor is pointing at the "ghost" non-element then the entire contents
+    /// of the `VecDeque` are moved.
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn split_before(&mut self) -> VecDeque<T> {
+        let tail = self.deque.split_off(self.index);
+        self.index = 0;
+        mem::replace(self.deque, tail)
+    }
+}
+
+/// Returns the index following `index` in a deque of length `len`, going
+/// from the "ghost" non-element at `len` back to the front.
+fn next_index(index: usize, len: usize) -> usize {
+    if index == len { 0 } else { index + 1 }
+}
@@ -44,6 +44,11 @@
 
 mod iter;
 
+#[unstable(feature = "vec_deque_cursors", issue = "none")]
+pub use self::cursor::{Cursor, CursorMut};
+
+mod cursor;
+
 use self::pair_slices::PairSlices;
 
 mod pair_slices;
@@ -955,6 +960,57 @@ pub fn iter_mut(&mut self) -> IterMut<'_, T> {
         }
     }
 
+    /// Provides a cursor at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the deque is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(vec_deque_cursors)]
+    /// use std::collections::VecDeque;
+    ///
+    /// let deque: VecDeque<_> = vec![1, 2, 3].into_iter().collect();
+    /// let mut cursor = deque.cursor_front();
+    /// assert_eq!(cursor.current(), Some(&1));
+    /// cursor.move_prev();
+    /// assert_eq!(cursor.current(), None);
+    /// assert_eq!(cursor.peek_prev(), Some(&3));
+    /// ```
+    #[inline]
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn cursor_front(&self) -> Cursor<'_, T> {
+        Cursor::new(self, 0)
+    }
+
+    /// Provides a cursor with editing operations at the front element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the deque is empty.
+    #[inline]
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn cursor_front_mut(&mut self) -> CursorMut<'_, T> {
+        CursorMut::new(self, 0)
+    }
+
+    /// Provides a cursor at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the deque is empty.
+    #[inline]
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn cursor_back(&self) -> Cursor<'_, T> {
+        Cursor::new(self, self.len().saturating_sub(1))
+    }
+
+    /// Provides a cursor with editing operations at the back element.
+    ///
+    /// The cursor is pointing to the "ghost" non-element if the deque is empty.
+    #[inline]
+    #[unstable(feature = "vec_deque_cursors", issue = "none")]
+    pub fn cursor_back_mut(&mut self) -> CursorMut<'_, T> {
+        let index = self.len().saturating_sub(1);
+        CursorMut::new(self, index)
+    }
+
     /// Returns a pair of slices which contain, in order, the contents of the
     /// `VecDeque`.
     ///
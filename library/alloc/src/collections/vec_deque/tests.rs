@@ -584,3 +584,146 @@ fn issue_53529() {
         assert_eq!(*a, 2);
     }
 }
+
+#[test]
+fn test_cursor_move_peek() {
+    let mut m: VecDeque<u32> = VecDeque::with_capacity(8);
+    // Make the contents wrap around the end of the buffer.
+    m.extend(&[3, 4, 5, 6]);
+    m.push_front(2);
+    m.push_front(1);
+    let mut cursor = m.cursor_front();
+    assert_eq!(cursor.current(), Some(&1));
+    assert_eq!(cursor.peek_next(), Some(&2));
+    assert_eq!(cursor.peek_prev(), None);
+    assert_eq!(cursor.index(), Some(0));
+    cursor.move_prev();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&1));
+    assert_eq!(cursor.peek_prev(), Some(&6));
+    assert_eq!(cursor.index(), None);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.current(), Some(&2));
+    assert_eq!(cursor.peek_next(), Some(&3));
+    assert_eq!(cursor.peek_prev(), Some(&1));
+    assert_eq!(cursor.index(), Some(1));
+
+    let mut cursor = m.cursor_back();
+    assert_eq!(cursor.current(), Some(&6));
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(cursor.peek_prev(), Some(&5));
+    assert_eq!(cursor.index(), Some(5));
+    cursor.move_next();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&1));
+    assert_eq!(cursor.peek_prev(), Some(&6));
+    assert_eq!(cursor.index(), None);
+
+    let mut m: VecDeque<u32> = VecDeque::new();
+    let mut cursor = m.cursor_front_mut();
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), None);
+    assert_eq!(cursor.peek_prev(), None);
+    cursor.move_next();
+    assert_eq!(cursor.index(), None);
+    assert_eq!(m.cursor_back().current(), None);
+}
+
+#[test]
+fn test_cursor_mut_insert_remove() {
+    let mut m: VecDeque<u32> = VecDeque::with_capacity(8);
+    m.extend(&[3, 4, 5, 6]);
+    m.push_front(2);
+    m.push_front(1);
+    let mut cursor = m.cursor_front_mut();
+    cursor.insert_before(7);
+    cursor.insert_after(8);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert_eq!(m, [7, 1, 8, 2, 3, 4, 5, 6]);
+    let mut cursor = m.cursor_front_mut();
+    cursor.move_prev();
+    cursor.insert_before(9);
+    cursor.insert_after(10);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 10));
+    assert_eq!(cursor.peek_prev(), Some(&mut 9));
+    assert_eq!(m, [10, 7, 1, 8, 2, 3, 4, 5, 6, 9]);
+    let mut cursor = m.cursor_front_mut();
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), None);
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(7));
+    assert_eq!(cursor.current(), Some(&mut 1));
+    cursor.move_prev();
+    cursor.move_prev();
+    cursor.move_prev();
+    assert_eq!(cursor.remove_current(), Some(9));
+    assert_eq!(cursor.current(), None);
+    cursor.move_next();
+    assert_eq!(cursor.remove_current(), Some(10));
+    assert_eq!(m, [1, 8, 2, 3, 4, 5, 6]);
+}
+
+#[test]
+fn test_cursor_mut_remove_range() {
+    let mut m: VecDeque<u32> = (1..=6).collect();
+    let mut cursor = m.cursor_front_mut();
+    assert_eq!(cursor.remove_range(Some(0)), []);
+    cursor.move_next();
+    assert_eq!(cursor.remove_range(Some(3)), [2, 3]);
+    assert_eq!(cursor.current(), Some(&mut 4));
+    assert_eq!(cursor.index(), Some(1));
+    cursor.move_next();
+    assert_eq!(cursor.remove_range(None), [5, 6]);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.remove_range(None), []);
+    assert_eq!(m, [1, 4]);
+}
+
+#[test]
+#[should_panic(expected = "range end out of bounds")]
+fn test_cursor_mut_remove_range_backwards() {
+    let mut m: VecDeque<u32> = (1..=6).collect();
+    let mut cursor = m.cursor_back_mut();
+    cursor.remove_range(Some(2));
+}
+
+#[test]
+fn test_cursor_mut_splice_split() {
+    let mut m: VecDeque<u32> = (1..=3).collect();
+    let mut cursor = m.cursor_front_mut();
+    cursor.splice_after((100..=101).collect());
+    cursor.splice_before((200..=201).collect());
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert_eq!(cursor.index(), Some(2));
+    cursor.move_prev();
+    cursor.move_prev();
+    cursor.move_prev();
+    cursor.extend_after(vec![10]);
+    cursor.extend_before(vec![11]);
+    assert_eq!(cursor.current(), None);
+    assert_eq!(cursor.peek_next(), Some(&mut 10));
+    assert_eq!(cursor.peek_prev(), Some(&mut 11));
+    assert_eq!(m, [10, 200, 201, 1, 100, 101, 2, 3, 11]);
+
+    let mut cursor = m.cursor_front_mut();
+    cursor.move_next();
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.split_before(), [10, 200, 201]);
+    assert_eq!(cursor.current(), Some(&mut 1));
+    assert_eq!(cursor.index(), Some(0));
+    cursor.move_next();
+    cursor.move_next();
+    assert_eq!(cursor.split_after(), [2, 3, 11]);
+    assert_eq!(cursor.current(), Some(&mut 101));
+    assert_eq!(m, [1, 100, 101]);
+
+    let mut cursor = m.cursor_back_mut();
+    cursor.move_next();
+    assert_eq!(cursor.split_after(), [1, 100, 101]);
+    assert_eq!(cursor.current(), None);
+    assert!(m.is_empty());
+}
@@ -1341,6 +1341,32 @@ macro_rules! trace_macros {
         /* compiler built-in */
     }
 
+    /// Attribute macro applied to a function to run it before each test of its module, and of
+    /// the modules nested in it.
+    #[unstable(
+        feature = "test_fixtures",
+        issue = "none",
+        reason = "test setup and teardown hooks are an unstable feature"
+    )]
+    #[allow_internal_unstable(test, rustc_attrs)]
+    #[rustc_builtin_macro]
+    pub macro test_setup($item:item) {
+        /* compiler built-in */
+    }
+
+    /// Attribute macro applied to a function to run it after each test of its module, and of
+    /// the modules nested in it, even if the test failed.
+    #[unstable(
+        feature = "test_fixtures",
+        issue = "none",
+        reason = "test setup and teardown hooks are an unstable feature"
+    )]
+    #[allow_internal_unstable(test, rustc_attrs)]
+    #[rustc_builtin_macro]
+    pub macro test_teardown($item:item) {
+        /* compiler built-in */
+    }
+
     /// Attribute macro applied to a static to register it as a global allocator.
     ///
     /// See also [`std::alloc::GlobalAlloc`](../std/alloc/trait.GlobalAlloc.html).
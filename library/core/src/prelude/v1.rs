@@ -76,3 +76,11 @@
 )]
 #[doc(no_inline)]
 pub use crate::macros::builtin::cfg_accessible;
+
+#[unstable(
+    feature = "test_fixtures",
+    issue = "none",
+    reason = "test setup and teardown hooks are an unstable feature"
+)]
+#[doc(no_inline)]
+pub use crate::macros::builtin::{test_setup, test_teardown};
@@ -62,6 +62,14 @@
 #[doc(hidden)]
 pub use core::prelude::v1::cfg_accessible;
 
+#[unstable(
+    feature = "test_fixtures",
+    issue = "none",
+    reason = "test setup and teardown hooks are an unstable feature"
+)]
+#[doc(hidden)]
+pub use core::prelude::v1::{test_setup, test_teardown};
+
 // The file so far is equivalent to src/libcore/prelude/v1.rs,
 // and below to src/liballoc/prelude.rs.
 // Those files are duplicated rather than using glob imports
@@ -0,0 +1,386 @@
+//! Bounded channel based on a preallocated array.
+//!
+//! This flavor has a fixed, positive capacity. Sending and receiving do not
+//! take any locks as long as no thread has to block.
+//!
+//! The implementation is based on Dmitry Vyukov's bounded MPMC queue:
+//!
+//! * http://www.1024cores.net/home/lock-free-algorithms/queues/bounded-mpmc-queue
+//!
+//! Every slot of the array carries a "stamp", which tells senders and
+//! receivers whether the slot currently holds a message of the current lap
+//! around the array. The `head` and `tail` indices carry their lap in their
+//! upper bits, and `tail` additionally holds the "mark bit" that flags the
+//! channel as disconnected.
+
+use crate::cell::UnsafeCell;
+use crate::hint;
+use crate::mem::MaybeUninit;
+use crate::ptr;
+use crate::sync::atomic::{self, AtomicUsize, Ordering};
+use crate::sync::mpsc::cache_aligned::CacheAligned;
+use crate::sync::mpsc::{RecvTimeoutError, TryRecvError, TrySendError};
+use crate::thread;
+use crate::time::Instant;
+
+use super::waker::Waker;
+
+/// A slot in the buffer.
+struct Slot<T> {
+    /// The current stamp.
+    stamp: AtomicUsize,
+
+    /// The message in this slot, initialized if and only if the stamp says so.
+    msg: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Bounded channel based on a preallocated array.
+pub(super) struct Channel<T> {
+    /// The head of the channel, where the next message will be received from.
+    ///
+    /// This value is a "stamp" consisting of an index into the buffer and a
+    /// lap, packed into a single `usize`. The lower bits represent the index,
+    /// while the upper bits represent the lap. The mark bit in the head is
+    /// always zero.
+    head: CacheAligned<AtomicUsize>,
+
+    /// The tail of the channel, where the next message will be sent to.
+    ///
+    /// This value has the same layout as `head`, except that the mark bit
+    /// indicates that the channel is disconnected.
+    tail: CacheAligned<AtomicUsize>,
+
+    /// The buffer holding the slots.
+    buffer: Box<[Slot<T>]>,
+
+    /// The channel capacity.
+    cap: usize,
+
+    /// A stamp with the value of `{ lap: 1, mark: 0, index: 0 }`.
+    one_lap: usize,
+
+    /// If this bit is set in the tail, that means the channel is disconnected.
+    mark_bit: usize,
+
+    /// Threads waiting for a free slot.
+    senders: Waker,
+
+    /// Threads waiting for a message.
+    receivers: Waker,
+
+    /// The number of `Sender`s and `Receiver`s of the channel.
+    sender_count: AtomicUsize,
+    receiver_count: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for Channel<T> {}
+unsafe impl<T: Send> Sync for Channel<T> {}
+
+impl<T> Channel<T> {
+    /// Creates a channel of capacity `cap`, with one sender and one receiver.
+    pub(super) fn with_capacity(cap: usize) -> Channel<T> {
+        assert!(cap > 0, "capacity must be positive");
+
+        // Compute constants `mark_bit` and `one_lap`.
+        let mark_bit = (cap + 1).next_power_of_two();
+        let one_lap = mark_bit * 2;
+
+        // Allocate a buffer of `cap` slots. The stamp of each slot is set to
+        // `{ lap: 0, mark: 0, index: i }`, i.e., empty for the first lap.
+        let buffer = (0..cap)
+            .map(|i| Slot {
+                stamp: AtomicUsize::new(i),
+                msg: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+
+        Channel {
+            head: CacheAligned::new(AtomicUsize::new(0)),
+            tail: CacheAligned::new(AtomicUsize::new(0)),
+            buffer,
+            cap,
+            one_lap,
+            mark_bit,
+            senders: Waker::new(),
+            receivers: Waker::new(),
+            sender_count: AtomicUsize::new(1),
+            receiver_count: AtomicUsize::new(1),
+        }
+    }
+
+    /// Attempts to send a message without blocking.
+    pub(super) fn try_send(&self, msg: T) -> Result<(), TrySendError<T>> {
+        let mut step = 0;
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        loop {
+            if tail & self.mark_bit != 0 {
+                return Err(TrySendError::Disconnected(msg));
+            }
+
+            // Deconstruct the tail.
+            let index = tail & (self.mark_bit - 1);
+            let lap = tail & !(self.one_lap - 1);
+
+            // The tail after this send, wrapping around to the next lap at the
+            // end of the buffer.
+            let new_tail =
+                if index + 1 < self.cap { tail + 1 } else { lap.wrapping_add(self.one_lap) };
+
+            let slot = &self.buffer[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if tail == stamp {
+                // The slot is empty for this lap, try to claim it.
+                match self.tail.compare_exchange_weak(
+                    tail,
+                    new_tail,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe {
+                            slot.msg.get().write(MaybeUninit::new(msg));
+                        }
+                        slot.stamp.store(tail + 1, Ordering::Release);
+                        self.receivers.notify();
+                        return Ok(());
+                    }
+                    Err(t) => {
+                        tail = t;
+                        spin(&mut step);
+                    }
+                }
+            } else if stamp.wrapping_add(self.one_lap) == tail + 1 {
+                // The slot still holds a message of the previous lap, so the
+                // channel might be full.
+                atomic::fence(Ordering::SeqCst);
+                let head = self.head.load(Ordering::Relaxed);
+                if head.wrapping_add(self.one_lap) == tail {
+                    return Err(TrySendError::Full(msg));
+                }
+                spin(&mut step);
+                tail = self.tail.load(Ordering::Relaxed);
+            } else {
+                // Another sender has claimed the slot but not written to it
+                // yet, so wait for it.
+                snooze(&mut step);
+                tail = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Sends a message, blocking while the channel is full.
+    pub(super) fn send(&self, mut msg: T) -> Result<(), T> {
+        loop {
+            match self.try_send(msg) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Disconnected(m)) => return Err(m),
+                Err(TrySendError::Full(m)) => msg = m,
+            }
+            self.senders.wait_until(None, || !self.is_full() || self.is_disconnected());
+        }
+    }
+
+    /// Attempts to receive a message without blocking.
+    pub(super) fn try_recv(&self) -> Result<T, TryRecvError> {
+        let mut step = 0;
+        let mut head = self.head.load(Ordering::Relaxed);
+
+        loop {
+            // Deconstruct the head.
+            let index = head & (self.mark_bit - 1);
+            let lap = head & !(self.one_lap - 1);
+
+            let slot = &self.buffer[index];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+
+            if head + 1 == stamp {
+                // The slot holds a message for this lap, try to claim it.
+                let new_head =
+                    if index + 1 < self.cap { head + 1 } else { lap.wrapping_add(self.one_lap) };
+
+                match self.head.compare_exchange_weak(
+                    head,
+                    new_head,
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let msg = unsafe { slot.msg.get().read().assume_init() };
+                        slot.stamp.store(head.wrapping_add(self.one_lap), Ordering::Release);
+                        self.senders.notify();
+                        return Ok(msg);
+                    }
+                    Err(h) => {
+                        head = h;
+                        spin(&mut step);
+                    }
+                }
+            } else if stamp == head {
+                // The slot is empty, so the channel might be empty.
+                atomic::fence(Ordering::SeqCst);
+                let tail = self.tail.load(Ordering::Relaxed);
+                if (tail & !self.mark_bit) == head {
+                    return if tail & self.mark_bit != 0 {
+                        Err(TryRecvError::Disconnected)
+                    } else {
+                        Err(TryRecvError::Empty)
+                    };
+                }
+                spin(&mut step);
+                head = self.head.load(Ordering::Relaxed);
+            } else {
+                // Another receiver has claimed the slot but not read from it
+                // yet, so wait for it.
+                snooze(&mut step);
+                head = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Receives a message, blocking while the channel is empty, but at most
+    /// until `deadline`.
+    pub(super) fn recv(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        loop {
+            match self.try_recv() {
+                Ok(msg) => return Ok(msg),
+                Err(TryRecvError::Disconnected) => return Err(RecvTimeoutError::Disconnected),
+                Err(TryRecvError::Empty) => {}
+            }
+            if let Some(deadline) = deadline {
+                if Instant::now() >= deadline {
+                    return Err(RecvTimeoutError::Timeout);
+                }
+            }
+            self.receivers.wait_until(deadline, || !self.is_empty() || self.is_disconnected());
+        }
+    }
+
+    /// Returns the number of messages in the channel.
+    pub(super) fn len(&self) -> usize {
+        loop {
+            // Load the tail, then load the head.
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+
+            // If the tail didn't change, we've got consistent values to work
+            // with.
+            if self.tail.load(Ordering::SeqCst) == tail {
+                return self.len_between(head, tail);
+            }
+        }
+    }
+
+    // The number of messages from `head` to `tail`, ignoring the mark bit.
+    fn len_between(&self, head: usize, tail: usize) -> usize {
+        let hix = head & (self.mark_bit - 1);
+        let tix = tail & (self.mark_bit - 1);
+
+        if hix < tix {
+            tix - hix
+        } else if hix > tix {
+            self.cap - hix + tix
+        } else if (tail & !self.mark_bit) == head {
+            0
+        } else {
+            self.cap
+        }
+    }
+
+    pub(super) fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        let head = self.head.load(Ordering::SeqCst);
+        let tail = self.tail.load(Ordering::SeqCst);
+        (tail & !self.mark_bit) == head
+    }
+
+    pub(super) fn is_full(&self) -> bool {
+        let tail = self.tail.load(Ordering::SeqCst);
+        let head = self.head.load(Ordering::SeqCst);
+        head.wrapping_add(self.one_lap) == tail & !self.mark_bit
+    }
+
+    fn is_disconnected(&self) -> bool {
+        self.tail.load(Ordering::SeqCst) & self.mark_bit != 0
+    }
+
+    /// Flags the channel as disconnected and wakes up all blocked threads.
+    fn disconnect(&self) {
+        let tail = self.tail.fetch_or(self.mark_bit, Ordering::SeqCst);
+        if tail & self.mark_bit == 0 {
+            self.senders.disconnect();
+            self.receivers.disconnect();
+        }
+    }
+
+    pub(super) fn acquire_sender(&self) {
+        self.sender_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called when a sender is dropped, disconnecting the channel if it was
+    /// the last one.
+    pub(super) fn release_sender(&self) {
+        if self.sender_count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.disconnect();
+        }
+    }
+
+    pub(super) fn acquire_receiver(&self) {
+        self.receiver_count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called when a receiver is dropped, disconnecting the channel if it was
+    /// the last one.
+    pub(super) fn release_receiver(&self) {
+        if self.receiver_count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.disconnect();
+        }
+    }
+}
+
+impl<T> Drop for Channel<T> {
+    fn drop(&mut self) {
+        // Get the index of the head, and drop all of the messages that are
+        // still in the channel.
+        let head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+        let hix = head & (self.mark_bit - 1);
+
+        for i in 0..self.len_between(head, tail) {
+            let index = if hix + i < self.cap { hix + i } else { hix + i - self.cap };
+            unsafe {
+                ptr::drop_in_place((*self.buffer[index].msg.get()).as_mut_ptr());
+            }
+        }
+    }
+}
+
+// Number of exponential backoff steps that only spin, before yielding to the
+// scheduler.
+const SPIN_LIMIT: u32 = 6;
+
+/// Backs off in a lock-free loop that raced with another thread.
+fn spin(step: &mut u32) {
+    for _ in 0..1 << (*step).min(SPIN_LIMIT) {
+        hint::spin_loop();
+    }
+    if *step <= SPIN_LIMIT {
+        *step += 1;
+    }
+}
+
+/// Backs off in a loop that waits for another thread to make progress.
+fn snooze(step: &mut u32) {
+    if *step <= SPIN_LIMIT {
+        for _ in 0..1 << *step {
+            hint::spin_loop();
+        }
+        *step += 1;
+    } else {
+        thread::yield_now();
+    }
+}
@@ -0,0 +1,381 @@
+//! Multi-producer, multi-consumer FIFO queue communication primitives.
+//!
+//! This module provides a bounded channel that, unlike the channels of
+//! [`mpsc`], can have any number of [`Receiver`]s. Receivers are cloned just
+//! like senders, and every message is received by exactly one of them, which
+//! makes the channel suitable as the shared work queue of a pool of worker
+//! threads.
+//!
+//! The channel is created by the [`sync_channel`] function, and holds a fixed
+//! number of messages in a preallocated buffer. Sending and receiving do not
+//! take any locks unless the calling thread has to block because the channel
+//! is full or empty, respectively.
+//!
+//! The errors reported by the channel are the same as those of [`mpsc`]: a
+//! channel is disconnected once all of its senders, or all of its receivers,
+//! have been dropped.
+//!
+//! [`mpsc`]: crate::sync::mpsc
+//!
+//! # Examples
+//!
+//! ```
+//! #![feature(mpmc_channel)]
+//! use std::sync::mpmc::sync_channel;
+//! use std::thread;
+//!
+//! let (tx, rx) = sync_channel(16);
+//!
+//! let workers: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let rx = rx.clone();
+//!         thread::spawn(move || rx.iter().map(|n: u64| n * n).sum::<u64>())
+//!     })
+//!     .collect();
+//! drop(rx);
+//!
+//! for n in 0..100 {
+//!     tx.send(n).unwrap();
+//! }
+//! drop(tx);
+//!
+//! let total: u64 = workers.into_iter().map(|w| w.join().unwrap()).sum();
+//! assert_eq!(total, (0..100).map(|n| n * n).sum());
+//! ```
+
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod tests;
+
+mod array;
+mod waker;
+
+use crate::fmt;
+use crate::sync::Arc;
+use crate::time::{Duration, Instant};
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub use crate::sync::mpsc::{RecvError, RecvTimeoutError, SendError, TryRecvError, TrySendError};
+
+/// The sending half of a [`sync_channel`].
+///
+/// Messages can be sent through this channel with [`send`] and [`try_send`].
+/// The sender can be cloned to send to the same channel from multiple threads.
+///
+/// [`send`]: Sender::send
+/// [`try_send`]: Sender::try_send
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub struct Sender<T> {
+    inner: Arc<array::Channel<T>>,
+}
+
+/// The receiving half of a [`sync_channel`].
+///
+/// Messages sent to the channel can be retrieved using [`recv`]. The receiver
+/// can be cloned to receive from the same channel in multiple threads; each
+/// message is only received by one of the receivers.
+///
+/// [`recv`]: Receiver::recv
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub struct Receiver<T> {
+    inner: Arc<array::Channel<T>>,
+}
+
+/// An iterator over messages on a [`Receiver`], created by [`iter`].
+///
+/// [`iter`]: Receiver::iter
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct Iter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+/// An iterator that attempts to yield all pending values for a [`Receiver`],
+/// created by [`try_iter`].
+///
+/// [`try_iter`]: Receiver::try_iter
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct TryIter<'a, T: 'a> {
+    rx: &'a Receiver<T>,
+}
+
+/// An owning iterator over messages on a [`Receiver`], created by
+/// [`into_iter`].
+///
+/// [`into_iter`]: Receiver::into_iter
+#[unstable(feature = "mpmc_channel", issue = "none")]
+#[derive(Debug)]
+pub struct IntoIter<T> {
+    rx: Receiver<T>,
+}
+
+/// Creates a new bounded multi-producer, multi-consumer channel, returning
+/// the sender/receiver halves.
+///
+/// The channel has an internal buffer on which messages will be queued.
+/// `bound` specifies the buffer size. When the buffer becomes full, future
+/// sends will block waiting for the buffer to open up.
+///
+/// Both halves can be cloned, and the channel is disconnected once all of the
+/// [`Sender`]s or all of the [`Receiver`]s have been dropped.
+///
+/// # Panics
+///
+/// Panics if `bound` is zero. Unlike [`mpsc::sync_channel`], this channel
+/// does not support rendezvous channels.
+///
+/// [`mpsc::sync_channel`]: crate::sync::mpsc::sync_channel
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpmc_channel)]
+/// use std::sync::mpmc::{sync_channel, TrySendError};
+///
+/// let (tx, rx) = sync_channel(1);
+/// tx.send(1).unwrap();
+/// assert_eq!(tx.try_send(2), Err(TrySendError::Full(2)));
+///
+/// let rx2 = rx.clone();
+/// assert_eq!(rx2.recv(), Ok(1));
+/// ```
+#[unstable(feature = "mpmc_channel", issue = "none")]
+pub fn sync_channel<T>(bound: usize) -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(array::Channel::with_capacity(bound));
+    (Sender { inner: inner.clone() }, Receiver { inner })
+}
+
+impl<T> Sender<T> {
+    /// Sends a value on this channel, blocking while the buffer is full.
+    ///
+    /// An unsuccessful send would be one where all of the corresponding
+    /// receivers have already been dropped. In that case the value is
+    /// returned back in the [`SendError`]. A successful send does not
+    /// guarantee that the value will ever be received.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn send(&self, t: T) -> Result<(), SendError<T>> {
+        self.inner.send(t).map_err(SendError)
+    }
+
+    /// Attempts to send a value on this channel without blocking.
+    ///
+    /// This returns [`TrySendError::Full`] if the buffer is full, and
+    /// [`TrySendError::Disconnected`] if all of the receivers have been
+    /// dropped. In both cases the value is returned in the error.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn try_send(&self, t: T) -> Result<(), TrySendError<T>> {
+        self.inner.try_send(t)
+    }
+
+    /// Returns the number of messages in the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the channel holds no messages.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns `true` if the buffer of the channel is full.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+
+    /// Returns the capacity of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Sender<T> {
+        self.inner.acquire_sender();
+        Sender { inner: self.inner.clone() }
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.inner.release_sender();
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender").finish()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Attempts to return a pending value on this receiver without blocking.
+    ///
+    /// This returns [`TryRecvError::Empty`] if there is no message in the
+    /// channel, and [`TryRecvError::Disconnected`] if there is none and all of
+    /// the senders have been dropped.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.inner.try_recv()
+    }
+
+    /// Attempts to wait for a value on this receiver, returning an error if
+    /// the corresponding channel has hung up.
+    ///
+    /// This blocks while the channel is empty. Once all of the senders have
+    /// been dropped, the remaining messages can still be received, after
+    /// which this returns [`RecvError`].
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn recv(&self) -> Result<T, RecvError> {
+        self.inner.recv(None).map_err(|_| RecvError)
+    }
+
+    /// Attempts to wait for a value on this receiver, returning an error if
+    /// the corresponding channel has hung up, or if it waits more than
+    /// `timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpmc_channel)]
+    /// use std::sync::mpmc::{sync_channel, RecvTimeoutError};
+    /// use std::time::Duration;
+    ///
+    /// let (tx, rx) = sync_channel::<i32>(1);
+    /// assert_eq!(rx.recv_timeout(Duration::from_millis(10)), Err(RecvTimeoutError::Timeout));
+    /// drop(tx);
+    /// assert_eq!(
+    ///     rx.recv_timeout(Duration::from_millis(10)),
+    ///     Err(RecvTimeoutError::Disconnected)
+    /// );
+    /// ```
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.recv_deadline(deadline),
+            // So far in the future that it's practically the same as waiting
+            // indefinitely.
+            None => self.recv().map_err(RecvTimeoutError::from),
+        }
+    }
+
+    /// Attempts to wait for a value on this receiver, returning an error if
+    /// the corresponding channel has hung up, or if `deadline` is reached.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
+        self.inner.recv(Some(deadline))
+    }
+
+    /// Returns an iterator that will block waiting for messages, until the
+    /// channel has hung up.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { rx: self }
+    }
+
+    /// Returns an iterator that will yield all pending messages without
+    /// blocking, until the channel is empty or has hung up.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { rx: self }
+    }
+
+    /// Returns the number of messages in the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns `true` if the channel holds no messages.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns `true` if the buffer of the channel is full.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+
+    /// Returns the capacity of the channel.
+    #[unstable(feature = "mpmc_channel", issue = "none")]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Receiver<T> {
+        self.inner.acquire_receiver();
+        Receiver { inner: self.inner.clone() }
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.inner.release_receiver();
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver").finish()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> Iterator for TryIter<'a, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.try_recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<'a, T> IntoIterator for &'a Receiver<T> {
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.rx.recv().ok()
+    }
+}
+
+#[unstable(feature = "mpmc_channel", issue = "none")]
+impl<T> IntoIterator for Receiver<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { rx: self }
+    }
+}
@@ -0,0 +1,218 @@
+use super::*;
+use crate::sync::atomic::{AtomicUsize, Ordering};
+use crate::thread;
+use crate::time::{Duration, Instant};
+
+#[test]
+fn smoke() {
+    let (tx, rx) = sync_channel::<i32>(1);
+    tx.send(1).unwrap();
+    assert_eq!(rx.recv().unwrap(), 1);
+}
+
+#[test]
+#[should_panic(expected = "capacity must be positive")]
+fn zero_capacity() {
+    sync_channel::<i32>(0);
+}
+
+#[test]
+fn try_send_full() {
+    let (tx, rx) = sync_channel::<i32>(2);
+    assert_eq!(tx.capacity(), 2);
+    assert!(tx.is_empty());
+    tx.try_send(1).unwrap();
+    tx.try_send(2).unwrap();
+    assert!(tx.is_full());
+    assert_eq!(tx.len(), 2);
+    assert_eq!(tx.try_send(3), Err(TrySendError::Full(3)));
+    assert_eq!(rx.try_recv(), Ok(1));
+    tx.try_send(3).unwrap();
+    assert_eq!(rx.try_recv(), Ok(2));
+    assert_eq!(rx.try_recv(), Ok(3));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+}
+
+#[test]
+fn wrap_around() {
+    let (tx, rx) = sync_channel::<usize>(3);
+    for i in 0..100 {
+        tx.send(i).unwrap();
+        if i % 2 == 1 {
+            tx.send(i).unwrap();
+            assert_eq!(rx.len(), 2);
+            assert_eq!(rx.recv(), Ok(i));
+        }
+        assert_eq!(rx.recv(), Ok(i));
+    }
+    assert!(rx.is_empty());
+}
+
+#[test]
+fn disconnect_senders() {
+    let (tx, rx) = sync_channel::<i32>(4);
+    let tx2 = tx.clone();
+    tx.send(1).unwrap();
+    drop(tx);
+    tx2.send(2).unwrap();
+    drop(tx2);
+    // Buffered messages can still be received.
+    assert_eq!(rx.recv(), Ok(1));
+    assert_eq!(rx.try_recv(), Ok(2));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+    assert_eq!(rx.recv(), Err(RecvError));
+}
+
+#[test]
+fn disconnect_receivers() {
+    let (tx, rx) = sync_channel::<i32>(4);
+    let rx2 = rx.clone();
+    drop(rx);
+    tx.send(1).unwrap();
+    drop(rx2);
+    assert_eq!(tx.send(2), Err(SendError(2)));
+    assert_eq!(tx.try_send(3), Err(TrySendError::Disconnected(3)));
+}
+
+#[test]
+fn drop_buffered() {
+    static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+    struct Elem;
+    impl Drop for Elem {
+        fn drop(&mut self) {
+            DROPS.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let (tx, rx) = sync_channel(5);
+    for _ in 0..7 {
+        tx.send(Elem).unwrap();
+        drop(rx.try_recv());
+    }
+    for _ in 0..3 {
+        tx.send(Elem).unwrap();
+    }
+    assert_eq!(DROPS.load(Ordering::SeqCst), 7);
+    drop((tx, rx));
+    assert_eq!(DROPS.load(Ordering::SeqCst), 10);
+}
+
+#[test]
+fn recv_timeout() {
+    let (tx, rx) = sync_channel::<i32>(1);
+    let start = Instant::now();
+    assert_eq!(rx.recv_timeout(Duration::from_millis(100)), Err(RecvTimeoutError::Timeout));
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    let t = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(10));
+        tx.send(1).unwrap();
+    });
+    assert_eq!(rx.recv_timeout(Duration::from_secs(30)), Ok(1));
+    t.join().unwrap();
+    assert_eq!(rx.recv_timeout(Duration::from_secs(30)), Err(RecvTimeoutError::Disconnected));
+}
+
+#[test]
+fn blocking_send() {
+    let (tx, rx) = sync_channel::<i32>(1);
+    tx.send(1).unwrap();
+    let t = thread::spawn(move || {
+        // Blocks until the receiver makes room.
+        tx.send(2).unwrap();
+    });
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(rx.recv(), Ok(1));
+    assert_eq!(rx.recv(), Ok(2));
+    t.join().unwrap();
+}
+
+#[test]
+fn blocked_sender_disconnect() {
+    let (tx, rx) = sync_channel::<i32>(1);
+    tx.send(1).unwrap();
+    let t = thread::spawn(move || tx.send(2));
+    thread::sleep(Duration::from_millis(10));
+    drop(rx);
+    assert_eq!(t.join().unwrap(), Err(SendError(2)));
+}
+
+#[test]
+fn blocked_receivers_disconnect() {
+    let (tx, rx) = sync_channel::<i32>(1);
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let rx = rx.clone();
+            thread::spawn(move || rx.recv())
+        })
+        .collect();
+    thread::sleep(Duration::from_millis(10));
+    drop(tx);
+    for t in threads {
+        assert_eq!(t.join().unwrap(), Err(RecvError));
+    }
+}
+
+#[test]
+fn iter() {
+    let (tx, rx) = sync_channel::<i32>(4);
+    let t = thread::spawn(move || {
+        for i in 0..10 {
+            tx.send(i).unwrap();
+        }
+    });
+    assert_eq!(rx.iter().collect::<Vec<_>>(), (0..10).collect::<Vec<_>>());
+    t.join().unwrap();
+
+    let (tx, rx) = sync_channel::<i32>(4);
+    tx.send(1).unwrap();
+    tx.send(2).unwrap();
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), [1, 2]);
+    drop(tx);
+    assert_eq!(rx.into_iter().next(), None);
+}
+
+#[test]
+fn stress() {
+    const THREADS: usize = 4;
+    const AMT: usize = 10000;
+
+    let (tx, rx) = sync_channel::<usize>(8);
+    let senders: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for i in 0..AMT {
+                    tx.send(t * AMT + i).unwrap();
+                }
+            })
+        })
+        .collect();
+    drop(tx);
+    let receivers: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let rx = rx.clone();
+            thread::spawn(move || rx.iter().collect::<Vec<_>>())
+        })
+        .collect();
+    drop(rx);
+
+    for t in senders {
+        t.join().unwrap();
+    }
+    // Every message is received exactly once, and the messages of each sender
+    // are received in order by every receiver.
+    let mut seen = vec![false; THREADS * AMT];
+    for t in receivers {
+        let received = t.join().unwrap();
+        let mut last = [None; THREADS];
+        for n in received {
+            assert!(!seen[n]);
+            seen[n] = true;
+            assert!(last[n / AMT].map_or(true, |last| last < n));
+            last[n / AMT] = Some(n);
+        }
+    }
+    assert!(seen.iter().all(|&seen| seen));
+}
@@ -0,0 +1,112 @@
+//! Queues of threads blocked on a channel operation.
+
+use crate::collections::VecDeque;
+use crate::sync::atomic::{AtomicBool, Ordering};
+use crate::sync::{Arc, Mutex};
+use crate::thread::{self, Thread};
+use crate::time::Instant;
+
+/// A thread blocked on a channel operation, and whether it was woken up.
+#[derive(Clone)]
+struct Context {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    thread: Thread,
+    woken: AtomicBool,
+}
+
+impl Context {
+    fn new() -> Context {
+        Context {
+            inner: Arc::new(Inner { thread: thread::current(), woken: AtomicBool::new(false) }),
+        }
+    }
+
+    fn wake(&self) {
+        self.inner.woken.store(true, Ordering::SeqCst);
+        self.inner.thread.unpark();
+    }
+
+    /// Parks the current thread until it is woken up or `deadline` passes.
+    fn wait_until(&self, deadline: Option<Instant>) {
+        while !self.inner.woken.load(Ordering::SeqCst) {
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return;
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+                None => thread::park(),
+            }
+        }
+    }
+}
+
+/// The threads waiting for one side of a channel to become ready.
+///
+/// A thread registers itself before checking one last time whether the
+/// channel is ready, and the other side checks for registered threads after
+/// making the channel ready. The sequentially consistent accesses on both
+/// sides guarantee that at least one of them sees the other, so no wakeup is
+/// lost.
+pub(super) struct Waker {
+    waiters: Mutex<VecDeque<Context>>,
+    // Whether `waiters` is empty, so that `notify` can skip the lock when
+    // nobody is waiting.
+    is_empty: AtomicBool,
+}
+
+impl Waker {
+    pub(super) fn new() -> Waker {
+        Waker { waiters: Mutex::new(VecDeque::new()), is_empty: AtomicBool::new(true) }
+    }
+
+    /// Blocks the current thread until it is woken up by `notify` or
+    /// `disconnect`, or until `deadline` passes. The thread does not block at
+    /// all if `is_ready` returns `true` after it was registered.
+    ///
+    /// Returning from this does not mean that the operation can proceed, so
+    /// callers have to retry it (and wait again) in a loop.
+    pub(super) fn wait_until(&self, deadline: Option<Instant>, is_ready: impl FnOnce() -> bool) {
+        let cx = Context::new();
+        {
+            let mut waiters = self.waiters.lock().unwrap();
+            waiters.push_back(cx.clone());
+            self.is_empty.store(false, Ordering::SeqCst);
+        }
+
+        if !is_ready() {
+            cx.wait_until(deadline);
+        }
+
+        // If we were woken up by `notify`, we have already been removed.
+        let mut waiters = self.waiters.lock().unwrap();
+        waiters.retain(|other| !Arc::ptr_eq(&other.inner, &cx.inner));
+        self.is_empty.store(waiters.is_empty(), Ordering::SeqCst);
+    }
+
+    /// Wakes up the thread that has been waiting the longest, if any.
+    pub(super) fn notify(&self) {
+        if !self.is_empty.load(Ordering::SeqCst) {
+            let mut waiters = self.waiters.lock().unwrap();
+            if let Some(cx) = waiters.pop_front() {
+                cx.wake();
+            }
+            self.is_empty.store(waiters.is_empty(), Ordering::SeqCst);
+        }
+    }
+
+    /// Wakes up all of the waiting threads, after the channel has been
+    /// disconnected.
+    pub(super) fn disconnect(&self) {
+        let mut waiters = self.waiters.lock().unwrap();
+        for cx in waiters.drain(..) {
+            cx.wake();
+        }
+        self.is_empty.store(true, Ordering::SeqCst);
+    }
+}
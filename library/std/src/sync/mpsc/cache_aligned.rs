@@ -2,10 +2,10 @@
 
 #[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(align(64))]
-pub(super) struct Aligner;
+pub(in crate::sync) struct Aligner;
 
 #[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(super) struct CacheAligned<T>(pub T, pub Aligner);
+pub(in crate::sync) struct CacheAligned<T>(pub T, pub Aligner);
 
 impl<T> Deref for CacheAligned<T> {
     type Target = T;
@@ -21,7 +21,7 @@ fn deref_mut(&mut self) -> &mut Self::Target {
 }
 
 impl<T> CacheAligned<T> {
-    pub(super) fn new(t: T) -> Self {
+    pub(in crate::sync) fn new(t: T) -> Self {
         CacheAligned(t, Aligner)
     }
 }
@@ -114,6 +114,9 @@
 #[cfg(all(test, not(target_os = "emscripten")))]
 mod sync_tests;
 
+#[cfg(all(test, not(target_os = "emscripten")))]
+mod select_tests;
+
 // A description of how Rust's channel implementation works
 //
 // Channels are supposed to be the basic building block for all other
@@ -259,7 +262,9 @@
 // believe that there is anything fundamental that needs to change about these
 // channels, however, in order to support a more efficient select().
 //
-// FIXME: Select is now removed, so these factors are ready to be cleaned up!
+// The `Select` type in select.rs drives this protocol over a set of receivers,
+// through the `can_recv`, `start_selection` and `abort_selection` methods of
+// each flavor.
 //
 // # Conclusion
 //
@@ -270,18 +275,23 @@
 use crate::error;
 use crate::fmt;
 use crate::mem;
+use crate::sync::mpsc::blocking::SignalToken;
 use crate::sync::Arc;
 use crate::time::{Duration, Instant};
 
+#[unstable(feature = "mpsc_select", issue = "none")]
+pub use self::select::{ReadyTimeoutError, Select, TryReadyError};
+
 mod blocking;
 mod mpsc_queue;
 mod oneshot;
+mod select;
 mod shared;
 mod spsc_queue;
 mod stream;
 mod sync;
 
-mod cache_aligned;
+pub(super) mod cache_aligned;
 
 /// The receiving half of Rust's [`channel`] (or [`sync_channel`]) type.
 /// This half can only be owned by one thread.
@@ -1408,6 +1418,71 @@ pub fn try_iter(&self) -> TryIter<'_, T> {
     }
 }
 
+impl<T> select::Packet for Receiver<T> {
+    fn can_recv(&self) -> bool {
+        loop {
+            let new_port = match *unsafe { self.inner() } {
+                Flavor::Oneshot(ref p) => match p.can_recv() {
+                    Ok(ret) => return ret,
+                    Err(upgrade) => upgrade,
+                },
+                Flavor::Stream(ref p) => match p.can_recv() {
+                    Ok(ret) => return ret,
+                    Err(upgrade) => upgrade,
+                },
+                Flavor::Shared(ref p) => return p.can_recv(),
+                Flavor::Sync(ref p) => return p.can_recv(),
+            };
+            unsafe {
+                mem::swap(self.inner_mut(), new_port.inner_mut());
+            }
+        }
+    }
+
+    fn start_selection(&self, mut token: SignalToken) -> select::StartResult {
+        loop {
+            let (t, new_port) = match *unsafe { self.inner() } {
+                Flavor::Oneshot(ref p) => match p.start_selection(token) {
+                    oneshot::SelSuccess => return select::StartResult::Installed,
+                    oneshot::SelCanceled => return select::StartResult::Abort,
+                    oneshot::SelUpgraded(t, rx) => (t, rx),
+                },
+                Flavor::Stream(ref p) => match p.start_selection(token) {
+                    stream::SelSuccess => return select::StartResult::Installed,
+                    stream::SelCanceled => return select::StartResult::Abort,
+                    stream::SelUpgraded(t, rx) => (t, rx),
+                },
+                Flavor::Shared(ref p) => return p.start_selection(token),
+                Flavor::Sync(ref p) => return p.start_selection(token),
+            };
+            token = t;
+            unsafe {
+                mem::swap(self.inner_mut(), new_port.inner_mut());
+            }
+        }
+    }
+
+    fn abort_selection(&self) -> bool {
+        let mut was_upgrade = false;
+        loop {
+            let result = match *unsafe { self.inner() } {
+                Flavor::Oneshot(ref p) => p.abort_selection(),
+                Flavor::Stream(ref p) => p.abort_selection(was_upgrade),
+                Flavor::Shared(ref p) => return p.abort_selection(was_upgrade),
+                Flavor::Sync(ref p) => return p.abort_selection(),
+            };
+            let new_port = match result {
+                Ok(b) => return b,
+                Err(p) => p,
+            };
+            was_upgrade = true;
+            unsafe {
+                mem::swap(self.inner_mut(), new_port.inner_mut());
+            }
+        }
+    }
+}
+
 #[stable(feature = "rust1", since = "1.0.0")]
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = T;
@@ -0,0 +1,355 @@
+//! Selection over a set of receivers.
+//!
+//! Selecting is built on the blocking protocol of the channel flavors: the
+//! selecting thread installs the same `SignalToken` in every receiver it is
+//! waiting on, and whichever sender (or disconnection) fires first wakes it
+//! up. Afterwards the token is taken back out of all of the receivers with
+//! `abort_selection`, which also reports whether each of them is ready.
+
+use crate::error;
+use crate::fmt;
+use crate::sync::mpsc::blocking;
+use crate::sync::mpsc::blocking::SignalToken;
+use crate::time::{Duration, Instant};
+
+/// Waits on a set of [`Receiver`]s until one of them is ready.
+///
+/// A receiver is *ready* when receiving on it would not block, that is, when
+/// a message is available or when all of its senders have hung up. Selecting
+/// only reports which receiver is ready; the message is then taken out of it
+/// with [`Receiver::recv`], which returns right away for a ready receiver.
+///
+/// Receivers are added with [`recv`], which returns the index used to refer
+/// to that receiver in the results of [`ready`], [`try_ready`],
+/// [`ready_timeout`] and [`ready_deadline`]. If several receivers are ready
+/// at once, successive selections start looking at different receivers, so
+/// that a busy receiver cannot starve the others.
+///
+/// [`Receiver`]: super::Receiver
+/// [`Receiver::recv`]: super::Receiver::recv
+/// [`recv`]: Select::recv
+/// [`ready`]: Select::ready
+/// [`try_ready`]: Select::try_ready
+/// [`ready_timeout`]: Select::ready_timeout
+/// [`ready_deadline`]: Select::ready_deadline
+///
+/// # Examples
+///
+/// ```
+/// #![feature(mpsc_select)]
+/// use std::sync::mpsc::{channel, Select};
+/// use std::thread;
+///
+/// let (tx1, rx1) = channel::<i32>();
+/// let (tx2, rx2) = channel::<&str>();
+///
+/// thread::spawn(move || {
+///     tx2.send("hello").unwrap();
+///     drop(tx1);
+/// });
+///
+/// let mut sel = Select::new();
+/// let numbers = sel.recv(&rx1);
+/// let words = sel.recv(&rx2);
+///
+/// let mut got_word = false;
+/// let mut numbers_closed = false;
+/// while !(got_word && numbers_closed) {
+///     let index = sel.ready();
+///     if index == numbers {
+///         assert!(rx1.recv().is_err());
+///         numbers_closed = true;
+///         sel.remove(numbers);
+///     } else if index == words {
+///         assert_eq!(rx2.recv(), Ok("hello"));
+///         got_word = true;
+///         sel.remove(words);
+///     }
+/// }
+/// ```
+#[unstable(feature = "mpsc_select", issue = "none")]
+pub struct Select<'a> {
+    // Removed receivers leave a `None` behind so that indices stay stable.
+    receivers: Vec<Option<&'a dyn Packet>>,
+    // Index of the receiver that the next selection looks at first.
+    next_start: usize,
+}
+
+/// An error returned from [`Select::try_ready`] when none of the receivers
+/// were ready.
+#[unstable(feature = "mpsc_select", issue = "none")]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct TryReadyError;
+
+/// An error returned from [`Select::ready_timeout`] and
+/// [`Select::ready_deadline`] when none of the receivers became ready in
+/// time.
+#[unstable(feature = "mpsc_select", issue = "none")]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct ReadyTimeoutError;
+
+// Result of installing a selecting thread's token in a receiver.
+#[derive(PartialEq, Eq)]
+pub enum StartResult {
+    Installed,
+    Abort,
+}
+
+// The selection interface of a receiver, independent of its message type.
+// This is implemented for `Receiver<T>` in the parent module.
+pub trait Packet {
+    // Returns whether receiving would not block right now.
+    fn can_recv(&self) -> bool;
+
+    // Installs `token` to be signaled when the receiver becomes ready, or
+    // returns `Abort` if it already is.
+    fn start_selection(&self, token: SignalToken) -> StartResult;
+
+    // Takes the token of a successful `start_selection` back out of the
+    // receiver, returning whether it is ready.
+    fn abort_selection(&self) -> bool;
+}
+
+impl<'a> Select<'a> {
+    /// Creates a new, empty selection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::Select;
+    ///
+    /// let mut sel = Select::new();
+    /// assert!(sel.try_ready().is_err());
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn new() -> Select<'a> {
+        Select { receivers: Vec::new(), next_start: 0 }
+    }
+
+    /// Adds a receiver to the selection and returns its index.
+    ///
+    /// The receiver stays borrowed for as long as the selection exists, but
+    /// it can still be received on in the meantime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the receiver is already in the selection.
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn recv<T>(&mut self, rx: &'a super::Receiver<T>) -> usize {
+        let ptr = rx as *const super::Receiver<T> as *const u8;
+        let present = self
+            .receivers
+            .iter()
+            .flatten()
+            .any(|&other| other as *const dyn Packet as *const u8 == ptr);
+        assert!(!present, "receiver is already in the selection");
+        self.receivers.push(Some(rx));
+        self.receivers.len() - 1
+    }
+
+    /// Removes the receiver with the given index from the selection.
+    ///
+    /// Indices of the other receivers are not affected. This is typically
+    /// used to stop selecting on a receiver whose senders have hung up, which
+    /// would otherwise always be ready.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` was not returned by [`recv`] on this selection, or if
+    /// the receiver has already been removed.
+    ///
+    /// [`recv`]: Select::recv
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn remove(&mut self, index: usize) {
+        let removed = self.receivers.get_mut(index).and_then(Option::take);
+        assert!(removed.is_some(), "no receiver with index {} in the selection", index);
+    }
+
+    /// Blocks until one of the receivers is ready, and returns its index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no receivers in the selection, as this would
+    /// block forever.
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn ready(&mut self) -> usize {
+        assert!(self.receivers.iter().any(Option::is_some), "no receivers to select on");
+        self.select(None).unwrap()
+    }
+
+    /// Returns the index of a receiver that is ready, without blocking.
+    ///
+    /// If none of the receivers is ready, or if there are no receivers in the
+    /// selection, this returns an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::{channel, Select};
+    ///
+    /// let (tx, rx) = channel();
+    /// let mut sel = Select::new();
+    /// let index = sel.recv(&rx);
+    /// assert!(sel.try_ready().is_err());
+    ///
+    /// tx.send(1).unwrap();
+    /// assert_eq!(sel.try_ready(), Ok(index));
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn try_ready(&mut self) -> Result<usize, TryReadyError> {
+        self.poll().ok_or(TryReadyError)
+    }
+
+    /// Blocks until one of the receivers is ready, for at most `timeout`, and
+    /// returns its index.
+    ///
+    /// If there are no receivers in the selection, this waits for the whole
+    /// `timeout` and then returns an error. A `timeout` too large to compute
+    /// its deadline is treated as waiting indefinitely, in which case the
+    /// error is returned right away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// #![feature(mpsc_select)]
+    /// use std::sync::mpsc::{channel, ReadyTimeoutError, Select};
+    /// use std::time::Duration;
+    ///
+    /// let (_tx, rx) = channel::<i32>();
+    /// let mut sel = Select::new();
+    /// sel.recv(&rx);
+    /// assert_eq!(sel.ready_timeout(Duration::from_millis(10)), Err(ReadyTimeoutError));
+    /// ```
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn ready_timeout(&mut self, timeout: Duration) -> Result<usize, ReadyTimeoutError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.ready_deadline(deadline),
+            // So far in the future that it's practically the same as waiting
+            // indefinitely.
+            None => self.select(None).ok_or(ReadyTimeoutError),
+        }
+    }
+
+    /// Blocks until one of the receivers is ready, or until `deadline` is
+    /// reached, and returns its index.
+    ///
+    /// If there are no receivers in the selection, this waits until the
+    /// `deadline` and then returns an error.
+    #[unstable(feature = "mpsc_select", issue = "none")]
+    pub fn ready_deadline(&mut self, deadline: Instant) -> Result<usize, ReadyTimeoutError> {
+        self.select(Some(deadline)).ok_or(ReadyTimeoutError)
+    }
+
+    // Iterates over the receivers, starting with the one at `next_start`.
+    fn rotated(&self) -> impl Iterator<Item = (usize, &'a dyn Packet)> + '_ {
+        let (front, back) = self.receivers.split_at(self.next_start);
+        back.iter()
+            .chain(front)
+            .enumerate()
+            .filter_map(move |(i, rx)| Some(((i + self.next_start) % self.receivers.len(), (*rx)?)))
+    }
+
+    // Records that the receiver at `index` was selected, so that the next
+    // selection starts with the receiver after it.
+    fn selected(&mut self, index: usize) -> usize {
+        self.next_start = (index + 1) % self.receivers.len();
+        index
+    }
+
+    // Checks all of the receivers once, without blocking.
+    fn poll(&mut self) -> Option<usize> {
+        let index = self.rotated().find(|(_, rx)| rx.can_recv()).map(|(i, _)| i)?;
+        Some(self.selected(index))
+    }
+
+    // Body of the blocking selections. Returns `None` if `deadline` passed
+    // before any of the receivers became ready, or if there is no deadline
+    // and no receivers to wait for.
+    fn select(&mut self, deadline: Option<Instant>) -> Option<usize> {
+        // Optimistic preflight check, blocking is expensive.
+        if let Some(index) = self.poll() {
+            return Some(index);
+        }
+
+        let (wait_token, signal_token) = blocking::tokens();
+        let mut installed = Vec::with_capacity(self.receivers.len());
+        let mut canceled = None;
+        for (i, rx) in self.rotated() {
+            match rx.start_selection(signal_token.clone()) {
+                StartResult::Installed => installed.push(rx),
+                StartResult::Abort => {
+                    canceled = Some(i);
+                    break;
+                }
+            }
+        }
+        drop(signal_token);
+        if let Some(index) = canceled {
+            // Take our token back out of the receivers that we have already
+            // started selecting on.
+            for rx in installed {
+                rx.abort_selection();
+            }
+            return Some(self.selected(index));
+        }
+        if installed.is_empty() && deadline.is_none() {
+            // Without any receivers, nothing can wake us up.
+            return None;
+        }
+
+        match deadline {
+            Some(deadline) => {
+                wait_token.wait_max_until(deadline);
+            }
+            None => wait_token.wait(),
+        }
+
+        // Whether we were woken up or timed out, all of the receivers need to
+        // give back our token. A receiver may have become ready just as we
+        // timed out, in which case we report it rather than the timeout.
+        let mut ready = None;
+        for (i, rx) in self.rotated() {
+            if rx.abort_selection() && ready.is_none() {
+                ready = Some(i);
+            }
+        }
+        debug_assert!(ready.is_some() || deadline.is_some());
+        Some(self.selected(ready?))
+    }
+}
+
+#[unstable(feature = "mpsc_select", issue = "none")]
+impl Default for Select<'_> {
+    fn default() -> Self {
+        Select::new()
+    }
+}
+
+#[unstable(feature = "mpsc_select", issue = "none")]
+impl fmt::Debug for Select<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Select").finish()
+    }
+}
+
+#[unstable(feature = "mpsc_select", issue = "none")]
+impl fmt::Display for TryReadyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "no receiver is ready".fmt(f)
+    }
+}
+
+#[unstable(feature = "mpsc_select", issue = "none")]
+impl error::Error for TryReadyError {}
+
+#[unstable(feature = "mpsc_select", issue = "none")]
+impl fmt::Display for ReadyTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "timed out waiting for a receiver to become ready".fmt(f)
+    }
+}
+
+#[unstable(feature = "mpsc_select", issue = "none")]
+impl error::Error for ReadyTimeoutError {}
@@ -0,0 +1,225 @@
+use super::*;
+use crate::thread;
+use crate::time::{Duration, Instant};
+
+#[test]
+fn smoke() {
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = channel::<i32>();
+    let mut sel = Select::new();
+    let i1 = sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    assert_ne!(i1, i2);
+
+    tx1.send(1).unwrap();
+    assert_eq!(sel.ready(), i1);
+    assert_eq!(rx1.try_recv(), Ok(1));
+    tx2.send(2).unwrap();
+    assert_eq!(sel.ready(), i2);
+    assert_eq!(rx2.try_recv(), Ok(2));
+
+    drop(tx1);
+    assert_eq!(sel.ready(), i1);
+    assert_eq!(rx1.try_recv(), Err(TryRecvError::Disconnected));
+    sel.remove(i1);
+    drop(tx2);
+    assert_eq!(sel.ready(), i2);
+    assert_eq!(rx2.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn try_ready() {
+    let mut sel = Select::new();
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = sync_channel::<i32>(1);
+    let i1 = sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+    tx2.send(2).unwrap();
+    assert_eq!(sel.try_ready(), Ok(i2));
+    assert_eq!(rx2.try_recv(), Ok(2));
+    assert_eq!(sel.try_ready(), Err(TryReadyError));
+    drop(tx1);
+    assert_eq!(sel.try_ready(), Ok(i1));
+}
+
+#[test]
+fn ready_timeout() {
+    let (tx1, rx1) = channel::<i32>();
+    let (_tx2, rx2) = sync_channel::<i32>(0);
+    let mut sel = Select::new();
+    sel.recv(&rx1);
+    sel.recv(&rx2);
+
+    let start = Instant::now();
+    assert_eq!(sel.ready_timeout(Duration::from_millis(100)), Err(ReadyTimeoutError));
+    assert!(start.elapsed() >= Duration::from_millis(100));
+
+    // The receivers can still be used after the selection timed out.
+    tx1.send(1).unwrap();
+    assert_eq!(rx1.recv_timeout(Duration::from_millis(100)), Ok(1));
+}
+
+#[test]
+fn ready_timeout_empty() {
+    let mut sel = Select::new();
+    let start = Instant::now();
+    assert_eq!(sel.ready_deadline(start + Duration::from_millis(50)), Err(ReadyTimeoutError));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+}
+
+#[test]
+fn ready_timeout_empty_max() {
+    let mut sel = Select::new();
+    assert_eq!(sel.ready_timeout(Duration::MAX), Err(ReadyTimeoutError));
+
+    let (tx, rx) = channel::<i32>();
+    let index = sel.recv(&rx);
+    tx.send(1).unwrap();
+    assert_eq!(sel.ready_timeout(Duration::MAX), Ok(index));
+}
+
+#[test]
+#[should_panic(expected = "no receivers to select on")]
+fn ready_empty() {
+    Select::new().ready();
+}
+
+#[test]
+#[should_panic(expected = "receiver is already in the selection")]
+fn recv_twice() {
+    let (_tx, rx) = channel::<i32>();
+    let mut sel = Select::new();
+    sel.recv(&rx);
+    sel.recv(&rx);
+}
+
+#[test]
+fn blocking() {
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = sync_channel::<i32>(0);
+    let (tx3, rx3) = channel::<()>();
+    let t = thread::spawn(move || {
+        rx3.recv().unwrap();
+        tx2.send(2).unwrap();
+        rx3.recv().unwrap();
+        tx1.send(1).unwrap();
+        rx3.recv().unwrap();
+    });
+
+    let mut sel = Select::new();
+    let i1 = sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    tx3.send(()).unwrap();
+    assert_eq!(sel.ready(), i2);
+    assert_eq!(rx2.recv(), Ok(2));
+    tx3.send(()).unwrap();
+    assert_eq!(sel.ready_timeout(Duration::from_secs(30)), Ok(i1));
+    assert_eq!(rx1.recv(), Ok(1));
+    tx3.send(()).unwrap();
+    t.join().unwrap();
+
+    // Both senders are gone now.
+    assert!(sel.try_ready().is_ok());
+}
+
+#[test]
+fn upgrades() {
+    // Upgrade a oneshot channel to a stream channel, and then to a shared
+    // channel, while the selecting thread is blocked on it.
+    let (tx1, rx1) = channel::<i32>();
+    let (_tx2, rx2) = channel::<i32>();
+    let (tx3, rx3) = channel::<()>();
+    let t = thread::spawn(move || {
+        tx1.send(1).unwrap();
+        rx3.recv().unwrap();
+        tx1.send(2).unwrap();
+        rx3.recv().unwrap();
+        let tx1_2 = tx1.clone();
+        tx1_2.send(3).unwrap();
+        rx3.recv().unwrap();
+        drop(tx1);
+        drop(tx1_2);
+    });
+
+    let mut sel = Select::new();
+    let i1 = sel.recv(&rx1);
+    sel.recv(&rx2);
+    for i in 1..=3 {
+        assert_eq!(sel.ready(), i1);
+        assert_eq!(rx1.recv(), Ok(i));
+        tx3.send(()).unwrap();
+    }
+    assert_eq!(sel.ready(), i1);
+    assert_eq!(rx1.recv(), Err(RecvError));
+    t.join().unwrap();
+}
+
+#[test]
+fn fairness() {
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = channel::<i32>();
+    let mut sel = Select::new();
+    let i1 = sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    for i in 0..10 {
+        tx1.send(i).unwrap();
+        tx2.send(i).unwrap();
+    }
+
+    // With both receivers always ready, neither of them gets starved.
+    let mut counts = [0; 2];
+    for _ in 0..10 {
+        let index = sel.ready();
+        counts[index] += 1;
+        if index == i1 {
+            rx1.recv().unwrap();
+        } else {
+            rx2.recv().unwrap();
+        }
+    }
+    assert_eq!(counts[i1], 5);
+    assert_eq!(counts[i2], 5);
+}
+
+#[test]
+fn stress() {
+    const AMT: i32 = 10000;
+    let (tx1, rx1) = channel::<i32>();
+    let (tx2, rx2) = sync_channel::<i32>(1);
+    let t1 = thread::spawn(move || {
+        for i in 0..AMT {
+            tx1.send(i).unwrap();
+        }
+    });
+    let t2 = thread::spawn(move || {
+        for i in 0..AMT {
+            tx2.send(i).unwrap();
+        }
+    });
+
+    let mut sel = Select::new();
+    let i1 = sel.recv(&rx1);
+    let i2 = sel.recv(&rx2);
+    let (mut next1, mut next2) = (0, 0);
+    while next1 < AMT || next2 < AMT {
+        let index = sel.ready();
+        if index == i1 {
+            assert_eq!(rx1.recv(), Ok(next1));
+            next1 += 1;
+            if next1 == AMT {
+                sel.remove(i1);
+            }
+        } else {
+            assert_eq!(rx2.recv(), Ok(next2));
+            next2 += 1;
+            if next2 == AMT {
+                sel.remove(i2);
+            }
+        }
+    }
+    t1.join().unwrap();
+    t2.join().unwrap();
+}
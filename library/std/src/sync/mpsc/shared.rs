@@ -8,7 +8,7 @@
 /// module. You'll also note that the implementation of the shared and stream
 /// channels are quite similar, and this is no coincidence!
 pub use self::Failure::*;
-use self::StartResult::*;
+use crate::sync::mpsc::select::StartResult::{self, *};
 
 use core::cmp;
 use core::intrinsics::abort;
@@ -54,12 +54,6 @@ pub enum Failure {
     Disconnected,
 }
 
-#[derive(PartialEq, Eq)]
-enum StartResult {
-    Installed,
-    Abort,
-}
-
 impl<T> Packet<T> {
     // Creation of a packet *must* be followed by a call to postinit_lock
     // and later by inherit_blocker
@@ -420,6 +414,12 @@ fn take_to_wake(&self) -> SignalToken {
     // select implementation
     ////////////////////////////////////////////////////////////////////////////
 
+    // Tests to see whether this port can receive without blocking.
+    pub fn can_recv(&self) -> bool {
+        let cnt = self.cnt.load(Ordering::SeqCst);
+        cnt == DISCONNECTED || cnt - unsafe { *self.steals.get() } > 0
+    }
+
     // increment the count on the channel (used for selection)
     fn bump(&self, amt: isize) -> isize {
         match self.cnt.fetch_add(amt, Ordering::SeqCst) {
@@ -431,6 +431,22 @@ fn bump(&self, amt: isize) -> isize {
         }
     }
 
+    // Inserts the signal token for selection on this port, returning true if
+    // blocking should proceed.
+    //
+    // The code here is the same as in stream.rs, except that it doesn't need to
+    // peek at the channel to see if an upgrade is pending.
+    pub fn start_selection(&self, token: SignalToken) -> StartResult {
+        match self.decrement(token) {
+            Installed => Installed,
+            Abort => {
+                let prev = self.bump(1);
+                assert!(prev == DISCONNECTED || prev >= 0);
+                Abort
+            }
+        }
+    }
+
     // Cancels a previous thread waiting on this port, returning whether there's
     // data on the port.
     //
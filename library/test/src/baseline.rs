@@ -0,0 +1,182 @@
+//! Module `baseline` contains everything related to the benchmark baselines,
+//! saved with `--save-baseline` and compared with by `--baseline`.
+//!
+//! A baseline is saved next to the test executable, in
+//! `bench-baselines/<executable name>/<baseline name>`. Each line of the file holds
+//! the samples of one benchmark: the MB/s, the fields of its `stats::Summary` and
+//! then its name.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use super::bench::BenchSamples;
+use super::stats::Summary;
+
+/// The results of the benchmarks of a baseline, by name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Baseline(BTreeMap<String, BenchSamples>);
+
+impl Baseline {
+    pub fn new() -> Baseline {
+        Baseline(BTreeMap::new())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BenchSamples> {
+        self.0.get(name)
+    }
+
+    pub fn insert(&mut self, name: &str, samples: BenchSamples) {
+        self.0.insert(name.to_owned(), samples);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Loads the baseline named `name`.
+    pub fn load(name: &str) -> io::Result<Baseline> {
+        let path = baseline_path(name)?;
+        let with_context = |e: io::Error| {
+            io::Error::new(
+                e.kind(),
+                format!("couldn't load baseline `{}` from {}: {}", name, path.display(), e),
+            )
+        };
+
+        let contents = fs::read_to_string(&path).map_err(with_context)?;
+        Baseline::parse(&contents)
+            .map_err(|e| with_context(io::Error::new(io::ErrorKind::InvalidData, e)))
+    }
+
+    /// Saves the results as the baseline named `name`. The benchmarks of the
+    /// previous baseline with that name which aren't in `self` are kept.
+    pub fn save(&self, name: &str) -> io::Result<()> {
+        let path = baseline_path(name)?;
+        let with_context = |e: io::Error| {
+            io::Error::new(
+                e.kind(),
+                format!("couldn't save baseline `{}` to {}: {}", name, path.display(), e),
+            )
+        };
+
+        let mut baseline = match fs::read_to_string(&path) {
+            Ok(contents) => Baseline::parse(&contents).unwrap_or_default(),
+            Err(_) => Baseline::new(),
+        };
+        baseline.0.extend(self.0.iter().map(|(name, bs)| (name.clone(), bs.clone())));
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(with_context)?;
+        }
+        fs::write(&path, baseline.serialize()).map_err(with_context)
+    }
+
+    pub fn parse(contents: &str) -> Result<Baseline, String> {
+        let mut baseline = Baseline::new();
+        for (i, line) in contents.lines().enumerate() {
+            let invalid = || format!("invalid benchmark results on line {}", i + 1);
+
+            let mut fields = line.splitn(16, ' ');
+            let mb_s = fields.next().and_then(|f| f.parse().ok()).ok_or_else(invalid)?;
+            let mut values = [0.0; 14];
+            for value in &mut values {
+                *value = fields.next().and_then(|f| f.parse().ok()).ok_or_else(invalid)?;
+            }
+            let name = fields.next().filter(|name| !name.is_empty()).ok_or_else(invalid)?;
+
+            let v = values;
+            let ns_iter_summ = Summary {
+                sum: v[0],
+                min: v[1],
+                max: v[2],
+                mean: v[3],
+                median: v[4],
+                var: v[5],
+                std_dev: v[6],
+                std_dev_pct: v[7],
+                median_abs_dev: v[8],
+                median_abs_dev_pct: v[9],
+                quartiles: (v[10], v[11], v[12]),
+                iqr: v[13],
+            };
+            baseline.insert(name, BenchSamples { ns_iter_summ, mb_s });
+        }
+        Ok(baseline)
+    }
+
+    pub fn serialize(&self) -> String {
+        let mut contents = String::new();
+        for (name, bs) in &self.0 {
+            let s = &bs.ns_iter_summ;
+            let (q1, q2, q3) = s.quartiles;
+            let values = [
+                s.sum,
+                s.min,
+                s.max,
+                s.mean,
+                s.median,
+                s.var,
+                s.std_dev,
+                s.std_dev_pct,
+                s.median_abs_dev,
+                s.median_abs_dev_pct,
+                q1,
+                q2,
+                q3,
+                s.iqr,
+            ];
+            contents.push_str(&bs.mb_s.to_string());
+            for value in &values {
+                contents.push_str(&format!(" {}", value));
+            }
+            contents.push_str(&format!(" {}\n", name));
+        }
+        contents
+    }
+}
+
+fn baseline_path(name: &str) -> io::Result<PathBuf> {
+    let exe = env::current_exe()?;
+    let exe_name = exe.file_stem().unwrap_or_default();
+    let dir = exe.parent().map(PathBuf::from).unwrap_or_default();
+    Ok(dir.join("bench-baselines").join(exe_name).join(name))
+}
+
+/// How the results of a benchmark changed from its baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BenchChange {
+    Improved,
+    Regressed,
+    /// The difference between the results isn't significant.
+    NoChange,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BenchComparison {
+    pub baseline_median: f64,
+    /// The change of the median, in percent of the baseline.
+    pub change_pct: f64,
+    pub change: BenchChange,
+}
+
+/// Compares the results of a benchmark with its baseline. The change is
+/// significant if the interquartile ranges of the samples don't overlap, i.e.
+/// if three quarters of the new samples are faster (or slower) than three
+/// quarters of the baseline samples.
+pub fn compare(baseline: &BenchSamples, new: &BenchSamples) -> BenchComparison {
+    let (old, new) = (&baseline.ns_iter_summ, &new.ns_iter_summ);
+    let change = if new.quartiles.2 < old.quartiles.0 {
+        BenchChange::Improved
+    } else if new.quartiles.0 > old.quartiles.2 {
+        BenchChange::Regressed
+    } else {
+        BenchChange::NoChange
+    };
+    let change_pct =
+        if old.median > 0.0 { (new.median - old.median) / old.median * 100.0 } else { 0.0 };
+
+    BenchComparison { baseline_median: old.median, change_pct, change }
+}
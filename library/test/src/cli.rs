@@ -2,8 +2,9 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
 
-use super::helpers::isatty;
+use super::helpers::{isatty, shuffle};
 use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
 use super::time::TestTimeOptions;
 
@@ -23,7 +24,13 @@ pub struct TestOpts {
     pub format: OutputFormat,
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
+    pub shard_index: usize,
+    pub shard_count: usize,
+    pub shuffle_seed: Option<u64>,
     pub time_options: Option<TestTimeOptions>,
+    pub timeout: Option<Duration>,
+    pub save_baseline: Option<String>,
+    pub baseline: Option<String>,
     pub options: Options,
 }
 
@@ -86,6 +93,25 @@ fn optgroups() -> getopts::Options {
              Alias to --format=terse",
         )
         .optflag("", "exact", "Exactly match filters rather than by substring")
+        .optopt(
+            "",
+            "shard-count",
+            "Split the tests into N shards, and only run the shard given by --shard-index",
+            "N",
+        )
+        .optopt(
+            "",
+            "shard-index",
+            "Index of the shard to run, from 0 to the --shard-count minus one",
+            "INDEX",
+        )
+        .optflagopt(
+            "",
+            "shuffle",
+            "Run the tests in a random order. The order is determined by SEED,
+            which is random if not given, and printed to reproduce the order",
+            "SEED",
+        )
         .optopt(
             "",
             "color",
@@ -101,8 +127,9 @@ fn optgroups() -> getopts::Options {
             "Configure formatting of output:
             pretty = Print verbose output;
             terse  = Display one character per test;
-            json   = Output a json document",
-            "pretty|terse|json",
+            json   = Output a json document
+            junit  = Output a JUnit document",
+            "pretty|terse|json|junit",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -143,6 +170,30 @@ fn optgroups() -> getopts::Options {
 
             `CRITICAL_TIME` here means the limit that should not be exceeded by test.
             ",
+        )
+        .optopt(
+            "",
+            "timeout",
+            "Kill the tests which are still running after SECONDS, and report them as failed.
+
+            To be killed, the tests run in their own process instead of a thread,
+            unless --force-run-in-process is given.",
+            "SECONDS",
+        )
+        .optopt(
+            "",
+            "save-baseline",
+            "Save the results of the benchmarks as the baseline NAME,
+            to compare later results with them.",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "Compare the results of the benchmarks with the baseline NAME,
+            saved by a previous run with --save-baseline NAME, and
+            report the significant changes.",
+            "NAME",
         );
     opts
 }
@@ -209,7 +260,7 @@ pub fn parse_opts(args: &[String]) -> Option<OptRes> {
 
 // Gets the option value and checks if unstable features are enabled.
 macro_rules! unstable_optflag {
-    ($matches:ident, $allow_unstable:ident, $option_name:literal) => {{
+    ($matches:ident, $allow_unstable:ident, $option_name:expr) => {{
         let opt = $matches.opt_present($option_name);
         if !$allow_unstable && opt {
             return Err(format!(
@@ -232,6 +283,11 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
     let exclude_should_panic = unstable_optflag!(matches, allow_unstable, "exclude-should-panic");
     let include_ignored = unstable_optflag!(matches, allow_unstable, "include-ignored");
     let time_options = get_time_options(&matches, allow_unstable)?;
+    let timeout = get_timeout(&matches, allow_unstable)?;
+    let save_baseline = get_baseline_name(&matches, allow_unstable, "save-baseline")?;
+    let baseline = get_baseline_name(&matches, allow_unstable, "baseline")?;
+    let (shard_index, shard_count) = get_shard(&matches, allow_unstable)?;
+    let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
 
     let quiet = matches.opt_present("quiet");
     let exact = matches.opt_present("exact");
@@ -266,7 +322,13 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         format,
         test_threads,
         skip,
+        shard_index,
+        shard_count,
+        shuffle_seed,
         time_options,
+        timeout,
+        save_baseline,
+        baseline,
         options,
     };
 
@@ -307,6 +369,103 @@ fn get_time_options(
     Ok(options)
 }
 
+// Gets the time after which tests are killed.
+fn get_timeout(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<Option<Duration>> {
+    if !unstable_optflag!(matches, allow_unstable, "timeout") {
+        return Ok(None);
+    }
+
+    let timeout = match matches.opt_str("timeout").unwrap().parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Duration::from_secs_f64(secs),
+        Ok(secs) => {
+            return Err(format!("argument for --timeout must be a duration > 0 (was {})", secs));
+        }
+        Err(e) => {
+            return Err(format!(
+                "argument for --timeout must be a number of seconds (error: {})",
+                e
+            ));
+        }
+    };
+
+    Ok(Some(timeout))
+}
+
+// Gets the name of a benchmark baseline, which is used as a file name.
+fn get_baseline_name(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+    option: &str,
+) -> OptPartRes<Option<String>> {
+    if !unstable_optflag!(matches, allow_unstable, option) {
+        return Ok(None);
+    }
+
+    let name = matches.opt_str(option).unwrap();
+    if name.is_empty() || name.starts_with('.') || name.contains(&['/', '\\'][..]) {
+        return Err(format!(
+            "argument for --{} must be a name, not starting with `.` \
+             and without path separators (was {})",
+            option, name
+        ));
+    }
+
+    Ok(Some(name))
+}
+
+// Gets the shard index and count, which default to a single shard.
+fn get_shard(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<(usize, usize)> {
+    let has_index = unstable_optflag!(matches, allow_unstable, "shard-index");
+    let has_count = unstable_optflag!(matches, allow_unstable, "shard-count");
+    if has_index != has_count {
+        return Err("the options --shard-index and --shard-count must be used together".into());
+    }
+    if !has_count {
+        return Ok((0, 1));
+    }
+
+    let shard_count = match matches.opt_str("shard-count").unwrap().parse::<usize>() {
+        Ok(0) => return Err("argument for --shard-count must not be 0".to_string()),
+        Ok(n) => n,
+        Err(e) => {
+            return Err(format!("argument for --shard-count must be a number > 0 (error: {})", e));
+        }
+    };
+    let shard_index = match matches.opt_str("shard-index").unwrap().parse::<usize>() {
+        Ok(n) if n < shard_count => n,
+        Ok(n) => {
+            return Err(format!(
+                "argument for --shard-index must be less than the shard count {} (was {})",
+                shard_count, n
+            ));
+        }
+        Err(e) => {
+            return Err(format!("argument for --shard-index must be a number (error: {})", e));
+        }
+    };
+
+    Ok((shard_index, shard_count))
+}
+
+// Gets the seed to shuffle the tests with, picking one if `--shuffle` is given without a seed.
+fn get_shuffle_seed(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<Option<u64>> {
+    if !unstable_optflag!(matches, allow_unstable, "shuffle") {
+        return Ok(None);
+    }
+
+    let seed = match matches.opt_str("shuffle") {
+        Some(seed) => match seed.parse::<u64>() {
+            Ok(seed) => seed,
+            Err(e) => {
+                return Err(format!("argument for --shuffle must be a number (error: {})", e));
+            }
+        },
+        None => shuffle::random_seed(),
+    };
+
+    Ok(Some(seed))
+}
+
 fn get_test_threads(matches: &getopts::Matches) -> OptPartRes<Option<usize>> {
     let test_threads = match matches.opt_str("test-threads") {
         Some(n_str) => match n_str.parse::<usize>() {
@@ -341,10 +500,16 @@ fn get_format(
             }
             OutputFormat::Json
         }
+        Some("junit") => {
+            if !allow_unstable {
+                return Err("The \"junit\" format is only accepted on the nightly compiler".into());
+            }
+            OutputFormat::Junit
+        }
 
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, or json (was \
+                "argument for --format must be pretty, terse, json or junit (was \
                  {})",
                 v
             ));
@@ -6,11 +6,12 @@
 use std::time::Instant;
 
 use super::{
-    bench::fmt_bench_samples,
+    baseline::{compare, Baseline, BenchComparison},
+    bench::{fmt_bench_samples, BenchSamples},
     cli::TestOpts,
     event::{CompletedTest, TestEvent},
     filter_tests,
-    formatters::{JsonFormatter, OutputFormatter, PrettyFormatter, TerseFormatter},
+    formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter},
     helpers::{concurrency::get_concurrency, metrics::MetricMap},
     options::{Options, OutputFormat},
     run_tests,
@@ -56,6 +57,9 @@ pub struct ConsoleTestState {
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
     pub time_failures: Vec<(TestDesc, Vec<u8>)>,
     pub options: Options,
+    pub shuffle_seed: Option<u64>,
+    pub baseline: Option<Baseline>,
+    pub bench_results: Baseline,
 }
 
 impl ConsoleTestState {
@@ -80,6 +84,9 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             not_failures: Vec::new(),
             time_failures: Vec::new(),
             options: opts.options,
+            shuffle_seed: opts.shuffle_seed,
+            baseline: None,
+            bench_results: Baseline::new(),
         })
     }
 
@@ -125,6 +132,17 @@ pub fn write_log_result(
         self.write_log(|| "\n")
     }
 
+    /// Compares the results of the benchmark with the ones of the baseline given
+    /// with `--baseline`, if it has some.
+    pub fn compare_to_baseline(
+        &self,
+        test: &TestDesc,
+        bs: &BenchSamples,
+    ) -> Option<BenchComparison> {
+        let baseline = self.baseline.as_ref()?.get(test.name.as_slice())?;
+        Some(compare(baseline, bs))
+    }
+
     fn current_test_count(&self) -> usize {
         self.passed + self.failed + self.ignored + self.measured + self.allowed_fail
     }
@@ -149,7 +167,7 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
         let TestDescAndFn { desc: TestDesc { name, .. }, testfn } = test;
 
         let fntype = match testfn {
-            StaticTestFn(..) | DynTestFn(..) => {
+            StaticTestFn(..) | StaticHookedTestFn(..) | DynTestFn(..) => {
                 ntest += 1;
                 "test"
             }
@@ -198,6 +216,7 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
                 bs.ns_iter_summ.median,
                 bs.ns_iter_summ.max - bs.ns_iter_summ.min,
             );
+            st.bench_results.insert(test.name.as_slice(), bs);
             st.measured += 1
         }
         TestResult::TrFailed => {
@@ -227,7 +246,7 @@ fn on_test_event(
     match (*event).clone() {
         TestEvent::TeFiltered(ref filtered_tests) => {
             st.total = filtered_tests.len();
-            out.write_run_start(filtered_tests.len())?;
+            out.write_run_start(filtered_tests.len(), st.shuffle_seed)?;
         }
         TestEvent::TeFilteredOut(filtered_out) => {
             st.filtered_out = filtered_out;
@@ -277,8 +296,12 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
             Box::new(TerseFormatter::new(output, opts.use_color(), max_name_len, is_multithreaded))
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
+        OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
     };
     let mut st = ConsoleTestState::new(opts)?;
+    if let Some(ref name) = opts.baseline {
+        st.baseline = Some(Baseline::load(name)?);
+    }
 
     // Prevent the usage of `Instant` in some cases:
     // - It's currently not supported for wasm targets.
@@ -291,7 +314,13 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
 
     assert!(st.current_test_count() == st.total);
 
-    out.write_run_finish(&st)
+    let success = out.write_run_finish(&st)?;
+    if let Some(ref name) = opts.save_baseline {
+        if !st.bench_results.is_empty() {
+            st.bench_results.save(name)?;
+        }
+    }
+    Ok(success)
 }
 
 // Calculates padding for given test description.
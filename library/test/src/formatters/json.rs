@@ -2,6 +2,7 @@
 
 use super::OutputFormatter;
 use crate::{
+    baseline::BenchChange,
     console::{ConsoleTestState, OutputLocation},
     test_result::TestResult,
     time,
@@ -60,10 +61,15 @@ fn write_event(
 }
 
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        let shuffle_seed_json = if let Some(shuffle_seed) = shuffle_seed {
+            format!(r#", "shuffle_seed": {}"#, shuffle_seed)
+        } else {
+            String::new()
+        };
         self.writeln_message(&*format!(
-            r#"{{ "type": "suite", "event": "started", "test_count": {} }}"#,
-            test_count
+            r#"{{ "type": "suite", "event": "started", "test_count": {}{} }}"#,
+            test_count, shuffle_seed_json
         ))
     }
 
@@ -138,15 +144,30 @@ fn write_result(
                     format!(r#", "mib_per_second": {}"#, bs.mb_s)
                 };
 
+                let baseline = match state.compare_to_baseline(desc, bs) {
+                    Some(comparison) => format!(
+                        r#", "baseline_median": {}, "change_pct": {:.2}, "change": "{}""#,
+                        comparison.baseline_median as usize,
+                        comparison.change_pct,
+                        match comparison.change {
+                            BenchChange::Improved => "improved",
+                            BenchChange::Regressed => "regressed",
+                            BenchChange::NoChange => "none",
+                        }
+                    ),
+                    None => String::new(),
+                };
+
                 let line = format!(
                     "{{ \"type\": \"bench\", \
                      \"name\": \"{}\", \
                      \"median\": {}, \
-                     \"deviation\": {}{} }}",
+                     \"deviation\": {}{}{} }}",
                     EscapedString(desc.name.as_slice()),
                     median,
                     deviation,
-                    mbps
+                    mbps,
+                    baseline
                 );
 
                 self.writeln_message(&*line)
@@ -0,0 +1,215 @@
+use std::io::{self, prelude::Write};
+use std::time::Duration;
+
+use super::OutputFormatter;
+use crate::{
+    console::{ConsoleTestState, OutputLocation},
+    test_result::TestResult,
+    time,
+    types::{TestDesc, TestType},
+};
+
+pub(crate) struct JunitFormatter<T> {
+    out: OutputLocation<T>,
+    results: Vec<(TestDesc, TestResult, Duration, Vec<u8>)>,
+}
+
+impl<T: Write> JunitFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self { out, results: Vec::new() }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn writeln_message(&mut self, s: &str) -> io::Result<()> {
+        self.out.write_all(s.as_ref())?;
+        self.out.write_all(b"\n")
+    }
+
+    fn write_testcase(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        duration: Duration,
+        stdout: &[u8],
+    ) -> io::Result<()> {
+        let (class_name, test_name) = parse_class_name(desc);
+        let time = match *result {
+            TestResult::TrBench(ref bs) => Duration::from_nanos(bs.ns_iter_summ.median as u64),
+            _ => duration,
+        };
+        self.writeln_message(&*format!(
+            r#"<testcase classname="{}" name="{}" time="{}">"#,
+            EscapedString(&class_name),
+            EscapedString(&test_name),
+            time.as_secs_f64()
+        ))?;
+
+        match *result {
+            TestResult::TrOk | TestResult::TrAllowedFail | TestResult::TrBench(_) => {}
+
+            TestResult::TrIgnored => self.writeln_message("<skipped/>")?,
+
+            TestResult::TrFailed => self.writeln_message(r#"<failure type="assert"/>"#)?,
+
+            TestResult::TrFailedMsg(ref m) => self.writeln_message(&*format!(
+                r#"<failure type="assert" message="{}"/>"#,
+                EscapedString(m)
+            ))?,
+
+            TestResult::TrTimedFail => {
+                self.writeln_message(r#"<failure type="timeout" message="time limit exceeded"/>"#)?
+            }
+        }
+
+        if !stdout.is_empty() {
+            self.writeln_message(&*format!(
+                "<system-out>{}</system-out>",
+                EscapedString(String::from_utf8_lossy(stdout))
+            ))?;
+        }
+
+        self.writeln_message("</testcase>")
+    }
+}
+
+impl<T: Write> OutputFormatter for JunitFormatter<T> {
+    fn write_run_start(
+        &mut self,
+        _test_count: usize,
+        _shuffle_seed: Option<u64>,
+    ) -> io::Result<()> {
+        // The header is written up front; everything else has to wait until
+        // the run is complete, since the `testsuite` element carries the totals.
+        self.writeln_message(r#"<?xml version="1.0" encoding="UTF-8"?>"#)
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test start.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test timeout.
+        Ok(())
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
+        let stdout = if display_stdout { stdout.to_vec() } else { Vec::new() };
+        let duration = exec_time.map(|t| t.0).unwrap_or_default();
+        self.results.push((desc.clone(), result.clone(), duration, stdout));
+        Ok(())
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        self.writeln_message("<testsuites>")?;
+
+        let time = state.exec_time.as_ref().map(|t| t.0).unwrap_or_default();
+        self.writeln_message(&*format!(
+            "<testsuite name=\"test\" package=\"test\" id=\"0\" \
+             errors=\"0\" \
+             failures=\"{}\" \
+             tests=\"{}\" \
+             skipped=\"{}\" \
+             time=\"{}\">",
+            state.failed,
+            state.total,
+            state.ignored,
+            time.as_secs_f64()
+        ))?;
+
+        for (desc, result, duration, stdout) in std::mem::take(&mut self.results) {
+            self.write_testcase(&desc, &result, duration, &stdout)?;
+        }
+
+        self.writeln_message("</testsuite>")?;
+        self.writeln_message("</testsuites>")?;
+
+        Ok(state.failed == 0)
+    }
+}
+
+/// Splits a test name into the `classname` and `name` attributes of a `testcase`.
+fn parse_class_name(desc: &TestDesc) -> (String, String) {
+    match desc.test_type {
+        TestType::UnitTest => parse_class_name_unit(desc),
+        TestType::DocTest => parse_class_name_doc(desc),
+        TestType::IntegrationTest => parse_class_name_integration(desc),
+        TestType::Unknown => (String::from("unknown"), String::from(desc.name.as_slice())),
+    }
+}
+
+fn parse_class_name_unit(desc: &TestDesc) -> (String, String) {
+    // Module path => classname
+    // Function name => name
+    match desc.name.as_slice().rsplit_once("::") {
+        Some((path, test)) => (String::from(path), String::from(test)),
+        None => (String::from("crate"), String::from(desc.name.as_slice())),
+    }
+}
+
+fn parse_class_name_doc(desc: &TestDesc) -> (String, String) {
+    // File path => classname
+    // Item path and line number => name
+    match desc.name.as_slice().split_once(" - ") {
+        Some((file, rest)) => (String::from(file.trim()), String::from(rest.trim())),
+        None => (String::from("doctest"), String::from(desc.name.as_slice())),
+    }
+}
+
+fn parse_class_name_integration(desc: &TestDesc) -> (String, String) {
+    (String::from("integration"), String::from(desc.name.as_slice()))
+}
+
+/// A formatting utility used to print strings with characters that are not
+/// allowed verbatim in XML attribute values or character data.
+struct EscapedString<S: AsRef<str>>(S);
+
+impl<S: AsRef<str>> std::fmt::Display for EscapedString<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        let mut start = 0;
+
+        for (i, c) in self.0.as_ref().char_indices() {
+            let escaped = match c {
+                '&' => "&amp;",
+                '<' => "&lt;",
+                '>' => "&gt;",
+                '"' => "&quot;",
+                '\'' => "&apos;",
+                '\n' => "&#10;",
+                '\r' => "&#13;",
+                '\t' => "&#9;",
+                // Other control characters cannot be represented in XML 1.0 at all.
+                '\x00'..='\x1f' => "\u{fffd}",
+                _ => {
+                    continue;
+                }
+            };
+
+            if start < i {
+                f.write_str(&self.0.as_ref()[start..i])?;
+            }
+
+            f.write_str(escaped)?;
+
+            start = i + c.len_utf8();
+        }
+
+        if start != self.0.as_ref().len() {
+            f.write_str(&self.0.as_ref()[start..])?;
+        }
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
 
 use super::OutputFormatter;
 use crate::{
+    baseline::{BenchChange, BenchComparison},
     bench::fmt_bench_samples,
     console::{ConsoleTestState, OutputLocation},
     test_result::TestResult,
@@ -60,6 +61,16 @@ pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
 
+    pub fn write_bench_comparison(&mut self, comparison: &BenchComparison) -> io::Result<()> {
+        self.write_plain(&format!(", {:+.2}% vs baseline (", comparison.change_pct))?;
+        match comparison.change {
+            BenchChange::Improved => self.write_pretty("improved", term::color::GREEN)?,
+            BenchChange::Regressed => self.write_pretty("regressed", term::color::RED)?,
+            BenchChange::NoChange => self.write_plain("no change")?,
+        }
+        self.write_plain(")")
+    }
+
     pub fn write_short_result(
         &mut self,
         result: &str,
@@ -167,6 +178,17 @@ pub fn write_time_failures(&mut self, state: &ConsoleTestState) -> io::Result<()
         self.write_results(&state.time_failures, "failures (time limit exceeded)")
     }
 
+    pub fn write_shuffle_seed_note(&mut self, state: &ConsoleTestState) -> io::Result<()> {
+        match state.shuffle_seed {
+            Some(shuffle_seed) => self.write_plain(&format!(
+                "\nnote: the tests ran in a shuffled order, use `--shuffle={}` to run them in the \
+                 same order again\n",
+                shuffle_seed
+            )),
+            None => Ok(()),
+        }
+    }
+
     fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = desc.padded_name(self.max_name_len, desc.name.padding());
         self.write_plain(&format!("test {} ... ", name))?;
@@ -176,9 +198,14 @@ fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
 }
 
 impl<T: Write> OutputFormatter for PrettyFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
         let noun = if test_count != 1 { "tests" } else { "test" };
-        self.write_plain(&format!("\nrunning {} {}\n", test_count, noun))
+        let shuffle_seed_msg = if let Some(shuffle_seed) = shuffle_seed {
+            format!(" (shuffle seed: {})", shuffle_seed)
+        } else {
+            String::new()
+        };
+        self.write_plain(&format!("\nrunning {} {}{}\n", test_count, noun, shuffle_seed_msg))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -199,7 +226,7 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         _: &[u8],
-        _: &ConsoleTestState,
+        state: &ConsoleTestState,
     ) -> io::Result<()> {
         if self.is_multithreaded {
             self.write_test_name(desc)?;
@@ -213,6 +240,9 @@ fn write_result(
             TestResult::TrBench(ref bs) => {
                 self.write_bench()?;
                 self.write_plain(&format!(": {}", fmt_bench_samples(bs)))?;
+                if let Some(comparison) = state.compare_to_baseline(desc, bs) {
+                    self.write_bench_comparison(&comparison)?;
+                }
             }
             TestResult::TrTimedFail => self.write_time_failed()?,
         }
@@ -246,6 +276,8 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
             if !state.time_failures.is_empty() {
                 self.write_time_failures(state)?;
             }
+
+            self.write_shuffle_seed_note(state)?;
         }
 
         self.write_plain("\ntest result: ")?;
@@ -2,6 +2,7 @@
 
 use super::OutputFormatter;
 use crate::{
+    baseline::{BenchChange, BenchComparison},
     bench::fmt_bench_samples,
     console::{ConsoleTestState, OutputLocation},
     test_result::TestResult,
@@ -61,6 +62,16 @@ pub fn write_bench(&mut self) -> io::Result<()> {
         self.write_pretty("bench", term::color::CYAN)
     }
 
+    pub fn write_bench_comparison(&mut self, comparison: &BenchComparison) -> io::Result<()> {
+        self.write_plain(&format!(", {:+.2}% vs baseline (", comparison.change_pct))?;
+        match comparison.change {
+            BenchChange::Improved => self.write_pretty("improved", term::color::GREEN)?,
+            BenchChange::Regressed => self.write_pretty("regressed", term::color::RED)?,
+            BenchChange::NoChange => self.write_plain("no change")?,
+        }
+        self.write_plain(")")
+    }
+
     pub fn write_short_result(
         &mut self,
         result: &str,
@@ -156,6 +167,17 @@ pub fn write_failures(&mut self, state: &ConsoleTestState) -> io::Result<()> {
         Ok(())
     }
 
+    pub fn write_shuffle_seed_note(&mut self, state: &ConsoleTestState) -> io::Result<()> {
+        match state.shuffle_seed {
+            Some(shuffle_seed) => self.write_plain(&format!(
+                "\nnote: the tests ran in a shuffled order, use `--shuffle={}` to run them in the \
+                 same order again\n",
+                shuffle_seed
+            )),
+            None => Ok(()),
+        }
+    }
+
     fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = desc.padded_name(self.max_name_len, desc.name.padding());
         self.write_plain(&format!("test {} ... ", name))?;
@@ -165,10 +187,15 @@ fn write_test_name(&mut self, desc: &TestDesc) -> io::Result<()> {
 }
 
 impl<T: Write> OutputFormatter for TerseFormatter<T> {
-    fn write_run_start(&mut self, test_count: usize) -> io::Result<()> {
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
         self.total_test_count = test_count;
         let noun = if test_count != 1 { "tests" } else { "test" };
-        self.write_plain(&format!("\nrunning {} {}\n", test_count, noun))
+        let shuffle_seed_msg = if let Some(shuffle_seed) = shuffle_seed {
+            format!(" (shuffle seed: {})", shuffle_seed)
+        } else {
+            String::new()
+        };
+        self.write_plain(&format!("\nrunning {} {}{}\n", test_count, noun, shuffle_seed_msg))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
@@ -189,7 +216,7 @@ fn write_result(
         result: &TestResult,
         _: Option<&time::TestExecTime>,
         _: &[u8],
-        _: &ConsoleTestState,
+        state: &ConsoleTestState,
     ) -> io::Result<()> {
         match *result {
             TestResult::TrOk => self.write_ok(),
@@ -203,7 +230,11 @@ fn write_result(
                     self.write_test_name(desc)?;
                 }
                 self.write_bench()?;
-                self.write_plain(&format!(": {}\n", fmt_bench_samples(bs)))
+                self.write_plain(&format!(": {}", fmt_bench_samples(bs)))?;
+                if let Some(comparison) = state.compare_to_baseline(desc, bs) {
+                    self.write_bench_comparison(&comparison)?;
+                }
+                self.write_plain("\n")
             }
         }
     }
@@ -223,6 +254,7 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
         let success = state.failed == 0;
         if !success {
             self.write_failures(state)?;
+            self.write_shuffle_seed_note(state)?;
         }
 
         self.write_plain("\ntest result: ")?;
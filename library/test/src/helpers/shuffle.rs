@@ -0,0 +1,61 @@
+//! Helper module to run the tests in a random, but reproducible, order.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::types::TestDescAndFn;
+
+/// Picks a seed for `--shuffle` when none was given.
+pub fn random_seed() -> u64 {
+    // The seed only needs to differ between runs, it is printed so that the order can be
+    // reproduced.
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    calculate_hash(&(nanos, std::process::id()))
+}
+
+/// Shuffles the tests with the given seed. The same seed gives the same order for the same set
+/// of tests, on every platform.
+pub fn shuffle_tests(seed: u64, tests: &mut [TestDescAndFn]) {
+    // Mixing the test names into the seed makes the order of unrelated test suites differ when
+    // they are run with the same seed. Everything is hashed as bytes, with the integers in
+    // little-endian order, as the `Hash` impls depend on the width and endianness of the
+    // platform's integers.
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&(tests.len() as u64).to_le_bytes());
+    for test in tests.iter() {
+        let name = test.desc.name.as_slice();
+        hasher.write(&(name.len() as u64).to_le_bytes());
+        hasher.write(name.as_bytes());
+    }
+    let mut rng = Rng { state: seed, names: hasher.finish() };
+
+    // Fisher-Yates shuffle.
+    for i in (1..tests.len()).rev() {
+        let j = rng.next_below(i as u64 + 1) as usize;
+        tests.swap(i, j);
+    }
+}
+
+/// A minimal deterministic random number generator, since libtest can't depend on `rand`.
+struct Rng {
+    state: u64,
+    names: u64,
+}
+
+impl Rng {
+    fn next_below(&mut self, bound: u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&self.state.to_le_bytes());
+        hasher.write(&self.names.to_le_bytes());
+        self.state = hasher.finish();
+        self.state % bound
+    }
+}
+
+fn calculate_hash<T: Hash>(t: &T) -> u64 {
+    // `DefaultHasher::new` always uses the same keys, so the hash is deterministic.
+    let mut hasher = DefaultHasher::new();
+    t.hash(&mut hasher);
+    hasher.finish()
+}
@@ -71,6 +71,7 @@ pub mod test {
     time::{Duration, Instant},
 };
 
+mod baseline;
 pub mod bench;
 mod cli;
 mod console;
@@ -89,6 +90,7 @@ pub mod test {
 use event::{CompletedTest, TestEvent};
 use helpers::concurrency::get_concurrency;
 use helpers::exit_code::get_exit_code;
+use helpers::shuffle::shuffle_tests;
 use options::{Concurrent, RunStrategy};
 use test_result::*;
 use time::TestExecTime;
@@ -135,8 +137,21 @@ pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Option<Opt
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=unwind.
 pub fn test_main_static(tests: &[&TestDescAndFn]) {
+    test_main_static_with_hooks(tests, &[])
+}
+
+/// A variant of `test_main_static` which runs the setup and teardown hooks of
+/// the modules of the tests around them.
+///
+/// This is the entry point for the main function generated by `rustc --test`
+/// when panic=unwind and the crate has `#[test_setup]` or `#[test_teardown]`
+/// functions.
+pub fn test_main_static_with_hooks(tests: &[&TestDescAndFn], hooks: &[&TestHook]) {
+    // With `--timeout`, the tests are run in subprocesses, like with panic=abort.
+    run_test_if_spawned_as_subprocess(tests, hooks);
+
     let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
+    let owned_tests: Vec<_> = tests.iter().map(|test| make_owned_test(test, hooks)).collect();
     test_main(&args, owned_tests, None)
 }
 
@@ -149,6 +164,24 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=abort.
 pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
+    test_main_static_abort_with_hooks(tests, &[])
+}
+
+/// A variant of `test_main_static_abort` which runs the setup and teardown
+/// hooks of the modules of the tests around them.
+///
+/// This is the entry point for the main function generated by `rustc --test`
+/// when panic=abort and the crate has `#[test_setup]` or `#[test_teardown]`
+/// functions.
+pub fn test_main_static_abort_with_hooks(tests: &[&TestDescAndFn], hooks: &[&TestHook]) {
+    run_test_if_spawned_as_subprocess(tests, hooks);
+
+    let args = env::args().collect::<Vec<_>>();
+    let owned_tests: Vec<_> = tests.iter().map(|test| make_owned_test(test, hooks)).collect();
+    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
+}
+
+fn run_test_if_spawned_as_subprocess(tests: &[&TestDescAndFn], hooks: &[&TestHook]) {
     // If we're being run in SpawnedSecondary mode, run the test here. run_test
     // will then exit the process.
     if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
@@ -156,29 +189,50 @@ pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
         let test = tests
             .iter()
             .filter(|test| test.desc.name.as_slice() == name)
-            .map(make_owned_test)
+            .map(|test| make_owned_test(test, hooks))
             .next()
             .unwrap_or_else(|| panic!("couldn't find a test with the provided name '{}'", name));
         let TestDescAndFn { desc, testfn } = test;
-        let testfn = match testfn {
-            StaticTestFn(f) => f,
+        let (testfn, teardown): (Box<dyn FnOnce() + Send>, _) = match testfn {
+            StaticTestFn(f) => (Box::new(f), Vec::new()),
+            // The process exits from the panic hook when the test panics, before anything could
+            // catch the panic, so the teardown hooks are run by the subprocess itself.
+            StaticHookedTestFn(f, hooks) => {
+                let hooks_of_kind =
+                    |kind| hooks.iter().filter(move |hook| hook.kind == kind).map(|hook| hook.hook);
+                let setup: Vec<fn()> = hooks_of_kind(TestHookKind::Setup).collect();
+                let teardown = hooks_of_kind(TestHookKind::Teardown).rev().collect();
+                let testfn = move || {
+                    for setup in setup {
+                        setup();
+                    }
+                    f();
+                };
+                (Box::new(testfn), teardown)
+            }
             _ => panic!("only static tests are supported"),
         };
-        run_test_in_spawned_subprocess(desc, Box::new(testfn));
+        run_test_in_spawned_subprocess(desc, testfn, teardown);
     }
-
-    let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
-    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
 }
 
 /// Clones static values for putting into a dynamic vector, which test_main()
 /// needs to hand out ownership of tests to parallel test runners.
 ///
+/// The hooks of the modules of static tests are attached to them.
+///
 /// This will panic when fed any dynamic tests, because they cannot be cloned.
-fn make_owned_test(test: &&TestDescAndFn) -> TestDescAndFn {
+fn make_owned_test(test: &&TestDescAndFn, hooks: &[&TestHook]) -> TestDescAndFn {
     match test.testfn {
-        StaticTestFn(f) => TestDescAndFn { testfn: StaticTestFn(f), desc: test.desc.clone() },
+        StaticTestFn(f) => {
+            let mut hooks: Vec<TestHook> =
+                hooks.iter().filter(|hook| hook.applies_to(&test.desc.name)).map(|&&h| h).collect();
+            // The hooks of outer modules have shorter paths, they are sorted first.
+            hooks.sort_by_key(|hook| hook.module_path.len());
+            let testfn =
+                if hooks.is_empty() { StaticTestFn(f) } else { StaticHookedTestFn(f, hooks) };
+            TestDescAndFn { testfn, desc: test.desc.clone() }
+        }
         StaticBenchFn(f) => TestDescAndFn { testfn: StaticBenchFn(f), desc: test.desc.clone() },
         _ => panic!("non-static tests passed to test::test_main_static"),
     }
@@ -239,16 +293,21 @@ pub fn run_tests<F>(
 
     let (filtered_tests, filtered_benchs): (Vec<_>, _) = filtered_tests
         .into_iter()
-        .partition(|e| matches!(e.testfn, StaticTestFn(_) | DynTestFn(_)));
+        .partition(|e| matches!(e.testfn, StaticTestFn(_) | StaticHookedTestFn(..) | DynTestFn(_)));
 
     let concurrency = opts.test_threads.unwrap_or_else(get_concurrency);
 
     let mut remaining = filtered_tests;
+    if let Some(shuffle_seed) = opts.shuffle_seed {
+        shuffle_tests(shuffle_seed, &mut remaining);
+    }
     remaining.reverse();
     let mut pending = 0;
 
     let (tx, rx) = channel::<CompletedTest>();
-    let run_strategy = if opts.options.panic_abort && !opts.force_run_in_process {
+    // Tests which time out can only be killed if they run in their own process.
+    let spawn_tests = opts.options.panic_abort || opts.timeout.is_some();
+    let run_strategy = if spawn_tests && !opts.force_run_in_process {
         RunStrategy::SpawnPrimary
     } else {
         RunStrategy::InProcess
@@ -386,6 +445,17 @@ pub fn filter_tests(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> Vec<TestDescA
     // Sort the tests alphabetically
     filtered.sort_by(|t1, t2| t1.desc.name.as_slice().cmp(t2.desc.name.as_slice()));
 
+    // Keep the tests of the selected shard, distributing them in turn so that shards of tests
+    // with similar names get a similar share of them
+    if opts.shard_count > 1 {
+        filtered = filtered
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| i % opts.shard_count == opts.shard_index)
+            .map(|(_, test)| test)
+            .collect();
+    }
+
     filtered
 }
 
@@ -434,6 +504,7 @@ struct TestRunOpts {
         pub nocapture: bool,
         pub concurrency: Concurrent,
         pub time: Option<time::TestTimeOptions>,
+        pub timeout: Option<Duration>,
     }
 
     fn run_test_inner(
@@ -460,6 +531,7 @@ fn run_test_inner(
                 opts.time.is_some(),
                 monitor_ch,
                 opts.time,
+                opts.timeout,
             ),
         };
 
@@ -475,8 +547,13 @@ fn run_test_inner(
         }
     }
 
-    let test_run_opts =
-        TestRunOpts { strategy, nocapture: opts.nocapture, concurrency, time: opts.time_options };
+    let mut test_run_opts = TestRunOpts {
+        strategy,
+        nocapture: opts.nocapture,
+        concurrency,
+        time: opts.time_options,
+        timeout: opts.timeout,
+    };
 
     match testfn {
         DynBenchFn(bencher) => {
@@ -492,6 +569,11 @@ fn run_test_inner(
         DynTestFn(f) => {
             match strategy {
                 RunStrategy::InProcess => (),
+                // Dynamic tests can't be spawned, they only run in process. They are then not
+                // killed when they time out.
+                RunStrategy::SpawnPrimary if !opts.options.panic_abort => {
+                    test_run_opts.strategy = RunStrategy::InProcess;
+                }
                 _ => panic!("Cannot run dynamic test fn out-of-process"),
             };
             run_test_inner(
@@ -507,6 +589,31 @@ fn run_test_inner(
             Box::new(move || __rust_begin_short_backtrace(f)),
             test_run_opts,
         ),
+        StaticHookedTestFn(f, hooks) => run_test_inner(
+            desc,
+            monitor_ch,
+            Box::new(move || __rust_begin_short_backtrace(|| run_with_hooks(f, &hooks))),
+            test_run_opts,
+        ),
+    }
+}
+
+/// Runs the test after the setup hooks, and then the teardown hooks in reverse
+/// order, even if the test or one of the setup hooks panicked.
+fn run_with_hooks(test: fn(), hooks: &[TestHook]) {
+    let hooks_of_kind = |kind| hooks.iter().filter(move |hook| hook.kind == kind);
+
+    let result = catch_unwind(|| {
+        for setup in hooks_of_kind(TestHookKind::Setup) {
+            (setup.hook)();
+        }
+        test();
+    });
+    for teardown in hooks_of_kind(TestHookKind::Teardown).rev() {
+        (teardown.hook)();
+    }
+    if let Err(payload) = result {
+        panic::resume_unwind(payload);
     }
 }
 
@@ -558,6 +665,7 @@ fn spawn_test_subprocess(
     report_time: bool,
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
+    timeout: Option<Duration>,
 ) {
     let (result, test_output, exec_time) = (|| {
         let args = env::args().collect::<Vec<_>>();
@@ -568,10 +676,13 @@ fn spawn_test_subprocess(
         if nocapture {
             command.stdout(process::Stdio::inherit());
             command.stderr(process::Stdio::inherit());
+        } else {
+            command.stdout(process::Stdio::piped());
+            command.stderr(process::Stdio::piped());
         }
 
         let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let (output, timed_out) = match output_with_timeout(&mut command, timeout) {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
@@ -589,6 +700,9 @@ fn spawn_test_subprocess(
         test_output.extend_from_slice(&stderr);
 
         let result = match (|| -> Result<TestResult, String> {
+            if let Some(timeout) = timeout.filter(|_| timed_out) {
+                return Ok(get_result_from_timeout(&desc, timeout));
+            }
             let exit_code = get_exit_code(status)?;
             Ok(get_result_from_exit_code(&desc, exit_code, &time_opts, &exec_time))
         })() {
@@ -606,9 +720,97 @@ fn spawn_test_subprocess(
     monitor_ch.send(message).unwrap();
 }
 
-fn run_test_in_spawned_subprocess(desc: TestDesc, testfn: Box<dyn FnOnce() + Send>) -> ! {
+/// Runs the command like `Command::output`, but kills it if it is still running after
+/// `timeout`. Also returns whether it was killed, in which case the output is what it wrote
+/// until then.
+///
+/// Processes spawned by the command may keep its output pipes open after it exited or was
+/// killed, so they are only read until the deadline, plus `PIPE_GRACE_PERIOD` to read what
+/// was written just before the command was killed.
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Option<Duration>,
+) -> io::Result<(process::Output, bool)> {
+    const PIPE_GRACE_PERIOD: Duration = Duration::from_millis(100);
+
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return command.output().map(|output| (output, false)),
+    };
+
+    let mut child = command.stdin(process::Stdio::null()).spawn()?;
+    // The pipes are read from other threads, so that the child doesn't block on them when they
+    // are full. The output read so far is shared with the readers, since they may never reach
+    // the end of the pipes.
+    let (done_tx, done_rx) = channel();
+    let mut readers = 0;
+    let mut read_pipe = |pipe: Option<Box<dyn io::Read + Send>>| {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        if let Some(mut pipe) = pipe {
+            let output = output.clone();
+            let done_tx = done_tx.clone();
+            readers += 1;
+            thread::spawn(move || {
+                let mut buf = [0; 4096];
+                loop {
+                    match pipe.read(&mut buf) {
+                        Ok(0) => break,
+                        Ok(n) => output.lock().unwrap().extend_from_slice(&buf[..n]),
+                        Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                        Err(_) => break,
+                    }
+                }
+                let _ = done_tx.send(());
+            });
+        }
+        output
+    };
+    let stdout = read_pipe(child.stdout.take().map(|pipe| Box::new(pipe) as Box<_>));
+    let stderr = read_pipe(child.stderr.take().map(|pipe| Box::new(pipe) as Box<_>));
+
+    let deadline = Instant::now() + timeout;
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status, false);
+        }
+        if Instant::now() >= deadline {
+            // The child may have exited in the meantime, in which case `kill` fails.
+            let _ = child.kill();
+            break (child.wait()?, true);
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+
+    let pipe_deadline = Instant::now().max(deadline) + PIPE_GRACE_PERIOD;
+    for _ in 0..readers {
+        let now = Instant::now();
+        if now >= pipe_deadline || done_rx.recv_timeout(pipe_deadline - now).is_err() {
+            break;
+        }
+    }
+
+    let take = |output: Arc<Mutex<Vec<u8>>>| std::mem::take(&mut *output.lock().unwrap());
+    let output = process::Output { status, stdout: take(stdout), stderr: take(stderr) };
+    Ok((output, timed_out))
+}
+
+/// Runs the test, then the `teardown` hooks, and exits with the result of the test. If the test
+/// panics, the teardown hooks run from the panic hook before the process exits.
+fn run_test_in_spawned_subprocess(
+    desc: TestDesc,
+    testfn: Box<dyn FnOnce() + Send>,
+    teardown: Vec<fn()>,
+) -> ! {
     let builtin_panic_hook = panic::take_hook();
+    // The hooks are taken out before running them, so that a panicking teardown hook, which
+    // calls the panic hook again, doesn't run them twice.
+    let teardown = Mutex::new(Some(teardown));
     let record_result = Arc::new(move |panic_info: Option<&'_ PanicInfo<'_>>| {
+        let hooks = teardown.lock().unwrap().take();
+        for hook in hooks.into_iter().flatten() {
+            hook();
+        }
+
         let test_result = match panic_info {
             Some(info) => calc_result(&desc, Err(info.payload()), &None, &None),
             None => calc_result(&desc, Ok(()), &None, &None),
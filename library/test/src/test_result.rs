@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::time::Duration;
 
 use super::bench::BenchSamples;
 use super::options::ShouldPanic;
@@ -85,6 +86,16 @@ pub fn calc_result<'a>(
     result
 }
 
+/// Creates a `TestResult` for a test subprocess which was killed after running for longer than
+/// `timeout`.
+pub fn get_result_from_timeout(desc: &TestDesc, timeout: Duration) -> TestResult {
+    if desc.allow_fail {
+        TestResult::TrAllowedFail
+    } else {
+        TestResult::TrFailedMsg(format!("test did not finish within {:?} and was killed", timeout))
+    }
+}
+
 /// Creates a `TestResult` depending on the exit code of test subprocess.
 pub fn get_result_from_exit_code(
     desc: &TestDesc,
@@ -1,9 +1,10 @@
 use super::*;
 
 use crate::{
-    bench::Bencher,
+    baseline::{compare, Baseline, BenchChange},
+    bench::{BenchSamples, Bencher},
     console::OutputLocation,
-    formatters::PrettyFormatter,
+    formatters::{JunitFormatter, OutputFormatter, PrettyFormatter},
     options::OutputFormat,
     test::{
         filter_tests,
@@ -19,6 +20,7 @@
         TestDesc,
         TestDescAndFn,
         TestOpts,
+        TrFailedMsg,
         TrIgnored,
         TrOk,
         // FIXME (introduced by #65251)
@@ -48,7 +50,13 @@ fn new() -> TestOpts {
             format: OutputFormat::Pretty,
             test_threads: None,
             skip: vec![],
+            shard_index: 0,
+            shard_count: 1,
+            shuffle_seed: None,
             time_options: None,
+            timeout: None,
+            save_baseline: None,
+            baseline: None,
             options: Options::new(),
         }
     }
@@ -565,6 +573,270 @@ fn testfn() {}
     }
 }
 
+fn named_tests(names: &[&str]) -> Vec<TestDescAndFn> {
+    fn testfn() {}
+    names
+        .iter()
+        .map(|name| TestDescAndFn {
+            desc: TestDesc {
+                name: DynTestName(name.to_string()),
+                ignore: false,
+                should_panic: ShouldPanic::No,
+                allow_fail: false,
+                test_type: TestType::Unknown,
+            },
+            testfn: DynTestFn(Box::new(testfn)),
+        })
+        .collect()
+}
+
+fn test_names(tests: &[TestDescAndFn]) -> Vec<String> {
+    tests.iter().map(|test| test.desc.name.to_string()).collect()
+}
+
+#[test]
+fn parse_shard_and_shuffle_flags() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--shard-index".to_string(),
+        "1".to_string(),
+        "--shard-count".to_string(),
+        "3".to_string(),
+        "--shuffle=42".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!((opts.shard_index, opts.shard_count), (1, 3));
+    assert_eq!(opts.shuffle_seed, Some(42));
+
+    let args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!((opts.shard_index, opts.shard_count), (0, 1));
+    assert_eq!(opts.shuffle_seed, None);
+
+    let args =
+        vec!["progname".to_string(), "-Zunstable-options".to_string(), "--shuffle".to_string()];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert!(opts.shuffle_seed.is_some());
+}
+
+#[test]
+fn parse_invalid_shard_flags() {
+    let parse = |args: &[&str]| {
+        let mut all_args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+        all_args.extend(args.iter().map(|arg| arg.to_string()));
+        parse_opts(&all_args).unwrap()
+    };
+    assert!(parse(&["--shard-index", "3", "--shard-count", "3"]).is_err());
+    assert!(parse(&["--shard-index", "0", "--shard-count", "0"]).is_err());
+    assert!(parse(&["--shard-index", "0"]).is_err());
+    assert!(parse(&["--shard-count", "2"]).is_err());
+
+    // The flags are unstable.
+    let args = vec!["progname".to_string(), "--shuffle".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn parse_timeout_flag() {
+    let parse = |args: &[&str]| {
+        let mut all_args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+        all_args.extend(args.iter().map(|arg| arg.to_string()));
+        parse_opts(&all_args).unwrap()
+    };
+    assert_eq!(parse(&["--timeout", "1.5"]).unwrap().timeout, Some(Duration::from_millis(1500)));
+    assert_eq!(parse(&[]).unwrap().timeout, None);
+    assert!(parse(&["--timeout", "0"]).is_err());
+    assert!(parse(&["--timeout", "forever"]).is_err());
+
+    // The flag is unstable.
+    let args = vec!["progname".to_string(), "--timeout".to_string(), "1".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+#[cfg(unix)]
+fn output_with_timeout_kills_the_process() {
+    let mut command = Command::new("sh");
+    command.args(&["-c", "echo started; exec sleep 10"]).stdout(process::Stdio::piped());
+    let start = Instant::now();
+    let (output, timed_out) =
+        output_with_timeout(&mut command, Some(Duration::from_millis(200))).unwrap();
+    assert!(timed_out);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(output.stdout, b"started\n");
+
+    let mut command = Command::new("true");
+    let (output, timed_out) =
+        output_with_timeout(&mut command, Some(Duration::from_secs(10))).unwrap();
+    assert!(!timed_out);
+    assert!(output.status.success());
+}
+
+#[test]
+#[cfg(unix)]
+fn output_with_timeout_doesnt_wait_for_grandchildren() {
+    // The background `sleep` keeps stdout open after the shell is killed.
+    let mut command = Command::new("sh");
+    command.args(&["-c", "echo started; sleep 10 & wait"]).stdout(process::Stdio::piped());
+    let start = Instant::now();
+    let (output, timed_out) =
+        output_with_timeout(&mut command, Some(Duration::from_millis(200))).unwrap();
+    assert!(timed_out);
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(output.stdout, b"started\n");
+
+    // Same when the shell exits by itself before the timeout.
+    let mut command = Command::new("sh");
+    command.args(&["-c", "echo started; sleep 10 &"]).stdout(process::Stdio::piped());
+    let start = Instant::now();
+    let (output, timed_out) =
+        output_with_timeout(&mut command, Some(Duration::from_millis(500))).unwrap();
+    assert!(!timed_out);
+    assert!(output.status.success());
+    assert!(start.elapsed() < Duration::from_secs(5));
+    assert_eq!(output.stdout, b"started\n");
+}
+
+#[test]
+fn parse_baseline_flags() {
+    let parse = |args: &[&str]| {
+        let mut all_args = vec!["progname".to_string(), "-Zunstable-options".to_string()];
+        all_args.extend(args.iter().map(|arg| arg.to_string()));
+        parse_opts(&all_args).unwrap()
+    };
+    let opts = parse(&["--save-baseline", "new", "--baseline", "main"]).unwrap();
+    assert_eq!(opts.save_baseline.as_deref(), Some("new"));
+    assert_eq!(opts.baseline.as_deref(), Some("main"));
+    assert!(parse(&["--baseline", ""]).is_err());
+    assert!(parse(&["--save-baseline", "../main"]).is_err());
+
+    // The flags are unstable.
+    let args = vec!["progname".to_string(), "--baseline".to_string(), "main".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+fn bench_samples(samples: &[f64]) -> BenchSamples {
+    BenchSamples { ns_iter_summ: stats::Summary::new(samples), mb_s: 0 }
+}
+
+#[test]
+fn baseline_round_trip() {
+    let mut baseline = Baseline::new();
+    baseline.insert("bench::fast", bench_samples(&[1.0, 2.5, 3.0]));
+    baseline.insert("bench::with spaces", BenchSamples { mb_s: 42, ..bench_samples(&[1e9]) });
+
+    let contents = baseline.serialize();
+    assert_eq!(Baseline::parse(&contents), Ok(baseline));
+    assert!(Baseline::parse("1 2 3 bench::truncated").is_err());
+}
+
+#[test]
+fn compare_with_baseline() {
+    let baseline = bench_samples(&[100.0, 101.0, 102.0, 103.0, 104.0]);
+
+    let slower = compare(&baseline, &bench_samples(&[110.0, 111.0, 112.0, 113.0, 114.0]));
+    assert_eq!(slower.change, BenchChange::Regressed);
+    assert_eq!(slower.baseline_median, 102.0);
+    assert!((slower.change_pct - 10.0 / 102.0 * 100.0).abs() < 1e-9);
+
+    let faster = compare(&baseline, &bench_samples(&[90.0, 91.0, 92.0, 93.0, 94.0]));
+    assert_eq!(faster.change, BenchChange::Improved);
+
+    let noisy = compare(&baseline, &bench_samples(&[95.0, 101.0, 103.0, 104.0, 120.0]));
+    assert_eq!(noisy.change, BenchChange::NoChange);
+}
+
+#[test]
+pub fn shards_partition_the_tests() {
+    let names = ["a", "b", "c", "d", "e", "f", "g"];
+    let mut all = Vec::new();
+    for shard_index in 0..3 {
+        let opts = TestOpts { shard_index, shard_count: 3, ..TestOpts::new() };
+        let filtered = test_names(&filter_tests(&opts, named_tests(&names)));
+        // The tests are distributed in turn, in alphabetical order.
+        assert!(filtered.len() == 2 || filtered.len() == 3);
+        all.extend(filtered);
+    }
+    all.sort();
+    assert_eq!(all, names);
+}
+
+#[test]
+pub fn shuffle_is_deterministic() {
+    let names = ["a", "b", "c", "d", "e", "f", "g", "h", "i", "j"];
+
+    let mut tests = named_tests(&names);
+    helpers::shuffle::shuffle_tests(42, &mut tests);
+    let shuffled = test_names(&tests);
+    // The order only depends on the seed and the names, so it's the same on every platform.
+    assert_eq!(shuffled, ["j", "a", "b", "d", "e", "i", "h", "f", "g", "c"]);
+
+    let mut tests = named_tests(&names);
+    helpers::shuffle::shuffle_tests(42, &mut tests);
+    assert_eq!(test_names(&tests), shuffled);
+
+    let mut tests = named_tests(&names);
+    helpers::shuffle::shuffle_tests(43, &mut tests);
+    assert_ne!(test_names(&tests), shuffled);
+
+    let mut sorted = shuffled;
+    sorted.sort();
+    assert_eq!(sorted, names);
+}
+
+#[test]
+fn test_hooks_apply_to_their_modules() {
+    let hook = |module_path| TestHook { module_path, kind: TestHookKind::Setup, hook: || {} };
+    assert!(hook("").applies_to(&StaticTestName("test")));
+    assert!(hook("").applies_to(&StaticTestName("a::test")));
+    assert!(hook("a").applies_to(&StaticTestName("a::test")));
+    assert!(hook("a").applies_to(&StaticTestName("a::b::test")));
+    assert!(!hook("a").applies_to(&StaticTestName("test")));
+    assert!(!hook("a").applies_to(&StaticTestName("ab::test")));
+    assert!(!hook("a::b").applies_to(&StaticTestName("a::test")));
+}
+
+#[test]
+fn test_hooks_run_around_the_test() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    // Each function appends its digit, to check the order in which they run.
+    static ORDER: AtomicUsize = AtomicUsize::new(0);
+    fn step(digit: usize) {
+        ORDER.store(ORDER.load(Ordering::SeqCst) * 10 + digit, Ordering::SeqCst);
+    }
+
+    let hooks = [
+        TestHook { module_path: "a::b", kind: TestHookKind::Teardown, hook: || step(4) },
+        TestHook { module_path: "a", kind: TestHookKind::Setup, hook: || step(1) },
+        TestHook { module_path: "a::b", kind: TestHookKind::Setup, hook: || step(2) },
+        TestHook { module_path: "", kind: TestHookKind::Teardown, hook: || step(5) },
+        TestHook { module_path: "c", kind: TestHookKind::Setup, hook: || step(9) },
+    ];
+    let hooks: Vec<_> = hooks.iter().collect();
+    let test = TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName("a::b::test"),
+            ignore: false,
+            should_panic: ShouldPanic::No,
+            allow_fail: false,
+            test_type: TestType::Unknown,
+        },
+        testfn: StaticTestFn(|| {
+            step(3);
+            panic!("the teardown hooks run anyway");
+        }),
+    };
+    let test = make_owned_test(&&test, &hooks);
+    assert!(matches!(test.testfn, StaticHookedTestFn(_, ref hooks) if hooks.len() == 4));
+
+    let (tx, rx) = channel();
+    run_test(&TestOpts::new(), false, test, RunStrategy::InProcess, tx, Concurrent::No);
+    assert_eq!(rx.recv().unwrap().result, TrFailed);
+    assert_eq!(ORDER.load(Ordering::SeqCst), 12345);
+}
+
 #[test]
 pub fn test_metricmap_compare() {
     let mut m1 = MetricMap::new();
@@ -675,6 +947,9 @@ fn should_sort_failures_before_printing_them() {
         options: Options::new(),
         not_failures: Vec::new(),
         time_failures: Vec::new(),
+        shuffle_seed: None,
+        baseline: None,
+        bench_results: Baseline::new(),
     };
 
     out.write_failures(&st).unwrap();
@@ -687,3 +962,69 @@ fn should_sort_failures_before_printing_them() {
     let bpos = s.find("b").unwrap();
     assert!(apos < bpos);
 }
+
+#[test]
+fn junit_output_escapes_and_reports_failures() {
+    let test_ok = TestDesc {
+        name: StaticTestName("module::ok"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        test_type: TestType::UnitTest,
+    };
+
+    let test_failed = TestDesc {
+        name: StaticTestName("module::failed"),
+        ignore: false,
+        should_panic: ShouldPanic::No,
+        allow_fail: false,
+        test_type: TestType::UnitTest,
+    };
+
+    let mut out = JunitFormatter::new(OutputLocation::Raw(Vec::new()));
+
+    let mut st = console::ConsoleTestState {
+        log_out: None,
+        total: 2,
+        passed: 1,
+        failed: 1,
+        ignored: 0,
+        allowed_fail: 0,
+        filtered_out: 0,
+        measured: 0,
+        exec_time: None,
+        metrics: MetricMap::new(),
+        failures: Vec::new(),
+        options: Options::new(),
+        not_failures: Vec::new(),
+        time_failures: Vec::new(),
+        shuffle_seed: None,
+        baseline: None,
+        bench_results: Baseline::new(),
+    };
+
+    out.write_run_start(2, None).unwrap();
+    out.write_result(&test_ok, &TrOk, Some(&test_exec_time(1500)), b"quiet", &st).unwrap();
+    out.write_result(
+        &test_failed,
+        &TrFailedMsg("expected <a> & \"b\"".to_string()),
+        None,
+        b"panicked\n",
+        &st,
+    )
+    .unwrap();
+    st.options = st.options.display_output(true);
+    assert!(!out.write_run_finish(&st).unwrap());
+
+    let s = match out.output_location() {
+        &OutputLocation::Raw(ref m) => String::from_utf8_lossy(&m[..]).into_owned(),
+        &OutputLocation::Pretty(_) => unreachable!(),
+    };
+
+    assert!(s.starts_with("<?xml"));
+    assert!(s.contains(r#"failures="1" tests="2" skipped="0""#));
+    assert!(s.contains(r#"<testcase classname="module" name="ok" time="1.5">"#));
+    assert!(!s.contains("quiet"));
+    assert!(s.contains(r#"message="expected &lt;a&gt; &amp; &quot;b&quot;""#));
+    assert!(s.contains("<system-out>panicked&#10;</system-out>"));
+}
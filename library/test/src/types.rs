@@ -85,6 +85,9 @@ pub trait TDynBenchFn: Send {
 // to support isolation of tests into threads.
 pub enum TestFn {
     StaticTestFn(fn()),
+    /// A static test function run between the setup and teardown hooks of its modules,
+    /// which are sorted from the outermost module to the innermost one.
+    StaticHookedTestFn(fn(), Vec<TestHook>),
     StaticBenchFn(fn(&mut Bencher)),
     DynTestFn(Box<dyn FnOnce() + Send>),
     DynBenchFn(Box<dyn TDynBenchFn + 'static>),
@@ -94,6 +97,7 @@ impl TestFn {
     pub fn padding(&self) -> NamePadding {
         match *self {
             StaticTestFn(..) => PadNone,
+            StaticHookedTestFn(..) => PadNone,
             StaticBenchFn(..) => PadOnRight,
             DynTestFn(..) => PadNone,
             DynBenchFn(..) => PadOnRight,
@@ -105,6 +109,7 @@ impl fmt::Debug for TestFn {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(match *self {
             StaticTestFn(..) => "StaticTestFn(..)",
+            StaticHookedTestFn(..) => "StaticHookedTestFn(..)",
             StaticBenchFn(..) => "StaticBenchFn(..)",
             DynTestFn(..) => "DynTestFn(..)",
             DynBenchFn(..) => "DynBenchFn(..)",
@@ -112,6 +117,34 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// Whether a test hook runs before or after the tests.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TestHookKind {
+    Setup,
+    Teardown,
+}
+
+/// A function which runs before or after each test of a module, and of the modules nested in it.
+/// These are generated by `#[test_setup]` and `#[test_teardown]`.
+#[derive(Copy, Clone, Debug)]
+pub struct TestHook {
+    /// The path of the module, as in the names of its tests.
+    pub module_path: &'static str,
+    pub kind: TestHookKind,
+    pub hook: fn(),
+}
+
+impl TestHook {
+    /// Whether the test is in the module of the hook.
+    pub fn applies_to(&self, test: &TestName) -> bool {
+        self.module_path.is_empty()
+            || test
+                .as_slice()
+                .strip_prefix(self.module_path)
+                .map_or(false, |rest| rest.starts_with("::"))
+    }
+}
+
 // The definition of a single test. A test runner will run a list of
 // these.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -420,6 +420,7 @@ macro_rules! describe {
                 test::Miri,
                 test::Clippy,
                 test::CompiletestTest,
+                test::RustdocSemver,
                 test::RustdocJSStd,
                 test::RustdocJSNotStd,
                 test::RustdocTheme,
@@ -365,6 +365,7 @@ fn run(self, builder: &Builder<'_>) -> PathBuf {
     RustDemangler, "src/tools/rust-demangler", "rust-demangler";
     RustInstaller, "src/tools/rust-installer", "fabricate", is_external_tool = true;
     RustdocTheme, "src/tools/rustdoc-themes", "rustdoc-themes";
+    RustdocSemver, "src/tools/rustdoc-semver", "rustdoc-semver";
     ExpandYamlAnchors, "src/tools/expand-yaml-anchors", "expand-yaml-anchors";
     LintDocs, "src/tools/lint-docs", "lint-docs";
 );
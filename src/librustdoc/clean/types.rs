@@ -60,6 +60,9 @@
     // These are later on moved into `CACHEKEY`, leaving the map empty.
     // Only here so that they can be filtered through the rustdoc passes.
     crate external_traits: Rc<RefCell<FxHashMap<DefId, Trait>>>,
+    /// Items from other crates referenced by this crate, inlined by the `inline-external-items`
+    /// pass for the JSON output. Empty unless `--json-include-external` is passed.
+    crate external_items: Vec<Item>,
     crate masked_crates: FxHashSet<CrateNum>,
     crate collapsed: bool,
 }
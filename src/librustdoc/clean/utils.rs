@@ -85,6 +85,7 @@
         externs,
         primitives,
         external_traits: cx.external_traits.clone(),
+        external_items: Vec::new(),
         masked_crates,
         collapsed: false,
     }
@@ -2,7 +2,7 @@
 use std::convert::TryFrom;
 use std::ffi::OsStr;
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_hir::def_id::DefId;
@@ -118,6 +118,12 @@ fn try_from(value: &str) -> Result<Self, Self::Error> {
     /// The path to a rustc-like binary to build tests with. If not set, we
     /// default to loading from $sysroot/bin/rustc.
     crate test_builder: Option<PathBuf>,
+    /// Whether to compile the doctests which allow it into a single binary, instead of compiling
+    /// each of them separately.
+    crate merge_doctests: bool,
+    /// Optional path to a directory recording the doctests which passed, so that they aren't run
+    /// again until they or the crate change.
+    crate doctest_cache: Option<PathBuf>,
 
     // Options that affect the documentation process
     /// The selected default set of passes to use.
@@ -185,6 +191,8 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             .field("runtool", &self.runtool)
             .field("runtool_args", &self.runtool_args)
             .field("enable-per-target-ignores", &self.enable_per_target_ignores)
+            .field("merge_doctests", &self.merge_doctests)
+            .field("doctest_cache", &self.doctest_cache)
             .field("run_check", &self.run_check)
             .finish()
     }
@@ -254,6 +262,20 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     crate document_private: bool,
     /// Document items that have `doc(hidden)`.
     crate document_hidden: bool,
+    /// Whether to also write the search index of the crate as JSON, to be read by
+    /// `rustdoc --search`.
+    crate search_index_json: bool,
+    /// Whether to include the items from other crates referenced by the documented crate in the
+    /// JSON output, instead of only their paths.
+    crate json_include_external: bool,
+    /// Whether to render graphs of the relationships between traits and types on the pages of
+    /// traits and modules.
+    crate generate_graphs: bool,
+    /// Whether to list the items missing documentation or examples, and the coverage per kind of
+    /// item, with `--show-coverage`.
+    crate coverage_details: bool,
+    /// The percentage of documented items below which `--show-coverage` reports an error.
+    crate coverage_threshold: Option<f64>,
     crate unstable_features: rustc_feature::UnstableFeatures,
 }
 
@@ -312,6 +334,7 @@ fn println_condition(condition: Condition) {
                     WhenDocumentPrivate => println!("  (when --document-private-items)"),
                     WhenNotDocumentPrivate => println!("  (when not --document-private-items)"),
                     WhenNotDocumentHidden => println!("  (when not --document-hidden-items)"),
+                    WhenJsonIncludeExternal => println!("  (when --json-include-external)"),
                 }
             }
 
@@ -355,6 +378,17 @@ fn println_condition(condition: Condition) {
             return Err(0);
         }
 
+        if let Some(query) = matches.opt_str("search") {
+            let doc_dir = matches.opt_str("o").unwrap_or_else(|| "doc".to_string());
+            return match crate::search::run(&query, Path::new(&doc_dir)) {
+                Ok(()) => Err(0),
+                Err(e) => {
+                    diag.struct_err(&e).emit();
+                    Err(1)
+                }
+            };
+        }
+
         if matches.free.is_empty() {
             diag.struct_err("missing file operand").emit();
             return Err(1);
@@ -581,9 +615,46 @@ fn println_condition(condition: Condition) {
         let runtool = matches.opt_str("runtool");
         let runtool_args = matches.opt_strs("runtool-arg");
         let enable_per_target_ignores = matches.opt_present("enable-per-target-ignores");
+        let merge_doctests = matches.opt_present("merge-doctests");
+        let doctest_cache = matches.opt_str("doctest-cache").map(PathBuf::from);
         let document_private = matches.opt_present("document-private-items");
         let document_hidden = matches.opt_present("document-hidden-items");
         let run_check = matches.opt_present("check");
+        let search_index_json = matches.opt_present("search-index-json");
+        let json_include_external = matches.opt_present("json-include-external");
+        let generate_graphs = matches.opt_present("generate-graphs");
+
+        if json_include_external && !output_format.map_or(false, |o| o.is_json()) {
+            diag.struct_err("the `--json-include-external` option requires `--output-format json`")
+                .emit();
+            return Err(1);
+        }
+
+        let coverage_details = matches.opt_present("coverage-details");
+        let coverage_threshold = match matches.opt_str("coverage-threshold") {
+            Some(s) => match s.parse::<f64>() {
+                Ok(threshold) if (0.0..=100.0).contains(&threshold) => Some(threshold),
+                _ => {
+                    diag.struct_err(&format!(
+                        "the `--coverage-threshold` option expects a percentage between 0 and \
+                         100, found `{}`",
+                        s
+                    ))
+                    .emit();
+                    return Err(1);
+                }
+            },
+            None => None,
+        };
+
+        if (coverage_details || coverage_threshold.is_some()) && !show_coverage {
+            diag.struct_err(
+                "the `--coverage-details` and `--coverage-threshold` options require \
+                 `--show-coverage`",
+            )
+            .emit();
+            return Err(1);
+        }
 
         let (lint_opts, describe_lints, lint_cap) = get_cmd_lint_options(matches, error_format);
 
@@ -618,6 +689,8 @@ fn println_condition(condition: Condition) {
             runtool_args,
             enable_per_target_ignores,
             test_builder,
+            merge_doctests,
+            doctest_cache,
             run_check,
             render_options: RenderOptions {
                 output,
@@ -640,6 +713,11 @@ fn println_condition(condition: Condition) {
                 generate_search_filter,
                 document_private,
                 document_hidden,
+                search_index_json,
+                json_include_external,
+                generate_graphs,
+                coverage_details,
+                coverage_threshold,
                 unstable_features: rustc_feature::UnstableFeatures::from_environment(
                     crate_name.as_deref(),
                 ),
@@ -3,6 +3,7 @@
 use rustc_driver::abort_on_err;
 use rustc_errors::emitter::{Emitter, EmitterWriter};
 use rustc_errors::json::JsonEmitter;
+use rustc_errors::sarif::SarifEmitter;
 use rustc_feature::UnstableFeatures;
 use rustc_hir::def::{Namespace::TypeNS, Res};
 use rustc_hir::def_id::{CrateNum, DefId, DefIndex, LocalDefId, CRATE_DEF_INDEX, LOCAL_CRATE};
@@ -169,8 +170,8 @@ impl<'tcx> DocContext<'tcx> {
 
 /// Creates a new diagnostic `Handler` that can be used to emit warnings and errors.
 ///
-/// If the given `error_format` is `ErrorOutputType::Json` and no `SourceMap` is given, a new one
-/// will be created for the handler.
+/// If the given `error_format` is `ErrorOutputType::Json` or `ErrorOutputType::Sarif` and no
+/// `SourceMap` is given, a new one will be created for the handler.
 crate fn new_handler(
     error_format: ErrorOutputType,
     source_map: Option<Lrc<source_map::SourceMap>>,
@@ -207,6 +208,12 @@ impl<'tcx> DocContext<'tcx> {
                 .ui_testing(debugging_opts.ui_testing),
             )
         }
+        ErrorOutputType::Sarif => {
+            let source_map = source_map.unwrap_or_else(|| {
+                Lrc::new(source_map::SourceMap::new(source_map::FilePathMapping::empty()))
+            });
+            Box::new(SarifEmitter::stderr(None, source_map))
+        }
     };
 
     rustc_errors::Handler::with_emitter_and_flags(
@@ -614,6 +621,7 @@ fn report_deprecated_attr(name: &str, diag: &rustc_errors::Handler) {
             WhenDocumentPrivate => ctxt.render_options.document_private,
             WhenNotDocumentPrivate => !ctxt.render_options.document_private,
             WhenNotDocumentHidden => !ctxt.render_options.document_hidden,
+            WhenJsonIncludeExternal => ctxt.render_options.json_include_external,
         };
         if run {
             debug!("running pass {}", p.pass.name);
@@ -1,7 +1,11 @@
 use rustc_ast as ast;
+use rustc_data_structures::fingerprint::Fingerprint;
+use rustc_data_structures::stable_hasher::StableHasher;
+use rustc_data_structures::svh::Svh;
 use rustc_data_structures::sync::Lrc;
 use rustc_errors::{ColorConfig, ErrorReported};
 use rustc_hir as hir;
+use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_hir::intravisit;
 use rustc_hir::{HirId, CRATE_HIR_ID};
 use rustc_interface::interface;
@@ -18,11 +22,14 @@
 
 use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::hash::Hash;
 use std::io::{self, Write};
 use std::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command, Stdio};
 use std::str;
+use std::sync::Arc;
 
 use crate::clean::Attributes;
 use crate::config::Options;
@@ -30,6 +37,10 @@
 use crate::html::markdown::{self, ErrorCodes, Ignore, LangString};
 use crate::passes::span_of_attrs;
 
+use self::merged::MergedDoctests;
+
+mod merged;
+
 #[derive(Clone, Default)]
 crate struct TestOptions {
     /// Whether to disable the default `extern crate my_crate;` when creating doctests.
@@ -121,6 +132,7 @@
             let mut global_ctxt = queries.global_ctxt()?.take();
 
             global_ctxt.enter(|tcx| {
+                collector.svh = Some(tcx.crate_hash(LOCAL_CRATE));
                 let krate = tcx.hir().krate();
 
                 let mut hir_collector = HirCollector {
@@ -254,6 +266,66 @@ fn run_test(
 
     let output_file = outdir.path().join("rust_out");
 
+    let mut compiler =
+        doctest_compiler(&options, &target, edition, &output_file, true, supports_color);
+    compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
+    compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
+    if as_test_harness {
+        compiler.arg("--test");
+    }
+    if no_run && !compile_fail {
+        compiler.arg("--emit=metadata");
+    }
+
+    let output = compile_from_stdin(compiler, &test);
+
+    struct Bomb<'a>(&'a str);
+    impl Drop for Bomb<'_> {
+        fn drop(&mut self) {
+            eprint!("{}", self.0);
+        }
+    }
+    let out = str::from_utf8(&output.stderr).unwrap();
+    let _bomb = Bomb(&out);
+    match (output.status.success(), compile_fail) {
+        (true, true) => {
+            return Err(TestFailure::UnexpectedCompilePass);
+        }
+        (true, false) => {}
+        (false, true) => {
+            if !error_codes.is_empty() {
+                // We used to check if the output contained "error[{}]: " but since we added the
+                // colored output, we can't anymore because of the color escape characters before
+                // the ":".
+                error_codes.retain(|err| !out.contains(&format!("error[{}]", err)));
+
+                if !error_codes.is_empty() {
+                    return Err(TestFailure::MissingErrorCodes(error_codes));
+                }
+            }
+        }
+        (false, false) => {
+            return Err(TestFailure::CompileError);
+        }
+    }
+
+    if no_run {
+        return Ok(());
+    }
+
+    run_binary(&output_file, runtool, runtool_args, should_panic, None)
+}
+
+/// Creates the command compiling a doctest binary, with the flags shared by all doctests. The
+/// caller adds the flags specific to the doctest, then passes the command to `compile_from_stdin`.
+fn doctest_compiler(
+    options: &Options,
+    target: &TargetTriple,
+    edition: Edition,
+    output_file: &Path,
+    single_codegen_unit: bool,
+    supports_color: bool,
+) -> Command {
     let rustc_binary = options
         .test_builder
         .as_deref()
@@ -263,34 +335,28 @@ fn run_test(
     for cfg in &options.cfgs {
         compiler.arg("--cfg").arg(&cfg);
     }
-    if let Some(sysroot) = options.maybe_sysroot {
+    if let Some(ref sysroot) = options.maybe_sysroot {
         compiler.arg("--sysroot").arg(sysroot);
     }
     compiler.arg("--edition").arg(&edition.to_string());
-    compiler.env("UNSTABLE_RUSTDOC_TEST_PATH", path);
-    compiler.env("UNSTABLE_RUSTDOC_TEST_LINE", format!("{}", line as isize - line_offset as isize));
-    compiler.arg("-o").arg(&output_file);
-    if as_test_harness {
-        compiler.arg("--test");
-    }
+    compiler.arg("-o").arg(output_file);
     for lib_str in &options.lib_strs {
         compiler.arg("-L").arg(&lib_str);
     }
     for extern_str in &options.extern_strs {
         compiler.arg("--extern").arg(&extern_str);
     }
-    compiler.arg("-Ccodegen-units=1");
+    if single_codegen_unit {
+        compiler.arg("-Ccodegen-units=1");
+    }
     for codegen_options_str in &options.codegen_options_strs {
         compiler.arg("-C").arg(&codegen_options_str);
     }
     for debugging_option_str in &options.debugging_opts_strs {
         compiler.arg("-Z").arg(&debugging_option_str);
     }
-    if no_run && !compile_fail {
-        compiler.arg("--emit=metadata");
-    }
     compiler.arg("--target").arg(match target {
-        TargetTriple::TargetTriple(s) => s,
+        TargetTriple::TargetTriple(s) => s.clone(),
         TargetTriple::TargetPath(path) => {
             path.to_str().expect("target path must be valid unicode").to_string()
         }
@@ -309,7 +375,11 @@ fn run_test(
             }
         }
     }
+    compiler
+}
 
+/// Runs `compiler` on `source`, which is given to it on its standard input.
+fn compile_from_stdin(mut compiler: Command, source: &str) -> process::Output {
     compiler.arg("-");
     compiler.stdin(Stdio::piped());
     compiler.stderr(Stdio::piped());
@@ -317,44 +387,20 @@ fn run_test(
     let mut child = compiler.spawn().expect("Failed to spawn rustc process");
     {
         let stdin = child.stdin.as_mut().expect("Failed to open stdin");
-        stdin.write_all(test.as_bytes()).expect("could write out test sources");
-    }
-    let output = child.wait_with_output().expect("Failed to read stdout");
-
-    struct Bomb<'a>(&'a str);
-    impl Drop for Bomb<'_> {
-        fn drop(&mut self) {
-            eprint!("{}", self.0);
-        }
-    }
-    let out = str::from_utf8(&output.stderr).unwrap();
-    let _bomb = Bomb(&out);
-    match (output.status.success(), compile_fail) {
-        (true, true) => {
-            return Err(TestFailure::UnexpectedCompilePass);
-        }
-        (true, false) => {}
-        (false, true) => {
-            if !error_codes.is_empty() {
-                // We used to check if the output contained "error[{}]: " but since we added the
-                // colored output, we can't anymore because of the color escape characters before
-                // the ":".
-                error_codes.retain(|err| !out.contains(&format!("error[{}]", err)));
-
-                if !error_codes.is_empty() {
-                    return Err(TestFailure::MissingErrorCodes(error_codes));
-                }
-            }
-        }
-        (false, false) => {
-            return Err(TestFailure::CompileError);
-        }
-    }
-
-    if no_run {
-        return Ok(());
+        stdin.write_all(source.as_bytes()).expect("could write out test sources");
     }
+    child.wait_with_output().expect("Failed to read stdout")
+}
 
+/// Runs a compiled doctest, under the runtool if one was given. `entry` selects the doctest to run
+/// from a binary built from merged doctests.
+fn run_binary(
+    output_file: &Path,
+    runtool: Option<String>,
+    runtool_args: Vec<String>,
+    should_panic: bool,
+    entry: Option<&str>,
+) -> Result<(), TestFailure> {
     // Run the code!
     let mut cmd;
 
@@ -365,6 +411,9 @@ fn drop(&mut self) {
     } else {
         cmd = Command::new(output_file);
     }
+    if let Some(entry) = entry {
+        cmd.env(merged::ENTRY_VAR, entry);
+    }
 
     match cmd.output() {
         Err(e) => return Err(TestFailure::ExecutionError(e)),
@@ -418,91 +467,12 @@ fn drop(&mut self) {
 
     // Uses librustc_ast to parse the doctest and find if there's a main fn and the extern
     // crate already is included.
-    let result = rustc_driver::catch_fatal_errors(|| {
-        rustc_span::with_session_globals(edition, || {
-            use rustc_errors::emitter::{Emitter, EmitterWriter};
-            use rustc_errors::Handler;
-            use rustc_parse::maybe_new_parser_from_source_str;
-            use rustc_session::parse::ParseSess;
-            use rustc_span::source_map::FilePathMapping;
-
-            let filename = FileName::anon_source_code(s);
-            let source = crates + everything_else;
-
-            // Any errors in parsing should also appear when the doctest is compiled for real, so just
-            // send all the errors that librustc_ast emits directly into a `Sink` instead of stderr.
-            let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
-            supports_color =
-                EmitterWriter::stderr(ColorConfig::Auto, None, false, false, Some(80), false)
-                    .supports_color();
-
-            let emitter =
-                EmitterWriter::new(box io::sink(), None, false, false, false, None, false);
-
-            // FIXME(misdreavus): pass `-Z treat-err-as-bug` to the doctest parser
-            let handler = Handler::with_emitter(false, None, box emitter);
-            let sess = ParseSess::with_span_handler(handler, sm);
-
-            let mut found_main = false;
-            let mut found_extern_crate = cratename.is_none();
-            let mut found_macro = false;
-
-            let mut parser = match maybe_new_parser_from_source_str(&sess, filename, source) {
-                Ok(p) => p,
-                Err(errs) => {
-                    for mut err in errs {
-                        err.cancel();
-                    }
-
-                    return (found_main, found_extern_crate, found_macro);
-                }
-            };
-
-            loop {
-                match parser.parse_item() {
-                    Ok(Some(item)) => {
-                        if !found_main {
-                            if let ast::ItemKind::Fn(..) = item.kind {
-                                if item.ident.name == sym::main {
-                                    found_main = true;
-                                }
-                            }
-                        }
-
-                        if !found_extern_crate {
-                            if let ast::ItemKind::ExternCrate(original) = item.kind {
-                                // This code will never be reached if `cratename` is none because
-                                // `found_extern_crate` is initialized to `true` if it is none.
-                                let cratename = cratename.unwrap();
-
-                                match original {
-                                    Some(name) => found_extern_crate = name.as_str() == cratename,
-                                    None => found_extern_crate = item.ident.as_str() == cratename,
-                                }
-                            }
-                        }
-
-                        if !found_macro {
-                            if let ast::ItemKind::MacCall(..) = item.kind {
-                                found_macro = true;
-                            }
-                        }
-
-                        if found_main && found_extern_crate {
-                            break;
-                        }
-                    }
-                    Ok(None) => break,
-                    Err(mut e) => {
-                        e.cancel();
-                        break;
-                    }
-                }
-            }
-
-            (found_main, found_extern_crate, found_macro)
-        })
-    });
+    let result = check_for_main_and_extern_crate(
+        cratename,
+        crates + everything_else,
+        edition,
+        &mut supports_color,
+    );
     let (already_has_main, already_has_extern_crate, found_macro) = match result {
         Ok(result) => result,
         Err(ErrorReported) => {
@@ -586,6 +556,101 @@ fn drop(&mut self) {
     (prog, line_offset, supports_color)
 }
 
+/// Parses the doctest source and returns whether it defines a `main` function, whether it already
+/// has an `extern crate` for `cratename`, and whether it has macro invocations at the top level,
+/// which may hide a `main` function.
+fn check_for_main_and_extern_crate(
+    cratename: Option<&str>,
+    source: String,
+    edition: Edition,
+    supports_color: &mut bool,
+) -> Result<(bool, bool, bool), ErrorReported> {
+    rustc_driver::catch_fatal_errors(|| {
+        rustc_span::with_session_globals(edition, || {
+            use rustc_errors::emitter::{Emitter, EmitterWriter};
+            use rustc_errors::Handler;
+            use rustc_parse::maybe_new_parser_from_source_str;
+            use rustc_session::parse::ParseSess;
+            use rustc_span::source_map::FilePathMapping;
+
+            let filename = FileName::anon_source_code(&source);
+
+            // Any errors in parsing should also appear when the doctest is compiled for real, so just
+            // send all the errors that librustc_ast emits directly into a `Sink` instead of stderr.
+            let sm = Lrc::new(SourceMap::new(FilePathMapping::empty()));
+            *supports_color =
+                EmitterWriter::stderr(ColorConfig::Auto, None, false, false, Some(80), false)
+                    .supports_color();
+
+            let emitter =
+                EmitterWriter::new(box io::sink(), None, false, false, false, None, false);
+
+            // FIXME(misdreavus): pass `-Z treat-err-as-bug` to the doctest parser
+            let handler = Handler::with_emitter(false, None, box emitter);
+            let sess = ParseSess::with_span_handler(handler, sm);
+
+            let mut found_main = false;
+            let mut found_extern_crate = cratename.is_none();
+            let mut found_macro = false;
+
+            let mut parser = match maybe_new_parser_from_source_str(&sess, filename, source) {
+                Ok(p) => p,
+                Err(errs) => {
+                    for mut err in errs {
+                        err.cancel();
+                    }
+
+                    return (found_main, found_extern_crate, found_macro);
+                }
+            };
+
+            loop {
+                match parser.parse_item() {
+                    Ok(Some(item)) => {
+                        if !found_main {
+                            if let ast::ItemKind::Fn(..) = item.kind {
+                                if item.ident.name == sym::main {
+                                    found_main = true;
+                                }
+                            }
+                        }
+
+                        if !found_extern_crate {
+                            if let ast::ItemKind::ExternCrate(original) = item.kind {
+                                // This code will never be reached if `cratename` is none because
+                                // `found_extern_crate` is initialized to `true` if it is none.
+                                let cratename = cratename.unwrap();
+
+                                match original {
+                                    Some(name) => found_extern_crate = name.as_str() == cratename,
+                                    None => found_extern_crate = item.ident.as_str() == cratename,
+                                }
+                            }
+                        }
+
+                        if !found_macro {
+                            if let ast::ItemKind::MacCall(..) = item.kind {
+                                found_macro = true;
+                            }
+                        }
+
+                        if found_main && found_extern_crate {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(mut e) => {
+                        e.cancel();
+                        break;
+                    }
+                }
+            }
+
+            (found_main, found_extern_crate, found_macro)
+        })
+    })
+}
+
 // FIXME(aburka): use a real parser to deal with multiline attributes
 fn partition_source(s: &str) -> (String, String, String) {
     #[derive(Copy, Clone, PartialEq)]
@@ -698,6 +763,11 @@ fn register_header(&mut self, _name: &str, _level: u32) {}
     source_map: Option<Lrc<SourceMap>>,
     filename: Option<PathBuf>,
     visited_tests: HashMap<(String, usize), usize>,
+    /// The hash of the crate, which `--doctest-cache` uses to know if it changed. This isn't
+    /// known when testing a Markdown file, which then isn't cached.
+    svh: Option<Svh>,
+    /// The doctests merged into a single binary with `--merge-doctests`, by edition.
+    merged: HashMap<Edition, Arc<MergedDoctests>>,
 }
 
 impl Collector {
@@ -722,6 +792,8 @@ impl Collector {
             source_map,
             filename,
             visited_tests: HashMap::new(),
+            svh: None,
+            merged: HashMap::new(),
         }
     }
 
@@ -754,6 +826,40 @@ fn get_filename(&self) -> FileName {
             FileName::Custom("input".to_owned())
         }
     }
+
+    /// Returns the file recording that the doctest passed with `--doctest-cache`. Its name is a
+    /// hash of everything the result of the doctest depends on, including the hash of the crate.
+    fn cache_entry(&self, test: &str, config: &LangString, edition: Edition) -> Option<PathBuf> {
+        let dir = self.options.doctest_cache.as_ref()?;
+        let svh = self.svh?;
+
+        let mut hasher = StableHasher::new();
+        svh.as_u64().hash(&mut hasher);
+        test.hash(&mut hasher);
+        self.cratename.hash(&mut hasher);
+        edition.to_string().hash(&mut hasher);
+        self.opts.no_crate_inject.hash(&mut hasher);
+        self.opts.display_warnings.hash(&mut hasher);
+        self.opts.attrs.hash(&mut hasher);
+        config.should_panic.hash(&mut hasher);
+        config.no_run.hash(&mut hasher);
+        config.compile_fail.hash(&mut hasher);
+        config.test_harness.hash(&mut hasher);
+        config.error_codes.hash(&mut hasher);
+        self.options.cfgs.hash(&mut hasher);
+        self.options.lib_strs.hash(&mut hasher);
+        self.options.extern_strs.hash(&mut hasher);
+        self.options.codegen_options_strs.hash(&mut hasher);
+        self.options.debugging_opts_strs.hash(&mut hasher);
+        self.options.maybe_sysroot.hash(&mut hasher);
+        self.options.target.to_string().hash(&mut hasher);
+        self.options.test_builder.hash(&mut hasher);
+        self.options.runtool.hash(&mut hasher);
+        self.options.runtool_args.hash(&mut hasher);
+        let hash: Fingerprint = hasher.finish();
+
+        Some(dir.join(hash.to_hex()))
+    }
 }
 
 impl Tester for Collector {
@@ -808,40 +914,74 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
             )
         };
 
+        let ignore = match config.ignore {
+            Ignore::All => true,
+            Ignore::None => false,
+            Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
+        };
+        let cache_entry = self.cache_entry(&test, &config, edition);
+        // Doctests which check the compilation itself, or which need their own harness, are
+        // always compiled on their own.
+        let merged = if self.options.merge_doctests
+            && !ignore
+            && !config.compile_fail
+            && !config.no_run
+            && !config.test_harness
+        {
+            let merged = self.merged.entry(edition).or_insert_with(|| {
+                let merged =
+                    MergedDoctests::new(cratename.clone(), options.clone(), opts.clone(), edition);
+                Arc::new(merged)
+            });
+            if merged.add(&test, &test_id) { Some(Arc::clone(merged)) } else { None }
+        } else {
+            None
+        };
+
         debug!("creating test {}: {}", name, test);
         self.tests.push(testing::TestDescAndFn {
             desc: testing::TestDesc {
                 name: testing::DynTestName(name),
-                ignore: match config.ignore {
-                    Ignore::All => true,
-                    Ignore::None => false,
-                    Ignore::Some(ref ignores) => ignores.iter().any(|s| target_str.contains(s)),
-                },
+                ignore,
                 // compiler failures are test failures
                 should_panic: testing::ShouldPanic::No,
                 allow_fail: config.allow_fail,
                 test_type: testing::TestType::DocTest,
             },
             testfn: testing::DynTestFn(box move || {
-                let res = run_test(
-                    &test,
-                    &cratename,
-                    line,
-                    options,
-                    config.should_panic,
-                    config.no_run,
-                    config.test_harness,
-                    runtool,
-                    runtool_args,
-                    target,
-                    config.compile_fail,
-                    config.error_codes,
-                    &opts,
-                    edition,
-                    outdir,
-                    path,
-                    &test_id,
-                );
+                if let Some(ref entry) = cache_entry {
+                    if entry.exists() {
+                        return;
+                    }
+                }
+
+                // If the merged doctests failed to compile, this one is compiled on its own, to
+                // report its own errors.
+                let merged_res = merged.and_then(|merged| {
+                    merged.run(&test_id, config.should_panic, runtool.clone(), runtool_args.clone())
+                });
+                let res = match merged_res {
+                    Some(res) => res,
+                    None => run_test(
+                        &test,
+                        &cratename,
+                        line,
+                        options,
+                        config.should_panic,
+                        config.no_run,
+                        config.test_harness,
+                        runtool,
+                        runtool_args,
+                        target,
+                        config.compile_fail,
+                        config.error_codes,
+                        &opts,
+                        edition,
+                        outdir,
+                        path,
+                        &test_id,
+                    ),
+                };
 
                 if let Err(err) = res {
                     match err {
@@ -900,6 +1040,15 @@ fn add_test(&mut self, test: String, config: LangString, line: usize) {
 
                     panic::resume_unwind(box ());
                 }
+
+                if let Some(entry) = cache_entry {
+                    // Failing to record the result only means that the doctest runs again next
+                    // time, so errors are ignored.
+                    if let Some(dir) = entry.parent() {
+                        let _ = fs::create_dir_all(dir);
+                    }
+                    let _ = fs::File::create(entry);
+                }
             }),
         });
     }
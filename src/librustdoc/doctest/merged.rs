@@ -0,0 +1,218 @@
+//! Merging of doctests with `--merge-doctests`.
+//!
+//! The doctests which allow it are compiled together into a single binary per edition, where each
+//! of them becomes a function. The binary is built when the first of its doctests runs, so the
+//! binaries of the different editions are built in parallel by the test threads. Each doctest then
+//! runs the binary in its own process, selecting its function through an environment variable,
+//! so doctests still pass or fail (or panic) separately.
+
+use std::mem;
+use std::sync::Mutex;
+
+use rustc_span::edition::Edition;
+use tempfile::Builder as TempFileBuilder;
+
+use super::{
+    check_for_main_and_extern_crate, compile_from_stdin, doctest_compiler, partition_source,
+    run_binary, DirState, TestFailure, TestOptions,
+};
+use crate::config::Options;
+
+/// The environment variable telling a merged binary which doctest to run.
+crate const ENTRY_VAR: &str = "RUSTDOC_DOCTEST_ENTRY";
+
+/// The doctests of one edition, compiled into a single binary.
+crate struct MergedDoctests {
+    cratename: String,
+    options: Options,
+    opts: TestOptions,
+    edition: Edition,
+    state: Mutex<State>,
+}
+
+enum State {
+    /// The doctests are still being collected, or none of them ran yet. Contains the id and the
+    /// function of each doctest.
+    Pending(Vec<(String, String)>),
+    /// The binary was built in the given directory.
+    Built(DirState),
+    /// The merged doctests failed to compile, so each of them is compiled on its own instead.
+    Failed,
+}
+
+impl MergedDoctests {
+    crate fn new(
+        cratename: String,
+        options: Options,
+        opts: TestOptions,
+        edition: Edition,
+    ) -> MergedDoctests {
+        MergedDoctests {
+            cratename,
+            options,
+            opts,
+            edition,
+            state: Mutex::new(State::Pending(Vec::new())),
+        }
+    }
+
+    /// Adds the doctest to the binary if it can be merged with the others, and returns whether it
+    /// was added.
+    crate fn add(&self, test: &str, test_id: &str) -> bool {
+        let function = match make_merged_test(test, &self.cratename, self.edition, test_id) {
+            Some(function) => function,
+            None => return false,
+        };
+        match *self.state.lock().unwrap() {
+            State::Pending(ref mut tests) => {
+                tests.push((test_id.to_string(), function));
+                true
+            }
+            State::Built(_) | State::Failed => false,
+        }
+    }
+
+    /// Runs the doctest from the binary, building the binary first if it wasn't already. Returns
+    /// `None` if the merged doctests failed to compile.
+    crate fn run(
+        &self,
+        test_id: &str,
+        should_panic: bool,
+        runtool: Option<String>,
+        runtool_args: Vec<String>,
+    ) -> Option<Result<(), TestFailure>> {
+        let mut state = self.state.lock().unwrap();
+        if let State::Pending(ref mut tests) = *state {
+            let tests = mem::take(tests);
+            *state = self.build(&tests);
+        }
+        let output_file = match *state {
+            State::Built(ref outdir) => outdir.path().join("rust_out"),
+            State::Pending(_) | State::Failed => return None,
+        };
+        // Let the other doctests run the binary at the same time.
+        drop(state);
+
+        Some(run_binary(&output_file, runtool, runtool_args, should_panic, Some(test_id)))
+    }
+
+    fn build(&self, tests: &[(String, String)]) -> State {
+        let source = merged_source(&self.cratename, &self.opts, tests);
+        debug!("merged doctests:\n{}", source);
+
+        let outdir = if let Some(ref path) = self.options.persist_doctests {
+            let path = path.join(format!("merged_{}", self.edition));
+
+            std::fs::create_dir_all(&path)
+                .expect("Couldn't create directory for doctest executables");
+
+            DirState::Perm(path)
+        } else {
+            DirState::Temp(
+                TempFileBuilder::new()
+                    .prefix("rustdoctest")
+                    .tempdir()
+                    .expect("rustdoc needs a tempdir"),
+            )
+        };
+        let output_file = outdir.path().join("rust_out");
+
+        // The binary is bigger than a single doctest, so unlike them it is compiled with several
+        // codegen units, in parallel.
+        let compiler = doctest_compiler(
+            &self.options,
+            &self.options.target,
+            self.edition,
+            &output_file,
+            false,
+            false,
+        );
+        let output = compile_from_stdin(compiler, &source);
+        if output.status.success() {
+            State::Built(outdir)
+        } else {
+            debug!(
+                "merged doctests failed to compile:\n{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            State::Failed
+        }
+    }
+}
+
+/// Turns a doctest into the function `_doctest_main_{test_id}` of a merged binary, or returns
+/// `None` if it can't be merged: doctests with crate attributes, `extern crate` items or their own
+/// `main` function need to be compiled on their own.
+crate fn make_merged_test(
+    s: &str,
+    cratename: &str,
+    edition: Edition,
+    test_id: &str,
+) -> Option<String> {
+    let (crate_attrs, everything_else, crates) = partition_source(s);
+    if crate_attrs.lines().any(|line| line.trim_start().starts_with("#![")) || !crates.is_empty() {
+        return None;
+    }
+    let everything_else = everything_else.trim();
+
+    let mut supports_color = false;
+    let (has_main, _, found_macro) = check_for_main_and_extern_crate(
+        Some(cratename),
+        everything_else.to_string(),
+        edition,
+        &mut supports_color,
+    )
+    .ok()?;
+    // A `main` function may be hidden in a macro invocation, see `make_test`.
+    if has_main || (found_macro && everything_else.contains("fn main")) {
+        return None;
+    }
+
+    let function = if everything_else.ends_with("(())") {
+        format!(
+            "#[allow(non_snake_case)]\nfn _doctest_main_{}() {{ \
+             fn _inner() -> Result<(), impl core::fmt::Debug> {{\n{}\n}} _inner().unwrap() }}\n",
+            test_id, everything_else
+        )
+    } else {
+        format!(
+            "#[allow(non_snake_case)]\nfn _doctest_main_{}() {{\n{}\n}}\n",
+            test_id, everything_else
+        )
+    };
+    Some(function)
+}
+
+/// Creates the source of a merged binary, whose `main` function calls the function of the doctest
+/// named by `ENTRY_VAR`.
+crate fn merged_source(cratename: &str, opts: &TestOptions, tests: &[(String, String)]) -> String {
+    let mut prog = String::new();
+
+    // The same attributes as in `make_test`.
+    if opts.attrs.is_empty() && !opts.display_warnings {
+        prog.push_str("#![allow(unused)]\n");
+    }
+    for attr in &opts.attrs {
+        prog.push_str(&format!("#![{}]\n", attr));
+    }
+    if !opts.no_crate_inject
+        && cratename != "std"
+        && tests.iter().any(|(_, function)| function.contains(cratename))
+    {
+        prog.push_str(&format!("extern crate {};\n", cratename));
+    }
+
+    for (_, function) in tests {
+        prog.push_str(function);
+    }
+
+    prog.push_str(&format!(
+        "fn main() {{\n    match &*::std::env::var(\"{}\").unwrap_or_default() {{\n",
+        ENTRY_VAR
+    ));
+    for (test_id, _) in tests {
+        prog.push_str(&format!("        \"{0}\" => _doctest_main_{0}(),\n", test_id));
+    }
+    prog.push_str("        entry => panic!(\"unknown doctest `{}`\", entry),\n    }\n}\n");
+    prog
+}
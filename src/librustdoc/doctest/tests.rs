@@ -1,3 +1,4 @@
+use super::merged::{make_merged_test, merged_source};
 use super::{make_test, TestOptions};
 use rustc_span::edition::DEFAULT_EDITION;
 
@@ -312,3 +313,72 @@ fn main() { #[allow(non_snake_case)] fn _doctest_main__some_unique_name() {
         make_test(input, None, false, &opts, DEFAULT_EDITION, Some("_some_unique_name"));
     assert_eq!((output, len), (expected, 2));
 }
+
+#[test]
+fn make_merged_test_basic() {
+    // wraps the doctest in a function named after its id
+    let input = "assert_eq!(2+2, 4);";
+    let expected = "#[allow(non_snake_case)]
+fn _doctest_main_foo_rs_1_0() {
+assert_eq!(2+2, 4);
+}
+"
+    .to_string();
+    let output = make_merged_test(input, "asdf", DEFAULT_EDITION, "foo_rs_1_0");
+    assert_eq!(output, Some(expected));
+}
+
+#[test]
+fn make_merged_test_returns_result() {
+    let input = "let x: u32 = \"1\".parse()?;
+Ok::<(), std::num::ParseIntError>(())";
+    let expected = "#[allow(non_snake_case)]
+fn _doctest_main_foo_rs_1_0() { fn _inner() -> Result<(), impl core::fmt::Debug> {
+let x: u32 = \"1\".parse()?;
+Ok::<(), std::num::ParseIntError>(())
+} _inner().unwrap() }
+"
+    .to_string();
+    let output = make_merged_test(input, "asdf", DEFAULT_EDITION, "foo_rs_1_0");
+    assert_eq!(output, Some(expected));
+}
+
+#[test]
+fn make_merged_test_not_mergeable() {
+    // doctests with crate attributes, extern crates or their own `main` are compiled on their own
+    let inputs = [
+        "#![feature(sick_rad)]\nassert_eq!(2+2, 4);",
+        "extern crate asdf;\nassert_eq!(2+2, 4);",
+        "fn main() {\n    assert_eq!(2+2, 4);\n}",
+        "test_wrapper! {\n    fn main() {}\n}",
+    ];
+    for input in &inputs {
+        let output = make_merged_test(input, "asdf", DEFAULT_EDITION, "foo_rs_1_0");
+        assert_eq!(output, None, "{}", input);
+    }
+}
+
+#[test]
+fn merged_source_dispatches_on_entry() {
+    let opts = TestOptions::default();
+    let tests = vec![
+        ("a_1_0".to_string(), "fn _doctest_main_a_1_0() {\nasdf::foo();\n}\n".to_string()),
+        ("a_5_0".to_string(), "fn _doctest_main_a_5_0() {\n}\n".to_string()),
+    ];
+    let expected = "#![allow(unused)]
+extern crate asdf;
+fn _doctest_main_a_1_0() {
+asdf::foo();
+}
+fn _doctest_main_a_5_0() {
+}
+fn main() {
+    match &*::std::env::var(\"RUSTDOC_DOCTEST_ENTRY\").unwrap_or_default() {
+        \"a_1_0\" => _doctest_main_a_1_0(),
+        \"a_5_0\" => _doctest_main_a_5_0(),
+        entry => panic!(\"unknown doctest `{}`\", entry),
+    }
+}
+";
+    assert_eq!(merged_source("asdf", &opts, &tests), expected);
+}
@@ -96,6 +96,10 @@ fn fold_crate(&mut self, mut c: Crate) -> Crate {
                 c.external_traits.borrow_mut().insert(k, v);
             }
         }
+        c.external_items = std::mem::take(&mut c.external_items)
+            .into_iter()
+            .filter_map(|i| self.fold_item(i))
+            .collect();
         c
     }
 }
@@ -197,7 +197,17 @@ impl Cache {
         }
 
         cache.stack.push(krate.name.to_string());
+        // The inlined external items don't live in this crate's module tree, so they must not be
+        // given paths from it. Their impls are only collected into `cache.impls`, to be rendered
+        // along with the items they belong to.
+        let (impls, external_items): (Vec<_>, Vec<_>) = mem::take(&mut krate.external_items)
+            .into_iter()
+            .partition(|item| matches!(*item.kind, clean::ImplItem(_)));
         krate = cache.fold_crate(krate);
+        for item in impls {
+            cache.fold_item(item);
+        }
+        krate.external_items = external_items;
 
         for (trait_did, dids, impl_) in cache.orphan_trait_impls.drain(..) {
             if cache.traits.contains_key(&trait_did) {
@@ -2,6 +2,7 @@
 use std::path::Path;
 
 use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def_id::DefId;
 use rustc_span::symbol::{sym, Symbol};
 use serde::Serialize;
 
@@ -11,6 +12,7 @@
 use crate::formats::item_type::ItemType;
 use crate::html::markdown::short_markdown_summary;
 use crate::html::render::{Generic, IndexItem, IndexItemFunctionType, RenderType, TypeWithKind};
+use crate::search;
 
 /// Indicates where an external crate can be found.
 crate enum ExternalLocation {
@@ -61,8 +63,13 @@
         .unwrap_or(Unknown) // Well, at least we tried.
 }
 
-/// Builds the search index from the collected metadata
-crate fn build_index(krate: &clean::Crate, cache: &mut Cache) -> String {
+/// Builds the search index from the collected metadata, along with its JSON form for
+/// `rustdoc --search` if `emit_json` is set.
+crate fn build_index(
+    krate: &clean::Crate,
+    cache: &mut Cache,
+    emit_json: bool,
+) -> (String, Option<String>) {
     let mut defid_to_pathid = FxHashMap::default();
     let mut crate_items = Vec::with_capacity(cache.search_index.len());
     let mut crate_paths = vec![];
@@ -97,7 +104,12 @@
     let mut lastpath = String::new();
     let mut lastpathid = 0usize;
 
+    let mut json_items = Vec::new();
     for item in search_index {
+        if emit_json {
+            json_items.push(search_index_entry(item, paths));
+        }
+
         item.parent_idx = item.parent.and_then(|defid| {
             if defid_to_pathid.contains_key(&defid) {
                 defid_to_pathid.get(&defid).copied()
@@ -130,6 +142,19 @@
         .map(|module| module.doc_value().map_or_else(String::new, |s| short_markdown_summary(&s)))
         .unwrap_or_default();
 
+    let json = if emit_json {
+        let index = search::CrateIndex {
+            format_version: search::FORMAT_VERSION,
+            name: krate.name.to_string(),
+            doc: crate_doc.clone(),
+            items: json_items,
+            aliases: aliases.clone(),
+        };
+        Some(serde_json::to_string(&index).expect("failed serde conversion"))
+    } else {
+        None
+    };
+
     #[derive(Serialize)]
     struct CrateData<'a> {
         doc: String,
@@ -146,7 +171,7 @@ struct CrateData<'a> {
     }
 
     // Collect the index into a string
-    format!(
+    let js = format!(
         r#""{}":{}"#,
         krate.name,
         serde_json::to_string(&CrateData {
@@ -161,7 +186,48 @@ struct CrateData<'a> {
         .replace("'", r"\'")
         // We need to escape double quotes for the JSON.
         .replace("\\\"", "\\\\\"")
-    )
+    );
+    (js, json)
+}
+
+/// Converts an item of the search index into its entry in the JSON search index, with the full
+/// path and the parent spelled out.
+fn search_index_entry(
+    item: &IndexItem,
+    paths: &FxHashMap<DefId, (Vec<String>, ItemType)>,
+) -> search::IndexEntry {
+    search::IndexEntry {
+        kind: item.ty.as_str().to_string(),
+        name: item.name.clone(),
+        path: item.path.clone(),
+        desc: item.desc.clone(),
+        parent: item.parent.and_then(|did| paths.get(&did)).map(|&(ref fqp, ty)| search::Parent {
+            kind: ty.as_str().to_string(),
+            name: fqp.last().unwrap().clone(),
+        }),
+        signature: item.search_type.as_ref().and_then(search_signature),
+    }
+}
+
+/// Converts the type of a function in the search index, if all of its types have a name.
+fn search_signature(search_type: &IndexItemFunctionType) -> Option<search::Signature> {
+    fn convert(types: &[TypeWithKind]) -> Option<Vec<search::SignatureType>> {
+        types
+            .iter()
+            .map(|t| {
+                Some(search::SignatureType {
+                    name: t.ty.name.clone()?,
+                    kind: ItemType::from(t.kind).as_str().to_string(),
+                    generics: t.ty.generics.iter().flatten().map(|g| g.name.clone()).collect(),
+                })
+            })
+            .collect()
+    }
+
+    Some(search::Signature {
+        inputs: convert(&search_type.inputs)?,
+        output: convert(search_type.output.as_deref().unwrap_or_default())?,
+    })
 }
 
 crate fn get_index_search_type(item: &clean::Item) -> Option<IndexItemFunctionType> {
@@ -0,0 +1,379 @@
+//! Graphs of the relationships between traits and types, rendered as inline SVG on the pages of
+//! traits and modules with `--generate-graphs`.
+//!
+//! The layout is deliberately simple: nodes are placed in rows so that every edge points
+//! upwards, from implementors to traits and from traits to their supertraits, which is enough for
+//! the mostly tree-shaped graphs found in practice.
+
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
+use rustc_hir as hir;
+use rustc_hir::def_id::DefId;
+use rustc_span::symbol::sym;
+
+use crate::clean::{self, GetDefId};
+use crate::formats::cache::Cache;
+use crate::html::escape::Escape;
+use crate::html::format::{href, Buffer};
+
+const NODE_HEIGHT: usize = 30;
+const ROW_GAP: usize = 50;
+const NODE_GAP: usize = 20;
+/// The width of a character of the labels, which use a monospace font.
+const CHAR_WIDTH: usize = 9;
+const NODE_PADDING: usize = 16;
+const MARGIN: usize = 10;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+crate enum NodeKind {
+    /// The item whose page the graph is on.
+    Current,
+    Trait,
+    Type,
+    AssocType,
+}
+
+impl NodeKind {
+    fn class(self) -> &'static str {
+        match self {
+            NodeKind::Current => "current",
+            NodeKind::Trait => "trait",
+            NodeKind::Type => "type",
+            NodeKind::AssocType => "associatedtype",
+        }
+    }
+}
+
+/// What makes two nodes the same: the item they stand for if there is one, their label otherwise.
+/// Labels can't be relied on in general, since types from different modules can share a name, and
+/// the same type can be printed with or without its generic arguments.
+#[derive(Clone, PartialEq, Eq, Hash)]
+crate enum NodeKey {
+    Item(DefId),
+    Label(String),
+}
+
+struct Node {
+    label: String,
+    href: Option<String>,
+    kind: NodeKind,
+}
+
+struct Edge {
+    from: usize,
+    to: usize,
+    label: Option<String>,
+    dashed: bool,
+}
+
+#[derive(Default)]
+crate struct Graph {
+    nodes: Vec<Node>,
+    /// The index of each node by key, since the same type can be reached in several ways.
+    keys: FxHashMap<NodeKey, usize>,
+    edges: Vec<Edge>,
+}
+
+impl Graph {
+    crate fn add_node(
+        &mut self,
+        key: NodeKey,
+        label: String,
+        href: Option<String>,
+        kind: NodeKind,
+    ) -> usize {
+        if let Some(&index) = self.keys.get(&key) {
+            let node = &mut self.nodes[index];
+            if node.href.is_none() {
+                node.href = href;
+            }
+            return index;
+        }
+        let index = self.nodes.len();
+        self.keys.insert(key, index);
+        self.nodes.push(Node { label, href, kind });
+        index
+    }
+
+    crate fn add_edge(&mut self, from: usize, to: usize, label: Option<String>, dashed: bool) {
+        if from == to || self.edges.iter().any(|e| e.from == from && e.to == to) {
+            return;
+        }
+        self.edges.push(Edge { from, to, label, dashed });
+    }
+
+    crate fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Computes the row of each node, as the length of the longest path from it to a node
+    /// without outgoing edges. Edges closing a cycle are ignored.
+    fn ranks(&self) -> Vec<usize> {
+        fn visit(
+            graph: &Graph,
+            node: usize,
+            ranks: &mut Vec<Option<usize>>,
+            visiting: &mut Vec<bool>,
+        ) -> usize {
+            if let Some(rank) = ranks[node] {
+                return rank;
+            }
+            visiting[node] = true;
+            let mut rank = 0;
+            for edge in graph.edges.iter().filter(|e| e.from == node) {
+                if !visiting[edge.to] {
+                    rank = rank.max(visit(graph, edge.to, ranks, visiting) + 1);
+                }
+            }
+            visiting[node] = false;
+            ranks[node] = Some(rank);
+            rank
+        }
+
+        let mut ranks = vec![None; self.nodes.len()];
+        let mut visiting = vec![false; self.nodes.len()];
+        (0..self.nodes.len()).map(|node| visit(self, node, &mut ranks, &mut visiting)).collect()
+    }
+
+    /// Returns the position of the top left corner of each node, its width, and the size of the
+    /// whole graph. Nodes without any edges are left out.
+    fn layout(&self) -> (Vec<Option<(usize, usize, usize)>>, usize, usize) {
+        let connected: FxHashSet<usize> =
+            self.edges.iter().flat_map(|e| vec![e.from, e.to]).collect();
+        let ranks = self.ranks();
+        let mut rows: Vec<Vec<usize>> = Vec::new();
+        for node in 0..self.nodes.len() {
+            if connected.contains(&node) {
+                let rank = ranks[node];
+                if rows.len() <= rank {
+                    rows.resize(rank + 1, Vec::new());
+                }
+                rows[rank].push(node);
+            }
+        }
+
+        let node_width =
+            |node: usize| self.nodes[node].label.chars().count() * CHAR_WIDTH + NODE_PADDING;
+        let row_width = |row: &[usize]| {
+            row.iter().map(|&node| node_width(node)).sum::<usize>()
+                + NODE_GAP * row.len().saturating_sub(1)
+        };
+        let width = rows.iter().map(|row| row_width(row)).max().unwrap_or(0) + 2 * MARGIN;
+        let height = (rows.len() * (NODE_HEIGHT + ROW_GAP)).saturating_sub(ROW_GAP) + 2 * MARGIN;
+
+        let mut positions = vec![None; self.nodes.len()];
+        for (rank, row) in rows.iter().enumerate() {
+            // Center each row.
+            let mut x = (width - row_width(row)) / 2;
+            let y = MARGIN + rank * (NODE_HEIGHT + ROW_GAP);
+            for &node in row {
+                positions[node] = Some((x, y, node_width(node)));
+                x += node_width(node) + NODE_GAP;
+            }
+        }
+        (positions, width, height)
+    }
+
+    crate fn render(&self, w: &mut Buffer) {
+        let (positions, width, height) = self.layout();
+        write!(
+            w,
+            "<svg class=\"relationship-graph\" xmlns=\"http://www.w3.org/2000/svg\" \
+                  width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\
+             <defs><marker id=\"relationship-arrow\" viewBox=\"0 0 10 10\" refX=\"10\" \
+                   refY=\"5\" markerWidth=\"8\" markerHeight=\"8\" orient=\"auto\">\
+                   <path d=\"M 0 0 L 10 5 L 0 10 z\"/>\
+             </marker></defs>",
+            width, height
+        );
+        for edge in &self.edges {
+            let (from, to) = match (positions[edge.from], positions[edge.to]) {
+                (Some(from), Some(to)) => (from, to),
+                _ => continue,
+            };
+            let (x1, y1) = (from.0 + from.2 / 2, from.1);
+            let (x2, y2) = (to.0 + to.2 / 2, to.1 + NODE_HEIGHT);
+            write!(
+                w,
+                "<line class=\"edge{}\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" \
+                       marker-end=\"url(#relationship-arrow)\"/>",
+                if edge.dashed { " dashed" } else { "" },
+                x1,
+                y1,
+                x2,
+                y2
+            );
+            if let Some(ref label) = edge.label {
+                write!(
+                    w,
+                    "<text class=\"edge-label\" x=\"{}\" y=\"{}\">{}</text>",
+                    (x1 + x2) / 2 + 4,
+                    (y1 + y2) / 2,
+                    Escape(label)
+                );
+            }
+        }
+        for (node, position) in self.nodes.iter().zip(positions) {
+            let (x, y, width) = match position {
+                Some(position) => position,
+                None => continue,
+            };
+            let class = node.kind.class();
+            match node.href {
+                Some(ref href) => {
+                    write!(w, "<a class=\"{}\" href=\"{}\">", class, Escape(href));
+                }
+                None => write!(w, "<g class=\"{}\">", class),
+            }
+            write!(
+                w,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\"/>\
+                 <text x=\"{}\" y=\"{}\" text-anchor=\"middle\" \
+                       dominant-baseline=\"central\">{}</text>",
+                x,
+                y,
+                width,
+                NODE_HEIGHT,
+                x + width / 2,
+                y + NODE_HEIGHT / 2,
+                Escape(&node.label)
+            );
+            w.write_str(if node.href.is_some() { "</a>" } else { "</g>" });
+        }
+        w.write_str("</svg>");
+    }
+
+    /// Adds a node for a type, unless it is a generic parameter, like the `T` of a blanket impl.
+    fn add_type(&mut self, ty: &clean::Type, kind: NodeKind) -> Option<usize> {
+        if let clean::Generic(_) = *ty {
+            return None;
+        }
+        let label = format!("{:#}", ty.print());
+        let (key, href) = match ty.def_id() {
+            Some(def_id) => (NodeKey::Item(def_id), href(def_id).map(|(url, ..)| url)),
+            None => (NodeKey::Label(label.clone()), None),
+        };
+        Some(self.add_node(key, label, href, kind))
+    }
+
+    fn add_bound(&mut self, bound: &clean::GenericBound) -> Option<usize> {
+        match *bound {
+            clean::GenericBound::TraitBound(ref poly, hir::TraitBoundModifier::None) => {
+                self.add_type(&poly.trait_, NodeKind::Trait)
+            }
+            _ => None,
+        }
+    }
+
+    fn add_item(&mut self, item: &clean::Item, kind: NodeKind) -> usize {
+        let href = href(item.def_id).map(|(url, ..)| url);
+        self.add_node(NodeKey::Item(item.def_id), item.name.unwrap().to_string(), href, kind)
+    }
+}
+
+/// Whether the impl should appear in the graphs: impls made up by rustdoc and derived impls are
+/// left out, since they would crowd the graph without saying much about the design of the crate.
+fn is_shown(impl_item: &clean::Item) -> bool {
+    let impl_ = match *impl_item.kind {
+        clean::ImplItem(ref impl_) => impl_,
+        _ => return false,
+    };
+    !impl_.synthetic
+        && impl_.blanket_impl.is_none()
+        && impl_.polarity != Some(clean::ImplPolarity::Negative)
+        && !impl_item.attrs.other_attrs.iter().any(|a| a.has_name(sym::automatically_derived))
+}
+
+/// Builds the graph of the supertraits, associated types and implementors of a trait.
+crate fn trait_graph(it: &clean::Item, t: &clean::Trait, cache: &Cache) -> Graph {
+    let mut graph = Graph::default();
+    let name = it.name.unwrap();
+    let current =
+        graph.add_node(NodeKey::Item(it.def_id), name.to_string(), None, NodeKind::Current);
+
+    for bound in &t.bounds {
+        if let Some(supertrait) = graph.add_bound(bound) {
+            graph.add_edge(current, supertrait, None, false);
+        }
+    }
+
+    for item in &t.items {
+        if let clean::AssocTypeItem(ref bounds, _) = *item.kind {
+            let label = format!("{}::{}", name, item.name.unwrap());
+            let assoc =
+                graph.add_node(NodeKey::Item(item.def_id), label, None, NodeKind::AssocType);
+            graph.add_edge(current, assoc, None, true);
+            for bound in bounds {
+                if let Some(bound) = graph.add_bound(bound) {
+                    graph.add_edge(assoc, bound, None, false);
+                }
+            }
+        }
+    }
+
+    for implementor in cache.implementors.get(&it.def_id).into_iter().flatten() {
+        // Auto trait implementations are all synthetic, so they're the only ones worth showing.
+        if !(t.is_auto && implementor.inner_impl().synthetic) && !is_shown(&implementor.impl_item) {
+            continue;
+        }
+        let impl_ = implementor.inner_impl();
+        let ty = match graph.add_type(&impl_.for_, NodeKind::Type) {
+            Some(ty) => ty,
+            None => continue,
+        };
+        graph.add_edge(ty, current, None, false);
+
+        // Link the implementors to the values of the associated types.
+        for item in &impl_.items {
+            if let clean::TypedefItem(ref typedef, true) = *item.kind {
+                if let Some(value) = graph.add_type(&typedef.type_, NodeKind::Type) {
+                    let label = format!("{} =", item.name.unwrap());
+                    graph.add_edge(ty, value, Some(label), true);
+                }
+            }
+        }
+    }
+
+    graph
+}
+
+/// Builds the graph of the traits and types defined in a module, along with the traits they
+/// implement and their implementors.
+crate fn module_graph(items: &[clean::Item], cache: &Cache) -> Graph {
+    let mut graph = Graph::default();
+    for item in items.iter().filter(|item| !item.is_stripped()) {
+        match *item.kind {
+            clean::TraitItem(ref t) => {
+                let node = graph.add_item(item, NodeKind::Trait);
+                for bound in &t.bounds {
+                    if let Some(supertrait) = graph.add_bound(bound) {
+                        graph.add_edge(node, supertrait, None, false);
+                    }
+                }
+                for implementor in cache.implementors.get(&item.def_id).into_iter().flatten() {
+                    if !is_shown(&implementor.impl_item) {
+                        continue;
+                    }
+                    if let Some(ty) = graph.add_type(&implementor.inner_impl().for_, NodeKind::Type)
+                    {
+                        graph.add_edge(ty, node, None, false);
+                    }
+                }
+            }
+            clean::StructItem(..) | clean::EnumItem(..) | clean::UnionItem(..) => {
+                let node = graph.add_item(item, NodeKind::Type);
+                for i in cache.impls.get(&item.def_id).into_iter().flatten() {
+                    if !is_shown(&i.impl_item) {
+                        continue;
+                    }
+                    if let Some(ref trait_) = i.inner_impl().trait_ {
+                        if let Some(trait_) = graph.add_type(trait_, NodeKind::Trait) {
+                            graph.add_edge(node, trait_, None, false);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    graph
+}
@@ -26,6 +26,7 @@
 //! both occur before the crate is rendered.
 
 crate mod cache;
+mod graph;
 
 #[cfg(test)]
 mod tests;
@@ -161,6 +162,9 @@
     crate edition: Edition,
     crate codes: ErrorCodes,
     playground: Option<markdown::Playground>,
+    /// Whether to render the graphs of the relationships between traits and types on the pages of
+    /// traits and modules.
+    crate generate_graphs: bool,
 }
 
 impl<'tcx> Context<'tcx> {
@@ -374,6 +378,7 @@ fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         "methods",
         "deref-methods",
         "implementations",
+        "relationships",
     ]
     .iter()
     .map(|id| (String::from(*id)))
@@ -404,6 +409,7 @@ fn init(
             resource_suffix,
             static_root_path,
             generate_search_filter,
+            generate_graphs,
             unstable_features,
             ..
         } = options;
@@ -478,6 +484,7 @@ fn init(
             edition,
             codes: ErrorCodes::from(unstable_features.is_nightly_build()),
             playground,
+            generate_graphs,
         };
 
         // Add the default themes to the `Vec` of stylepaths
@@ -498,7 +505,7 @@ fn init(
         krate = sources::render(&dst, &mut scx, krate)?;
 
         // Build our search index
-        let index = build_index(&krate, cache);
+        let (index, json_index) = build_index(&krate, cache, md_opts.search_index_json);
 
         let cache = Arc::new(cache);
         let mut cx = Context {
@@ -515,7 +522,7 @@ fn init(
 
         // Write shared runs within a flock; disable thread dispatching of IO temporarily.
         Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(true);
-        write_shared(&cx, &krate, index, &md_opts, &cache)?;
+        write_shared(&cx, &krate, index, json_index, &md_opts, &cache)?;
         Arc::get_mut(&mut cx.shared).unwrap().fs.set_sync_only(false);
         Ok((cx, krate))
     }
@@ -691,6 +698,7 @@ fn write_shared(
     cx: &Context<'_>,
     krate: &clean::Crate,
     search_index: String,
+    json_search_index: Option<String>,
     options: &RenderOptions,
     cache: &Cache,
 ) -> Result<(), Error> {
@@ -1051,6 +1059,15 @@ fn to_json_string(&self) -> String {
         v.push_str("\\\n}');\naddSearchOptions(searchIndex);initSearch(searchIndex);");
         cx.shared.fs.write(&dst, &v)?;
     }
+
+    // Unlike `search-index.js`, the JSON search index is written separately for each crate, into
+    // the directory of the crate, which is where `rustdoc --search` looks for it.
+    if let Some(json_search_index) = json_search_index {
+        let dir = cx.dst.join(&*krate.name.as_str());
+        cx.shared.ensure_dir(&dir)?;
+        cx.shared.fs.write(&dir.join(crate::search::INDEX_FILE_NAME), &json_search_index)?;
+    }
+
     if options.enable_index_page {
         if let Some(index_page) = options.index_page.clone() {
             let mut md_opts = options.clone();
@@ -1784,7 +1801,7 @@ fn print_item(cx: &Context<'_>, item: &clean::Item, buf: &mut Buffer, cache: &Ca
     write!(buf, "</span></h1>"); // in-band
 
     match *item.kind {
-        clean::ModuleItem(ref m) => item_module(buf, cx, item, &m.items),
+        clean::ModuleItem(ref m) => item_module(buf, cx, item, &m.items, cache),
         clean::FunctionItem(ref f) | clean::ForeignFunctionItem(ref f) => {
             item_function(buf, cx, item, f)
         }
@@ -2035,7 +2052,13 @@ fn take_parts<'a>(s: &mut &'a str) -> (&'a str, &'a str) {
     Ordering::Equal
 }
 
-fn item_module(w: &mut Buffer, cx: &Context<'_>, item: &clean::Item, items: &[clean::Item]) {
+fn item_module(
+    w: &mut Buffer,
+    cx: &Context<'_>,
+    item: &clean::Item,
+    items: &[clean::Item],
+    cache: &Cache,
+) {
     document(w, cx, item, None);
 
     let mut indices = (0..items.len()).filter(|i| !items[*i].is_stripped()).collect::<Vec<usize>>();
@@ -2221,6 +2244,18 @@ fn cmp(
     if curty.is_some() {
         write!(w, "</table>");
     }
+
+    if cx.shared.generate_graphs {
+        let graph = graph::module_graph(items, cache);
+        if !graph.is_empty() {
+            write!(
+                w,
+                "<h2 id=\"relationships\" class=\"section-header\">\
+                       <a href=\"#relationships\">Relationships</a></h2>"
+            );
+            graph.render(w);
+        }
+    }
 }
 
 /// Render the stability, deprecation and portability tags that are displayed in the item's summary
@@ -2842,6 +2877,14 @@ fn trait_item(
         }
     }
 
+    if cx.shared.generate_graphs {
+        let graph = graph::trait_graph(it, t, cache);
+        if !graph.is_empty() {
+            write_small_section_header(w, "relationships", "Relationships", "");
+            graph.render(w);
+        }
+    }
+
     write!(
         w,
         "<script type=\"text/javascript\" \
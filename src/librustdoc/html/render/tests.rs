@@ -38,3 +38,53 @@ fn test_name_sorting() {
     sorted.sort_by(|&l, r| compare_names(l, r));
     assert_eq!(names, sorted);
 }
+
+#[test]
+fn test_graph_layout() {
+    use graph::{Graph, NodeKey, NodeKind};
+
+    let mut graph = Graph::default();
+    let mut add_node = |label: &str, href: Option<&str>, kind| {
+        let key = NodeKey::Label(label.to_string());
+        graph.add_node(key, label.to_string(), href.map(str::to_string), kind)
+    };
+    let current = add_node("Trait", None, NodeKind::Current);
+    let supertrait = add_node("Super", Some("trait.Super.html"), NodeKind::Trait);
+    let ty = add_node("Foo<T>", None, NodeKind::Type);
+    add_node("Unrelated", None, NodeKind::Type);
+    graph.add_edge(current, supertrait, None, false);
+    graph.add_edge(ty, current, None, false);
+
+    let mut buf = Buffer::new();
+    graph.render(&mut buf);
+    let svg = buf.into_inner();
+    // One row per node, with the supertrait at the top and the implementor at the bottom.
+    assert!(svg.contains("width=\"90\" height=\"210\""), "{}", svg);
+    assert!(svg.contains("<a class=\"trait\" href=\"trait.Super.html\"><rect x=\"14\" y=\"10\""));
+    assert!(svg.contains("<g class=\"current\"><rect x=\"14\" y=\"90\""));
+    assert!(svg.contains("<g class=\"type\"><rect x=\"10\" y=\"170\""));
+    assert!(svg.contains(">Foo&lt;T&gt;</text>"));
+    assert!(!svg.contains("Unrelated"));
+
+    // Cycles are broken up instead of sending the layout into an infinite loop.
+    graph.add_edge(supertrait, ty, None, false);
+    let mut buf = Buffer::new();
+    graph.render(&mut buf);
+    assert!(buf.into_inner().ends_with("</svg>"));
+}
+
+#[test]
+fn test_graph_node_keys() {
+    use graph::{Graph, NodeKey, NodeKind};
+    use rustc_hir::def_id::{DefId, DefIndex, LOCAL_CRATE};
+
+    let key = |index| NodeKey::Item(DefId { krate: LOCAL_CRATE, index: DefIndex::from_u32(index) });
+    let mut graph = Graph::default();
+    // Types from different modules can share a name...
+    let a = graph.add_node(key(1), "Same".to_string(), None, NodeKind::Type);
+    let b = graph.add_node(key(2), "Same".to_string(), None, NodeKind::Type);
+    assert_ne!(a, b);
+    // ...and the same type can be printed with or without its generic arguments.
+    let c = graph.add_node(key(1), "Same<T>".to_string(), None, NodeKind::Type);
+    assert_eq!(a, c);
+}
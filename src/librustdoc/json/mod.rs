@@ -1,11 +1,10 @@
 //! Rustdoc's JSON backend
 //!
 //! This module contains the logic for rendering a crate as JSON rather than the normal static HTML
-//! output. See [the RFC](https://github.com/rust-lang/rfcs/pull/2963) and the [`types`] module
+//! output. See [the RFC](https://github.com/rust-lang/rfcs/pull/2963) and the [`types`] crate
 //! docs for usage and details.
 
 mod conversions;
-pub mod types;
 
 use std::cell::RefCell;
 use std::fs::File;
@@ -17,18 +16,22 @@
 use rustc_session::Session;
 use rustc_span::edition::Edition;
 
+use rustdoc_json_types as types;
+
 use crate::clean;
 use crate::config::{RenderInfo, RenderOptions};
 use crate::error::Error;
 use crate::formats::cache::Cache;
 use crate::formats::FormatRenderer;
 use crate::html::render::cache::ExternalLocation;
+use crate::json::conversions::from_def_id;
 
 #[derive(Clone)]
 crate struct JsonRenderer<'tcx> {
     tcx: ty::TyCtxt<'tcx>,
-    /// A mapping of IDs that contains all local items for this crate which gets output as a top
-    /// level field of the JSON blob.
+    /// A mapping of IDs that contains all local items for this crate, and the external items
+    /// inlined with `--json-include-external`, which gets output as a top level field of the JSON
+    /// blob.
     index: Rc<RefCell<FxHashMap<types::Id, types::Item>>>,
     /// The directory where the blob will be written to.
     out_path: PathBuf,
@@ -53,7 +56,7 @@ fn get_trait_implementors(
                     .map(|i| {
                         let item = &i.impl_item;
                         self.item(item.clone(), cache).unwrap();
-                        item.def_id.into()
+                        from_def_id(item.def_id)
                     })
                     .collect()
             })
@@ -69,9 +72,15 @@ fn get_impls(&mut self, id: rustc_span::def_id::DefId, cache: &Cache) -> Vec<typ
                     .iter()
                     .filter_map(|i| {
                         let item = &i.impl_item;
-                        if item.def_id.is_local() {
+                        // External types only get here with `--json-include-external`. Their
+                        // synthesized auto trait and blanket impls are left out since those
+                        // aren't specific to one type.
+                        let inner = i.inner_impl();
+                        if item.def_id.is_local()
+                            || (!id.is_local() && !inner.synthetic && inner.blanket_impl.is_none())
+                        {
                             self.item(item.clone(), cache).unwrap();
-                            Some(item.def_id.into())
+                            Some(from_def_id(item.def_id))
                         } else {
                             None
                         }
@@ -90,9 +99,9 @@ fn get_trait_items(&mut self, cache: &Cache) -> Vec<(types::Id, types::Item)> {
                 if !id.is_local() {
                     trait_item.items.clone().into_iter().for_each(|i| self.item(i, cache).unwrap());
                     Some((
-                        id.into(),
+                        from_def_id(id),
                         types::Item {
-                            id: id.into(),
+                            id: from_def_id(id),
                             crate_id: id.krate.as_u32(),
                             name: cache
                                 .paths
@@ -160,7 +169,7 @@ fn item(&mut self, item: clean::Item, cache: &Cache) -> Result<(), Error> {
             } else if let types::ItemEnum::EnumItem(ref mut e) = new_item.inner {
                 e.impls = self.get_impls(id, cache)
             }
-            let removed = self.index.borrow_mut().insert(id.into(), new_item.clone());
+            let removed = self.index.borrow_mut().insert(from_def_id(id), new_item.clone());
             // FIXME(adotinthevoid): Currently, the index is duplicated. This is a sanity check
             // to make sure the items are unique.
             if let Some(old_item) = removed {
@@ -201,13 +210,16 @@ fn mod_item_out(&mut self, _item_name: &str) -> Result<(), Error> {
 
     fn after_krate(&mut self, krate: &clean::Crate, cache: &Cache) -> Result<(), Error> {
         debug!("Done with crate");
+        for item in &krate.external_items {
+            self.item(item.clone(), cache).unwrap();
+        }
         let mut index = (*self.index).clone().into_inner();
         index.extend(self.get_trait_items(cache));
         let output = types::Crate {
             root: types::Id(String::from("0:0")),
             crate_version: krate.version.clone(),
             includes_private: cache.document_private,
-            index,
+            index: index.into_iter().collect(),
             paths: cache
                 .paths
                 .clone()
@@ -215,7 +227,7 @@ fn after_krate(&mut self, krate: &clean::Crate, cache: &Cache) -> Result<(), Err
                 .chain(cache.external_paths.clone().into_iter())
                 .map(|(k, (path, kind))| {
                     (
-                        k.into(),
+                        from_def_id(k),
                         types::ItemSummary { crate_id: k.krate.as_u32(), path, kind: kind.into() },
                     )
                 })
@@ -236,7 +248,7 @@ fn after_krate(&mut self, krate: &clean::Crate, cache: &Cache) -> Result<(), Err
                     )
                 })
                 .collect(),
-            format_version: 1,
+            format_version: types::FORMAT_VERSION,
         };
         let mut p = self.out_path.clone();
         p.push(output.index.get(&output.root).unwrap().name.clone().unwrap());
@@ -88,6 +88,7 @@
 mod json;
 mod markdown;
 mod passes;
+mod search;
 mod theme;
 mod visit_ast;
 mod visit_lib;
@@ -328,7 +329,7 @@ fn opts() -> Vec<RustcOptGroup> {
                 "",
                 "error-format",
                 "How errors and other messages are produced",
-                "human|json|short",
+                "human|json|short|sarif",
             )
         }),
         stable("json", |o| {
@@ -419,6 +420,72 @@ fn opts() -> Vec<RustcOptGroup> {
             )
         }),
         unstable("check", |o| o.optflag("", "check", "Run rustdoc checks")),
+        unstable("search-index-json", |o| {
+            o.optflag(
+                "",
+                "search-index-json",
+                "also write the search index of the crate as JSON, for use with `--search`",
+            )
+        }),
+        unstable("search", |o| {
+            o.optopt(
+                "",
+                "search",
+                "search the documentation generated with `--search-index-json` in the output \
+                 directory (ignoring all other arguments)",
+                "QUERY",
+            )
+        }),
+        unstable("json-include-external", |o| {
+            o.optflag(
+                "",
+                "json-include-external",
+                "also output the items from other crates that are referenced by the crate, with \
+                 `--output-format json`",
+            )
+        }),
+        unstable("generate-graphs", |o| {
+            o.optflag(
+                "",
+                "generate-graphs",
+                "render graphs of the supertraits, implementors and associated types of traits on \
+                 their pages, and of the traits and types of modules",
+            )
+        }),
+        unstable("coverage-details", |o| {
+            o.optflag(
+                "",
+                "coverage-details",
+                "with --show-coverage, also list the items missing documentation or examples and \
+                 the coverage per kind of item",
+            )
+        }),
+        unstable("coverage-threshold", |o| {
+            o.optopt(
+                "",
+                "coverage-threshold",
+                "with --show-coverage, exit with an error if less than the given percentage of \
+                 items is documented",
+                "PERCENT",
+            )
+        }),
+        unstable("merge-doctests", |o| {
+            o.optflag(
+                "",
+                "merge-doctests",
+                "compile the doctests which allow it into a single binary per edition, instead of \
+                 one binary per doctest",
+            )
+        }),
+        unstable("doctest-cache", |o| {
+            o.optopt(
+                "",
+                "doctest-cache",
+                "directory in which to remember the doctests which passed, so that they are \
+                 skipped as long as neither they nor the crate change",
+                "PATH",
+            )
+        }),
     ]
 }
 
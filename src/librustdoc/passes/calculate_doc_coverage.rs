@@ -1,9 +1,11 @@
 use crate::clean;
 use crate::core::DocContext;
 use crate::fold::{self, DocFolder};
+use crate::formats::item_type::ItemType;
 use crate::html::markdown::{find_testable_code, ErrorCodes};
 use crate::passes::doc_test_lints::{should_have_doc_example, Tests};
 use crate::passes::Pass;
+use rustc_hir::def_id::LOCAL_CRATE;
 use rustc_lint::builtin::MISSING_DOCS;
 use rustc_middle::lint::LintLevelSource;
 use rustc_session::lint;
@@ -26,6 +28,17 @@ fn calculate_doc_coverage(krate: clean::Crate, ctx: &DocContext<'_>) -> clean::C
 
     calc.print_results();
 
+    if let Some(threshold) = ctx.render_options.coverage_threshold {
+        if let Some(percentage) = calc.total().percentage() {
+            if percentage < threshold {
+                ctx.sess().err(&format!(
+                    "documentation coverage of {:.1}% is below the threshold of {}%",
+                    percentage, threshold
+                ));
+            }
+        }
+    }
+
     krate
 }
 
@@ -99,8 +112,33 @@ fn add_assign(&mut self, rhs: Self) {
     }
 }
 
+/// An item missing documentation or a code example, listed with `--coverage-details`.
+#[derive(Serialize, Debug)]
+struct MissingItem {
+    path: String,
+    kind: &'static str,
+    file: String,
+    line: usize,
+    column: usize,
+}
+
+/// The JSON output with `--coverage-details`.
+#[derive(Serialize)]
+struct CoverageDetails<'a> {
+    files: BTreeMap<String, &'a ItemCount>,
+    kinds: BTreeMap<&'static str, &'a ItemCount>,
+    undocumented: &'a [MissingItem],
+    missing_examples: &'a [MissingItem],
+}
+
 struct CoverageCalculator<'a, 'b> {
     items: BTreeMap<FileName, ItemCount>,
+    /// The counts per kind of item, only printed with `--coverage-details`.
+    kinds: BTreeMap<ItemType, ItemCount>,
+    undocumented: Vec<MissingItem>,
+    missing_examples: Vec<MissingItem>,
+    /// The path of the item currently being visited, starting with the crate name.
+    path: Vec<String>,
     ctx: &'a DocContext<'b>,
 }
 
@@ -116,17 +154,60 @@ fn limit_filename_len(filename: String) -> String {
 
 impl<'a, 'b> CoverageCalculator<'a, 'b> {
     fn new(ctx: &'a DocContext<'b>) -> CoverageCalculator<'a, 'b> {
-        CoverageCalculator { items: Default::default(), ctx }
+        CoverageCalculator {
+            items: Default::default(),
+            kinds: Default::default(),
+            undocumented: Vec::new(),
+            missing_examples: Vec::new(),
+            path: vec![ctx.tcx.crate_name(LOCAL_CRATE).to_string()],
+            ctx,
+        }
+    }
+
+    /// The sum of the counts of the files with at least one item to document.
+    fn total(&self) -> ItemCount {
+        let mut total = ItemCount::default();
+        for count in self.items.values() {
+            if count.percentage().is_some() {
+                total += *count;
+            }
+        }
+        total
+    }
+
+    fn missing_item(&self, i: &clean::Item) -> MissingItem {
+        let sess = self.ctx.sess();
+        let lo = i.source.lo(sess);
+        let mut path = self.path.join("::");
+        if let Some(name) = i.name {
+            path.push_str("::");
+            path.push_str(&name.as_str());
+        }
+        MissingItem {
+            path,
+            kind: i.type_().as_str(),
+            file: i.source.filename(sess).to_string(),
+            line: lo.line,
+            column: lo.col.0 + 1,
+        }
     }
 
     fn to_json(&self) -> String {
-        serde_json::to_string(
-            &self
-                .items
-                .iter()
-                .map(|(k, v)| (k.to_string(), v))
-                .collect::<BTreeMap<String, &ItemCount>>(),
-        )
+        let files = self
+            .items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect::<BTreeMap<String, &ItemCount>>();
+        if self.ctx.render_options.coverage_details {
+            serde_json::to_string(&CoverageDetails {
+                files,
+                kinds: self.kinds.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+                undocumented: &self.undocumented,
+                missing_examples: &self.missing_examples,
+            })
+        } else {
+            serde_json::to_string(&files)
+        }
         .expect("failed to convert JSON data to string")
     }
 
@@ -136,8 +217,6 @@ fn print_results(&self) {
             println!("{}", self.to_json());
             return;
         }
-        let mut total = ItemCount::default();
-
         fn print_table_line() {
             println!("+-{0:->35}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+-{0:->10}-+", "");
         }
@@ -169,11 +248,10 @@ fn print_table_record(
                     percentage,
                     count.examples_percentage().unwrap_or(0.),
                 );
-
-                total += count;
             }
         }
 
+        let total = self.total();
         print_table_line();
         print_table_record(
             "Total",
@@ -182,6 +260,46 @@ fn print_table_record(
             total.examples_percentage().unwrap_or(0.0),
         );
         print_table_line();
+
+        if !self.ctx.render_options.coverage_details {
+            return;
+        }
+
+        println!();
+        print_table_line();
+        println!(
+            "| {:<35} | {:>10} | {:>10} | {:>10} | {:>10} |",
+            "Item kind", "Documented", "Percentage", "Examples", "Percentage",
+        );
+        print_table_line();
+        for (kind, &count) in &self.kinds {
+            if let Some(percentage) = count.percentage() {
+                print_table_record(
+                    kind.as_str(),
+                    count,
+                    percentage,
+                    count.examples_percentage().unwrap_or(0.),
+                );
+            }
+        }
+        print_table_line();
+
+        fn print_missing_items(title: &str, items: &[MissingItem]) {
+            if items.is_empty() {
+                return;
+            }
+            println!();
+            println!("{}:", title);
+            for item in items {
+                println!(
+                    "    {}:{}:{}: {} `{}`",
+                    item.file, item.line, item.column, item.kind, item.path
+                );
+            }
+        }
+
+        print_missing_items("Items without documentation", &self.undocumented);
+        print_missing_items("Items without examples", &self.missing_examples);
     }
 }
 
@@ -244,6 +362,7 @@ fn fold_item(&mut self, i: clean::Item) -> Option<clean::Item> {
 
                 let filename = i.source.filename(self.ctx.sess());
                 let has_doc_example = tests.found_tests != 0;
+                let should_have_doc_example = should_have_doc_example(self.ctx, &i);
                 let hir_id = self.ctx.tcx.hir().local_def_id_to_hir_id(i.def_id.expect_local());
                 let (level, source) = self.ctx.tcx.lint_level_at_node(MISSING_DOCS, hir_id);
                 // `missing_docs` is allow-by-default, so don't treat this as ignoring the item
@@ -254,12 +373,43 @@ fn fold_item(&mut self, i: clean::Item) -> Option<clean::Item> {
                 self.items.entry(filename).or_default().count_item(
                     has_docs,
                     has_doc_example,
-                    should_have_doc_example(self.ctx, &i),
+                    should_have_doc_example,
                     should_have_docs,
                 );
+
+                if self.ctx.render_options.coverage_details {
+                    self.kinds.entry(i.type_()).or_default().count_item(
+                        has_docs,
+                        has_doc_example,
+                        should_have_doc_example,
+                        should_have_docs,
+                    );
+                    if should_have_docs && !has_docs {
+                        self.undocumented.push(self.missing_item(&i));
+                    }
+                    if should_have_doc_example && !has_doc_example {
+                        self.missing_examples.push(self.missing_item(&i));
+                    }
+                }
             }
         }
 
-        Some(self.fold_item_recur(i))
+        // Items inside impl blocks are listed under the name of the type.
+        let name = match *i.kind {
+            clean::ImplItem(ref impl_) => match impl_.for_ {
+                clean::ResolvedPath { ref path, .. } => Some(path.last_name().to_string()),
+                clean::Primitive(prim) => Some(prim.as_str().to_string()),
+                _ => None,
+            },
+            _ => i.name.map(|name| name.to_string()),
+        };
+        if let Some(name) = name {
+            self.path.push(name);
+            let i = self.fold_item_recur(i);
+            self.path.pop();
+            Some(i)
+        } else {
+            Some(self.fold_item_recur(i))
+        }
     }
 }
@@ -0,0 +1,58 @@
+use super::Pass;
+use crate::clean::*;
+use crate::core::DocContext;
+
+use rustc_data_structures::fx::FxHashSet;
+use rustc_hir::def::Res;
+use rustc_middle::ty::DefIdTree;
+
+crate const INLINE_EXTERNAL_ITEMS: Pass = Pass {
+    name: "inline-external-items",
+    run: inline_external_items,
+    description: "inlines the items from other crates that are referenced by the crate",
+};
+
+/// Inlines the external items whose paths were recorded while cleaning the crate, along with
+/// their inherent impls, into `Crate::external_items`.
+///
+/// This must run before `collect-trait-impls`, which then keeps the trait impls of these items
+/// as it does for local ones. Only the items referenced by the crate itself are inlined: the
+/// items they refer to in turn are still only known by their paths.
+crate fn inline_external_items(mut krate: Crate, cx: &DocContext<'_>) -> Crate {
+    let mut dids: Vec<_> = cx
+        .renderinfo
+        .borrow()
+        .external_paths
+        .iter()
+        .filter(|(_, (_, kind))| match kind {
+            TypeKind::Struct
+            | TypeKind::Enum
+            | TypeKind::Union
+            | TypeKind::Typedef
+            | TypeKind::Foreign
+            | TypeKind::Function
+            | TypeKind::Const
+            | TypeKind::Static => true,
+            // External traits are always recorded in `Crate::external_traits`.
+            _ => false,
+        })
+        .map(|(&did, _)| did)
+        .collect();
+    dids.sort();
+
+    let mut visited = FxHashSet::default();
+    for did in dids {
+        let parent = match cx.tcx.parent(did) {
+            Some(parent) => parent,
+            None => continue,
+        };
+        let res = Res::Def(cx.tcx.def_kind(did), did);
+        let name = cx.tcx.item_name(did);
+        let items = cx.sess().time("build_external_item", || {
+            inline::try_inline(cx, parent, res, name, None, &mut visited)
+        });
+        krate.external_items.extend(items.into_iter().flatten());
+    }
+
+    krate
+}
@@ -38,6 +38,9 @@
 mod collect_trait_impls;
 crate use self::collect_trait_impls::COLLECT_TRAIT_IMPLS;
 
+mod inline_external_items;
+crate use self::inline_external_items::INLINE_EXTERNAL_ITEMS;
+
 mod check_code_block_syntax;
 crate use self::check_code_block_syntax::CHECK_CODE_BLOCK_SYNTAX;
 
@@ -74,6 +77,8 @@
     WhenNotDocumentPrivate,
     /// When `--document-hidden-items` is not passed.
     WhenNotDocumentHidden,
+    /// When `--json-include-external` is passed.
+    WhenJsonIncludeExternal,
 }
 
 /// The full list of passes.
@@ -86,6 +91,7 @@
     PROPAGATE_DOC_CFG,
     COLLECT_INTRA_DOC_LINKS,
     CHECK_CODE_BLOCK_SYNTAX,
+    INLINE_EXTERNAL_ITEMS,
     COLLECT_TRAIT_IMPLS,
     CALCULATE_DOC_COVERAGE,
     CHECK_INVALID_HTML_TAGS,
@@ -94,6 +100,7 @@
 
 /// The list of passes run by default.
 crate const DEFAULT_PASSES: &[ConditionalPass] = &[
+    ConditionalPass::new(INLINE_EXTERNAL_ITEMS, WhenJsonIncludeExternal),
     ConditionalPass::always(COLLECT_TRAIT_IMPLS),
     ConditionalPass::always(UNINDENT_COMMENTS),
     ConditionalPass::always(CHECK_PRIVATE_ITEMS_DOC_TESTS),
@@ -0,0 +1,811 @@
+//! Searching the generated documentation from the command line.
+//!
+//! With `--search-index-json`, the HTML renderer also writes the search index of the crate as
+//! JSON, to `<crate>/search-index.json` in the output directory. `rustdoc --search <query>` reads
+//! the indexes of all of the crates in the output directory and runs the query against them the
+//! same way as the search of the HTML pages in `static/main.js` does:
+//!
+//! * `name` and `path::to::name` match item names and paths, allowing for typos,
+//! * `fn:name` (or `mod:`, `struct:`, `enum:`, `trait:`, `type:`, `const:`, `macro:`) only
+//!   matches items of that kind,
+//! * `"name"` only matches the exact name, and types in signatures with exactly that name,
+//! * `input -> output` matches functions by their signature, with `*` matching any type.
+//!
+//! Several queries can be separated by commas, except for signature queries, whose inputs are
+//! separated by commas.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_span::lev_distance::lev_distance;
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod tests;
+
+/// The version of the JSON format of the search index, bumped on incompatible changes.
+crate const FORMAT_VERSION: u32 = 1;
+
+/// The name of the JSON search index of a crate, in the directory of the crate.
+crate const INDEX_FILE_NAME: &str = "search-index.json";
+
+const MAX_LEV_DISTANCE: usize = 3;
+const MAX_RESULTS: usize = 200;
+
+/// The item kinds in the order of the discriminants of `ItemType`, which is the order in which
+/// results that are otherwise equally relevant are listed.
+const ITEM_KINDS: &[&str] = &[
+    "mod",
+    "externcrate",
+    "import",
+    "struct",
+    "enum",
+    "fn",
+    "type",
+    "static",
+    "trait",
+    "impl",
+    "tymethod",
+    "method",
+    "structfield",
+    "variant",
+    "macro",
+    "primitive",
+    "associatedtype",
+    "constant",
+    "associatedconstant",
+    "union",
+    "foreigntype",
+    "keyword",
+    "opaque",
+    "attr",
+    "derive",
+    "traitalias",
+];
+
+/// The search index of a crate, as written by `--search-index-json`.
+#[derive(Serialize, Deserialize)]
+crate struct CrateIndex {
+    crate format_version: u32,
+    #[serde(rename = "crate")]
+    crate name: String,
+    /// The summary of the crate documentation.
+    crate doc: String,
+    crate items: Vec<IndexEntry>,
+    /// The indices in `items` of the items with each `#[doc(alias)]`, in lowercase.
+    crate aliases: BTreeMap<String, Vec<usize>>,
+}
+
+/// An item in the search index.
+#[derive(Serialize, Deserialize)]
+crate struct IndexEntry {
+    /// The kind of the item, as used in the file names of the HTML pages (`struct`, `fn`, ...).
+    crate kind: String,
+    crate name: String,
+    /// The path of the module the item, or the parent of the item, is in.
+    crate path: String,
+    /// The summary of the item documentation, rendered as HTML.
+    crate desc: String,
+    /// The type or trait the item belongs to, for methods, fields, variants and the like.
+    crate parent: Option<Parent>,
+    /// The types of the parameters and of the return value of functions and methods.
+    crate signature: Option<Signature>,
+}
+
+#[derive(Serialize, Deserialize)]
+crate struct Parent {
+    crate kind: String,
+    crate name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+crate struct Signature {
+    crate inputs: Vec<SignatureType>,
+    crate output: Vec<SignatureType>,
+}
+
+#[derive(Serialize, Deserialize)]
+crate struct SignatureType {
+    /// The name of the type, or of the generic parameter, in lowercase.
+    crate name: String,
+    /// The kind of the type, as for `IndexEntry::kind`.
+    crate kind: String,
+    /// The names of the generic arguments of the type, in lowercase.
+    crate generics: Vec<String>,
+}
+
+/// Runs `query` against the search indexes in `doc_dir` and prints the results.
+crate fn run(query: &str, doc_dir: &Path) -> Result<(), String> {
+    let index = SearchIndex::load(doc_dir)?;
+    let results = index.search(query);
+
+    let tabs = [
+        ("In Names", &results.others),
+        ("In Parameters", &results.in_args),
+        ("In Return Types", &results.returned),
+    ];
+    if tabs.iter().all(|(_, matches)| matches.is_empty()) {
+        println!("No results for `{}`", query);
+        return Ok(());
+    }
+    for (title, matches) in tabs.iter() {
+        if matches.is_empty() {
+            continue;
+        }
+        println!("{} ({}):", title, matches.len());
+        for m in matches.iter() {
+            match m.alias {
+                Some(ref alias) => println!("    {} - see {} ({})", alias, m.path, m.kind),
+                None => println!("    {} ({})", m.path, m.kind),
+            }
+            let desc = plain_text(&m.desc);
+            if !desc.is_empty() {
+                println!("        {}", desc);
+            }
+            println!("        {}", doc_dir.join(&m.href).display());
+        }
+        println!();
+    }
+    Ok(())
+}
+
+/// An item found by a search.
+#[derive(Debug)]
+crate struct Match {
+    crate kind: String,
+    /// The full path of the item, as displayed by the HTML search.
+    crate path: String,
+    /// The page of the item, relative to the output directory.
+    crate href: String,
+    crate desc: String,
+    /// The `#[doc(alias)]` the item was found by.
+    crate alias: Option<String>,
+    lev: f64,
+}
+
+/// The results of a search, split the same way as the tabs of the HTML search.
+#[derive(Debug, Default)]
+crate struct Results {
+    /// Items whose name or path match the query.
+    crate others: Vec<Match>,
+    /// Functions taking the type in the query as a parameter.
+    crate in_args: Vec<Match>,
+    /// Functions returning the type in the query.
+    crate returned: Vec<Match>,
+}
+
+/// An item of the loaded search indexes.
+struct Row {
+    krate: String,
+    entry: IndexEntry,
+    /// The name of the item in lowercase, which queries are matched against.
+    word: String,
+}
+
+impl Row {
+    /// Identifies the item for deduplicating results.
+    fn full_id(&self) -> String {
+        let parent = self.entry.parent.as_ref().map_or("", |p| &p.name);
+        format!("{}{}{}{}", self.entry.kind, self.entry.path, parent, self.entry.name)
+    }
+}
+
+/// The search indexes of all of the crates in an output directory.
+crate struct SearchIndex {
+    rows: Vec<Row>,
+    /// The rows with each `#[doc(alias)]`.
+    aliases: BTreeMap<String, Vec<usize>>,
+}
+
+/// A query, without the commas separating it from other queries.
+struct Query {
+    /// The query in lowercase, without the kind filter.
+    val: String,
+    /// The kind of the items the query is restricted to.
+    type_filter: Option<&'static str>,
+}
+
+/// A type in a query, like `vec<u8>`.
+struct TypeQuery {
+    name: String,
+    generics: Vec<String>,
+}
+
+/// A result of a query before sorting.
+struct Candidate {
+    id: usize,
+    /// Where the query occurs in the name of the item, if it does.
+    index: Option<usize>,
+    lev: f64,
+    /// Whether the result is exact, and does not have to be checked against the query again.
+    dont_validate: bool,
+}
+
+impl Candidate {
+    fn exact(id: usize) -> Candidate {
+        Candidate { id, index: None, lev: 0.0, dont_validate: true }
+    }
+}
+
+type Candidates = FxIndexMap<String, Candidate>;
+
+impl SearchIndex {
+    /// Loads the search indexes of all of the crates documented in `doc_dir`.
+    crate fn load(doc_dir: &Path) -> Result<SearchIndex, String> {
+        let dir = fs::read_dir(doc_dir)
+            .map_err(|e| format!("failed to read `{}`: {}", doc_dir.display(), e))?;
+        let mut crates = Vec::new();
+        for entry in dir {
+            let entry =
+                entry.map_err(|e| format!("failed to read `{}`: {}", doc_dir.display(), e))?;
+            let path = entry.path().join(INDEX_FILE_NAME);
+            if !path.is_file() {
+                continue;
+            }
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read `{}`: {}", path.display(), e))?;
+            let index: CrateIndex = serde_json::from_str(&contents)
+                .map_err(|e| format!("failed to parse `{}`: {}", path.display(), e))?;
+            if index.format_version != FORMAT_VERSION {
+                return Err(format!(
+                    "`{}` has format version {}, but this rustdoc reads version {}",
+                    path.display(),
+                    index.format_version,
+                    FORMAT_VERSION
+                ));
+            }
+            crates.push(index);
+        }
+        if crates.is_empty() {
+            return Err(format!(
+                "no search index found in `{}`, document the crates with `--search-index-json`",
+                doc_dir.display()
+            ));
+        }
+        crates.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(SearchIndex::new(crates))
+    }
+
+    crate fn new(crates: Vec<CrateIndex>) -> SearchIndex {
+        let mut rows = Vec::new();
+        let mut aliases = BTreeMap::<_, Vec<_>>::new();
+        for krate in crates {
+            // The crate itself can be searched for as well.
+            rows.push(Row {
+                krate: krate.name.clone(),
+                word: krate.name.to_lowercase(),
+                entry: IndexEntry {
+                    kind: "externcrate".to_string(),
+                    name: krate.name.clone(),
+                    path: String::new(),
+                    desc: krate.doc,
+                    parent: None,
+                    signature: None,
+                },
+            });
+            let start = rows.len();
+            for (alias, ids) in krate.aliases {
+                aliases.entry(alias).or_default().extend(ids.into_iter().map(|id| start + id));
+            }
+            let name = krate.name;
+            rows.extend(krate.items.into_iter().map(|entry| Row {
+                krate: name.clone(),
+                word: entry.name.to_lowercase(),
+                entry,
+            }));
+        }
+        SearchIndex { rows, aliases }
+    }
+
+    /// Runs a (possibly comma-separated) query.
+    crate fn search(&self, raw: &str) -> Results {
+        let raw = raw.trim();
+        let queries: Vec<_> = if raw.contains("->") {
+            vec![raw]
+        } else {
+            raw.split(',').map(str::trim).filter(|q| !q.is_empty()).collect()
+        };
+        let mut results: Vec<_> = queries.into_iter().map(|q| self.exec_query(q)).collect();
+        if results.len() <= 1 {
+            return results.pop().unwrap_or_default();
+        }
+
+        let (mut others, mut in_args, mut returned) = (vec![], vec![], vec![]);
+        for r in results {
+            others.push(r.others);
+            in_args.push(r.in_args);
+            returned.push(r.returned);
+        }
+        Results {
+            others: merge_results(others),
+            in_args: merge_results(in_args),
+            returned: merge_results(returned),
+        }
+    }
+
+    fn exec_query(&self, raw: &str) -> Results {
+        let query = parse_query(raw);
+        let type_filter = query.type_filter;
+        let val = query.val.as_str();
+        let split: Vec<&str> = val.split("::").filter(|s| !s.is_empty()).collect();
+
+        let mut results = Candidates::default();
+        let mut results_in_args = Candidates::default();
+        let mut results_returned = Candidates::default();
+        // The name which results exactly matching are listed first for.
+        let mut exact_name = None;
+        let mut alias_results = Vec::new();
+
+        // Quoted values mean literal search.
+        if let Some(literal) = strip_quotes(val) {
+            let val = extract_generics(literal);
+            for (id, row) in self.rows.iter().enumerate() {
+                let full_id = row.full_id();
+                if row.word == val.name
+                    && type_passes_filter(type_filter, &row.entry.kind)
+                    && !results.contains_key(&full_id)
+                {
+                    results.insert(full_id.clone(), Candidate::exact(id));
+                }
+                if find_arg_literal(row, &val, type_filter) {
+                    results_in_args.entry(full_id.clone()).or_insert_with(|| Candidate::exact(id));
+                }
+                if check_returned_literal(row, &val, type_filter) {
+                    results_returned.entry(full_id).or_insert_with(|| Candidate::exact(id));
+                }
+            }
+        // Searching by type.
+        } else if val.contains("->") {
+            let mut parts = val.split("->").map(str::trim);
+            let input = parts.next().unwrap_or_default();
+            let output = extract_generics(parts.next().unwrap_or_default());
+            let mut inputs: Vec<_> = input.split(',').map(str::trim).collect();
+            // Sort the inputs so that their order does not matter.
+            inputs.sort_unstable();
+            let inputs: Vec<_> = inputs.into_iter().map(extract_generics).collect();
+
+            for (id, row) in self.rows.iter().enumerate() {
+                if row.entry.signature.is_none() {
+                    continue;
+                }
+                let returned = check_returned_literal(row, &output, None);
+                if output.name != "*" && !returned {
+                    continue;
+                }
+                let full_id = row.full_id();
+                if input == "*" {
+                    results.insert(full_id.clone(), Candidate::exact(id));
+                } else if inputs.iter().all(|input| find_arg_literal(row, input, None)) {
+                    results_in_args.insert(full_id.clone(), Candidate::exact(id));
+                }
+                if returned {
+                    results_returned.insert(full_id, Candidate::exact(id));
+                }
+            }
+        } else {
+            let val_generics = extract_generics(&val.replace('_', ""));
+            let name = match split.last() {
+                Some(name) => *name,
+                None => return Results::default(),
+            };
+            let contains = &split[..split.len() - 1];
+            exact_name = Some(name);
+
+            for (id, row) in self.rows.iter().enumerate() {
+                let mut lev_add = 0.0;
+                if !contains.is_empty() {
+                    let lev = check_path(contains, row);
+                    if lev > MAX_LEV_DISTANCE {
+                        continue;
+                    } else if lev > 0 {
+                        lev_add = lev as f64 / 10.0;
+                    }
+                }
+
+                let full_id = row.full_id();
+                let word = row.word.as_str();
+                let passes_filter = type_passes_filter(type_filter, &row.entry.kind);
+                let mut index = None;
+                let word_without_underscores = word.replace('_', "");
+                if (word.contains(name) || word_without_underscores.contains(name))
+                    && passes_filter
+                    && !results.contains_key(&full_id)
+                {
+                    index = word_without_underscores.find(name);
+                }
+                // We want levenshtein results to go lower than others.
+                let mut lev = lev_distance(word, name);
+                if lev <= MAX_LEV_DISTANCE {
+                    lev += 1;
+                }
+                let in_args = find_arg(row, &val_generics, type_filter);
+                let returned = check_returned(row, &val_generics, type_filter);
+
+                let mut lev = lev as f64 + lev_add;
+                if lev > 0.0 && name.len() > 3 && word.contains(name) {
+                    if name.len() < 6 {
+                        lev -= 1.0;
+                    } else {
+                        lev = 0.0;
+                    }
+                }
+                if in_args <= MAX_LEV_DISTANCE {
+                    add_candidate(&mut results_in_args, full_id.clone(), id, index, in_args as f64);
+                }
+                if returned <= MAX_LEV_DISTANCE {
+                    add_candidate(
+                        &mut results_returned,
+                        full_id.clone(),
+                        id,
+                        index,
+                        returned as f64,
+                    );
+                }
+                if passes_filter && (index.is_some() || lev <= MAX_LEV_DISTANCE as f64) {
+                    if index.is_some() && contains.is_empty() {
+                        lev = 0.0;
+                    }
+                    add_candidate(&mut results, full_id, id, index, lev);
+                }
+            }
+
+            if let Some(ids) = self.aliases.get(val) {
+                alias_results = ids.iter().map(|&id| self.to_match(id, 0.0, Some(raw))).collect();
+                alias_results.sort_by(|a, b| a.path.cmp(&b.path));
+            }
+        }
+
+        let mut others = self.sort_results(results, false, exact_name, &split);
+        if !alias_results.is_empty() {
+            alias_results.append(&mut others);
+            others = alias_results;
+            others.truncate(MAX_RESULTS);
+        }
+        Results {
+            others,
+            in_args: self.sort_results(results_in_args, true, exact_name, &split),
+            returned: self.sort_results(results_returned, true, exact_name, &split),
+        }
+    }
+
+    fn sort_results(
+        &self,
+        results: Candidates,
+        is_type: bool,
+        exact_name: Option<&str>,
+        split: &[&str],
+    ) -> Vec<Match> {
+        let mut results: Vec<_> = results.into_iter().map(|(_, c)| c).collect();
+        results.sort_by(|a, b| self.compare(a, b, exact_name));
+        results
+            .into_iter()
+            // This validation does not make sense when searching by types.
+            .filter(|c| c.dont_validate || is_type || validate_result(&self.rows[c.id], split))
+            .take(MAX_RESULTS)
+            .map(|c| self.to_match(c.id, c.lev, None))
+            .collect()
+    }
+
+    fn compare(&self, a: &Candidate, b: &Candidate, exact_name: Option<&str>) -> Ordering {
+        let (ra, rb) = (&self.rows[a.id], &self.rows[b.id]);
+        let mismatch = |row: &Row| exact_name.map_or(true, |name| row.word != name);
+        let special = |row: &Row| matches!(&*row.entry.kind, "primitive" | "keyword");
+        let kind = |row: &Row| ITEM_KINDS.iter().position(|&k| k == row.entry.kind);
+
+        // Exact matches first, then by levenshtein distance.
+        mismatch(ra)
+            .cmp(&mismatch(rb))
+            .then_with(|| a.lev.partial_cmp(&b.lev).unwrap_or(Ordering::Equal))
+            // Shorter names first, then alphabetically.
+            .then_with(|| ra.word.len().cmp(&rb.word.len()))
+            .then_with(|| ra.word.cmp(&rb.word))
+            // Literal occurrences of the query first, the earlier the better.
+            .then_with(|| a.index.is_none().cmp(&b.index.is_none()))
+            .then_with(|| a.index.cmp(&b.index))
+            // Primitive and keyword pages take precedence.
+            .then_with(|| special(rb).cmp(&special(ra)))
+            // Items with a description first.
+            .then_with(|| ra.entry.desc.is_empty().cmp(&rb.entry.desc.is_empty()))
+            .then_with(|| kind(ra).cmp(&kind(rb)))
+            .then_with(|| ra.entry.path.cmp(&rb.entry.path))
+    }
+
+    fn to_match(&self, id: usize, lev: f64, alias: Option<&str>) -> Match {
+        let row = &self.rows[id];
+        let (display_path, href) = build_href_and_path(row);
+        Match {
+            kind: row.entry.kind.clone(),
+            path: display_path + &row.entry.name,
+            href,
+            desc: row.entry.desc.clone(),
+            alias: alias.map(str::to_string),
+            lev,
+        }
+    }
+}
+
+fn add_candidate(
+    results: &mut Candidates,
+    full_id: String,
+    id: usize,
+    index: Option<usize>,
+    lev: f64,
+) {
+    let candidate =
+        results.entry(full_id).or_insert(Candidate { id, index, lev, dont_validate: false });
+    candidate.lev = candidate.lev.min(lev);
+}
+
+/// Merges the results of several queries, by increasing distance.
+fn merge_results(lists: Vec<Vec<Match>>) -> Vec<Match> {
+    let mut lists: Vec<_> = lists.into_iter().map(|l| l.into_iter().peekable()).collect();
+    let mut seen = FxHashSet::default();
+    let mut merged = Vec::new();
+    while merged.len() < MAX_RESULTS {
+        // Skip the items already found by another query.
+        for list in &mut lists {
+            while list.next_if(|m| seen.contains(&(m.path.clone(), m.kind.clone()))).is_some() {}
+        }
+        let smallest = lists
+            .iter_mut()
+            .filter_map(|l| l.peek().map(|m| m.lev))
+            .fold(None, |min: Option<f64>, lev| Some(min.map_or(lev, |min| min.min(lev))));
+        let smallest = match smallest {
+            Some(smallest) => smallest,
+            None => break,
+        };
+        for list in &mut lists {
+            if merged.len() >= MAX_RESULTS {
+                break;
+            }
+            if let Some(m) = list.next_if(|m| m.lev == smallest) {
+                if seen.insert((m.path.clone(), m.kind.clone())) {
+                    merged.push(m);
+                }
+            }
+        }
+    }
+    merged
+}
+
+fn parse_query(raw: &str) -> Query {
+    const TYPE_FILTERS: &[&str] =
+        &["fn", "mod", "struct", "enum", "trait", "type", "const", "macro"];
+
+    let mut query = raw;
+    let mut type_filter = None;
+    if let Some((prefix, rest)) = raw.split_once(':') {
+        let prefix = prefix.trim_end().to_lowercase();
+        if let Some(&filter) = TYPE_FILTERS.iter().find(|&&f| f == prefix) {
+            type_filter = Some(if filter == "const" { "constant" } else { filter });
+            query = rest.trim_start();
+        }
+    }
+    Query { val: query.to_lowercase(), type_filter }
+}
+
+fn strip_quotes(val: &str) -> Option<&str> {
+    let quote = val.chars().next().filter(|&c| c == '"' || c == '\'')?;
+    if val.len() >= 2 && val.ends_with(quote) { Some(&val[1..val.len() - 1]) } else { None }
+}
+
+fn extract_generics(val: &str) -> TypeQuery {
+    let val = val.to_lowercase();
+    match val.find('<') {
+        Some(start) => {
+            let end = val.rfind('>').filter(|&end| end > start).unwrap_or(val.len());
+            TypeQuery {
+                name: val[..start].to_string(),
+                generics: val[start + 1..end].split(',').map(|g| g.trim().to_string()).collect(),
+            }
+        }
+        None => TypeQuery { name: val, generics: Vec::new() },
+    }
+}
+
+/// Checks whether `filter` is either absent or matches the kind `kind`, or a related one.
+fn type_passes_filter(filter: Option<&str>, kind: &str) -> bool {
+    match filter {
+        None => true,
+        Some(filter) if filter == kind => true,
+        Some("constant") => kind == "associatedconstant",
+        Some("fn") => kind == "method" || kind == "tymethod",
+        Some("type") => kind == "primitive" || kind == "associatedtype",
+        Some("trait") => kind == "traitalias",
+        Some(_) => false,
+    }
+}
+
+/// Checks that the names of the generic arguments of `obj` roughly match those in `val`.
+fn check_generics(obj: &SignatureType, val: &TypeQuery) -> usize {
+    if val.generics.is_empty() || obj.generics.len() < val.generics.len() {
+        return MAX_LEV_DISTANCE + 1;
+    }
+    // We need to find the type that matches the most to remove it in order to move forward.
+    let mut elems: Vec<&str> = obj.generics.iter().map(|g| g.as_str()).collect();
+    let mut total = 0;
+    for generic in &val.generics {
+        let best = elems
+            .iter()
+            .enumerate()
+            .map(|(pos, elem)| (lev_distance(elem, generic), pos))
+            .filter(|&(lev, _)| lev <= MAX_LEV_DISTANCE)
+            .min();
+        match best {
+            Some((lev, pos)) => {
+                elems.remove(pos);
+                total += lev;
+            }
+            None => return MAX_LEV_DISTANCE + 1,
+        }
+    }
+    (total + val.generics.len() - 1) / val.generics.len()
+}
+
+/// Checks whether `obj` is the type in `val`, or has it as a generic argument.
+fn check_type_literal(obj: &SignatureType, val: &TypeQuery) -> bool {
+    if obj.name == val.name {
+        if val.generics.is_empty() {
+            return true;
+        }
+        if obj.generics.len() < val.generics.len() {
+            return false;
+        }
+        let mut elems: Vec<&str> = obj.generics.iter().map(|g| g.as_str()).collect();
+        let all_found = val.generics.iter().all(|generic| {
+            match elems.iter().position(|elem| elem == generic) {
+                Some(pos) => {
+                    elems.remove(pos);
+                    true
+                }
+                None => false,
+            }
+        });
+        if all_found {
+            return true;
+        }
+    }
+    // Names didn't match so let's check if one of the generic types could.
+    obj.generics.contains(&val.name)
+}
+
+/// Computes how far `obj` is from the type in `val`, checking its name and generic arguments.
+fn check_type(obj: &SignatureType, val: &TypeQuery) -> usize {
+    if obj.name == val.name {
+        if obj.generics.is_empty() {
+            return 0;
+        }
+        // If the generics don't match, the distance is computed below instead.
+        let lev = check_generics(obj, val);
+        if lev <= MAX_LEV_DISTANCE {
+            return lev;
+        }
+    }
+    let mut lev = lev_distance(&obj.name, &val.name).min(MAX_LEV_DISTANCE + 1);
+    if lev <= MAX_LEV_DISTANCE {
+        // The generics didn't match but the name kinda did, so we give it a distance that isn't
+        // *this* good so it goes into the search results but not too high.
+        lev = (check_generics(obj, val) + lev + 1) / 2;
+    } else {
+        // We can check if the type we're looking for is inside the generics!
+        for generic in &obj.generics {
+            lev = lev.min(lev_distance(generic, &val.name));
+        }
+    }
+    // Whatever happens, the returned distance is "less good", so we add 1 to it.
+    lev + 1
+}
+
+fn inputs<'a>(row: &'a Row, filter: Option<&'a str>) -> impl Iterator<Item = &'a SignatureType> {
+    let inputs = row.entry.signature.iter().flat_map(|s| &s.inputs);
+    inputs.filter(move |ty| type_passes_filter(filter, &ty.kind))
+}
+
+fn output<'a>(row: &'a Row, filter: Option<&'a str>) -> impl Iterator<Item = &'a SignatureType> {
+    let output = row.entry.signature.iter().flat_map(|s| &s.output);
+    output.filter(move |ty| type_passes_filter(filter, &ty.kind))
+}
+
+fn find_arg(row: &Row, val: &TypeQuery, filter: Option<&str>) -> usize {
+    inputs(row, filter).map(|ty| check_type(ty, val)).min().unwrap_or(MAX_LEV_DISTANCE + 1)
+}
+
+fn find_arg_literal(row: &Row, val: &TypeQuery, filter: Option<&str>) -> bool {
+    inputs(row, filter).any(|ty| check_type_literal(ty, val))
+}
+
+fn check_returned(row: &Row, val: &TypeQuery, filter: Option<&str>) -> usize {
+    output(row, filter).map(|ty| check_type(ty, val)).min().unwrap_or(MAX_LEV_DISTANCE + 1)
+}
+
+fn check_returned_literal(row: &Row, val: &TypeQuery, filter: Option<&str>) -> bool {
+    output(row, filter).any(|ty| check_type_literal(ty, val))
+}
+
+/// Computes how far the path of `row` is from containing the path segments in `contains`.
+fn check_path(contains: &[&str], row: &Row) -> usize {
+    let mut path: Vec<String> = row.entry.path.split("::").map(str::to_lowercase).collect();
+    if let Some(ref parent) = row.entry.parent {
+        path.push(parent.name.to_lowercase());
+    }
+    if contains.len() > path.len() {
+        return MAX_LEV_DISTANCE + 1;
+    }
+    path.windows(contains.len())
+        .filter_map(|window| {
+            let mut total = 0;
+            for (segment, expected) in window.iter().zip(contains) {
+                let lev = lev_distance(segment, expected);
+                if lev > MAX_LEV_DISTANCE {
+                    return None;
+                }
+                total += lev;
+            }
+            Some((total as f64 / contains.len() as f64).round() as usize)
+        })
+        .min()
+        .unwrap_or(MAX_LEV_DISTANCE + 1)
+}
+
+/// Checks that every segment of the query appears in the name, the path or the parent of the
+/// item, or is close to its name. For example, `File::open` only matches items that have both
+/// `file` and `open` somewhere.
+fn validate_result(row: &Row, keys: &[&str]) -> bool {
+    let path = row.entry.path.to_lowercase();
+    let parent = row.entry.parent.as_ref().map(|p| p.name.to_lowercase());
+    keys.iter().all(|key| {
+        row.word.contains(key)
+            || path.contains(key)
+            || parent.as_ref().map_or(false, |p| p.contains(key))
+            || lev_distance(&row.word, key) <= MAX_LEV_DISTANCE
+    })
+}
+
+/// Returns the path displayed before the name of the item, and the page of the item.
+fn build_href_and_path(row: &Row) -> (String, String) {
+    let IndexEntry { ref kind, ref name, ref path, ref parent, .. } = row.entry;
+    let dir = path.replace("::", "/");
+    match (&**kind, parent) {
+        ("mod", _) => (format!("{}::", path), format!("{}/{}/index.html", dir, name)),
+        ("primitive" | "keyword", _) => (String::new(), format!("{}/{}.{}.html", dir, kind, name)),
+        ("externcrate", _) => (String::new(), format!("{}/index.html", row.krate)),
+        ("structfield", Some(parent)) if parent.kind == "variant" => {
+            // Fields of variants are documented on the page of the enum, which is the last
+            // element of their path.
+            let (path, enum_name) = path.rsplit_once("::").unwrap_or(("", path));
+            (
+                format!("{}::{}::{}::", path, enum_name, parent.name),
+                format!(
+                    "{}/enum.{}.html#variant.{}.field.{}",
+                    path.replace("::", "/"),
+                    enum_name,
+                    parent.name,
+                    name
+                ),
+            )
+        }
+        (_, Some(parent)) => {
+            let display_path = if parent.kind == "primitive" {
+                format!("{}::", parent.name)
+            } else {
+                format!("{}::{}::", path, parent.name)
+            };
+            let href = format!("{}/{}.{}.html#{}.{}", dir, parent.kind, parent.name, kind, name);
+            (display_path, href)
+        }
+        (_, None) => (format!("{}::", path), format!("{}/{}.{}.html", dir, kind, name)),
+    }
+}
+
+/// Turns the HTML of a description into text for the terminal.
+fn plain_text(desc: &str) -> String {
+    desc.replace("<code>", "`")
+        .replace("</code>", "`")
+        .replace("<em>", "")
+        .replace("</em>", "")
+        .replace("<strong>", "")
+        .replace("</strong>", "")
+}
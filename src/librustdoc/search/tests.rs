@@ -0,0 +1,197 @@
+use super::*;
+
+fn ty(name: &str, kind: &str, generics: &[&str]) -> SignatureType {
+    SignatureType {
+        name: name.to_string(),
+        kind: kind.to_string(),
+        generics: generics.iter().map(|g| g.to_string()).collect(),
+    }
+}
+
+fn item(kind: &str, path: &str, name: &str, parent: Option<(&str, &str)>) -> IndexEntry {
+    IndexEntry {
+        kind: kind.to_string(),
+        name: name.to_string(),
+        path: path.to_string(),
+        desc: format!("The <code>{}</code> item.", name),
+        parent: parent
+            .map(|(kind, name)| Parent { kind: kind.to_string(), name: name.to_string() }),
+        signature: None,
+    }
+}
+
+fn function(
+    path: &str,
+    name: &str,
+    parent: Option<(&str, &str)>,
+    inputs: Vec<SignatureType>,
+    output: Vec<SignatureType>,
+) -> IndexEntry {
+    IndexEntry {
+        signature: Some(Signature { inputs, output }),
+        ..item(if parent.is_some() { "method" } else { "fn" }, path, name, parent)
+    }
+}
+
+fn index() -> SearchIndex {
+    let items = vec![
+        item("struct", "foo::file", "File", None),
+        function(
+            "foo::file",
+            "open",
+            Some(("struct", "File")),
+            vec![ty("path", "struct", &[])],
+            vec![ty("result", "enum", &["file"])],
+        ),
+        function(
+            "foo::file",
+            "read_to_string",
+            Some(("struct", "File")),
+            vec![ty("file", "struct", &[]), ty("string", "struct", &[])],
+            vec![ty("usize", "primitive", &[])],
+        ),
+        function("foo", "open_options", None, vec![], vec![ty("openoptions", "struct", &[])]),
+        item("struct", "foo::path", "Path", None),
+        function(
+            "foo::path",
+            "to_string",
+            Some(("struct", "Path")),
+            vec![ty("path", "struct", &[])],
+            vec![ty("string", "struct", &[])],
+        ),
+        item("enum", "foo", "Shape", None),
+        item("variant", "foo", "Circle", Some(("enum", "Shape"))),
+        item("structfield", "foo::Shape", "radius", Some(("variant", "Circle"))),
+        item("mod", "foo", "file", None),
+        item("macro", "foo", "open", None),
+    ];
+    let mut aliases = BTreeMap::new();
+    aliases.insert("fopen".to_string(), vec![1]);
+    SearchIndex::new(vec![CrateIndex {
+        format_version: FORMAT_VERSION,
+        name: "foo".to_string(),
+        doc: "The foo crate.".to_string(),
+        items,
+        aliases,
+    }])
+}
+
+fn paths(matches: &[Match]) -> Vec<&str> {
+    matches.iter().map(|m| m.path.as_str()).collect()
+}
+
+#[test]
+fn name() {
+    let results = index().search("open");
+    assert_eq!(paths(&results.others), ["foo::file::File::open", "foo::open", "foo::open_options"]);
+    let open = &results.others[0];
+    assert_eq!(open.kind, "method");
+    assert_eq!(open.href, "foo/file/struct.File.html#method.open");
+    assert_eq!(plain_text(&open.desc), "The `open` item.");
+    assert!(results.in_args.is_empty());
+    assert!(results.returned.is_empty());
+}
+
+#[test]
+fn typo() {
+    let results = index().search("opne");
+    assert_eq!(paths(&results.others)[..2], ["foo::file::File::open", "foo::open"]);
+}
+
+#[test]
+fn path() {
+    let results = index().search("file::open");
+    assert_eq!(paths(&results.others), ["foo::file::File::open"]);
+    let results = index().search("path::open");
+    assert!(results.others.is_empty());
+}
+
+#[test]
+fn type_filter() {
+    let results = index().search("macro:open");
+    assert_eq!(paths(&results.others), ["foo::open"]);
+    assert_eq!(results.others[0].href, "foo/macro.open.html");
+    // Methods are functions too.
+    let results = index().search("fn: open");
+    assert_eq!(paths(&results.others), ["foo::file::File::open", "foo::open_options"]);
+}
+
+#[test]
+fn types() {
+    let results = index().search("string");
+    assert_eq!(paths(&results.in_args), ["foo::file::File::read_to_string"]);
+    assert_eq!(paths(&results.returned), ["foo::path::Path::to_string"]);
+    // Generic arguments are searched too.
+    let results = index().search("file");
+    assert!(paths(&results.returned).contains(&"foo::file::File::open"));
+}
+
+#[test]
+fn literal() {
+    let results = index().search("\"open\"");
+    assert_eq!(paths(&results.others), ["foo::file::File::open", "foo::open"]);
+    let results = index().search("\"result<file>\"");
+    assert!(results.others.is_empty());
+    assert_eq!(paths(&results.returned), ["foo::file::File::open"]);
+    let results = index().search("\"result<string>\"");
+    assert!(results.returned.is_empty());
+}
+
+#[test]
+fn signature() {
+    let results = index().search("path -> string");
+    assert_eq!(paths(&results.in_args), ["foo::path::Path::to_string"]);
+    assert_eq!(paths(&results.returned), ["foo::path::Path::to_string"]);
+
+    let results = index().search("file, string -> usize");
+    assert_eq!(paths(&results.in_args), ["foo::file::File::read_to_string"]);
+
+    let results = index().search("* -> result<file>");
+    assert_eq!(paths(&results.others), ["foo::file::File::open"]);
+
+    let results = index().search("path -> *");
+    assert_eq!(paths(&results.in_args), ["foo::file::File::open", "foo::path::Path::to_string"]);
+    assert!(results.returned.is_empty());
+}
+
+#[test]
+fn several_queries() {
+    let results = index().search("shape, circle");
+    assert_eq!(paths(&results.others), ["foo::Shape", "foo::Shape::Circle"]);
+}
+
+#[test]
+fn alias() {
+    let results = index().search("fopen");
+    let alias = &results.others[0];
+    assert_eq!(alias.alias.as_deref(), Some("fopen"));
+    assert_eq!(alias.path, "foo::file::File::open");
+}
+
+#[test]
+fn hrefs() {
+    let results = index().search("foo");
+    assert_eq!(results.others[0].kind, "externcrate");
+    assert_eq!(results.others[0].href, "foo/index.html");
+
+    let results = index().search("mod:file");
+    assert_eq!(results.others[0].href, "foo/file/index.html");
+
+    let results = index().search("radius");
+    assert_eq!(paths(&results.others), ["foo::Shape::Circle::radius"]);
+    assert_eq!(results.others[0].href, "foo/enum.Shape.html#variant.Circle.field.radius");
+}
+
+#[test]
+fn roundtrip() {
+    let json = serde_json::to_string(&CrateIndex {
+        format_version: FORMAT_VERSION,
+        name: "foo".to_string(),
+        doc: String::new(),
+        items: vec![function("foo", "f", None, vec![ty("u8", "primitive", &[])], vec![])],
+        aliases: BTreeMap::new(),
+    })
+    .unwrap();
+    let index = SearchIndex::new(vec![serde_json::from_str(&json).unwrap()]);
+    assert_eq!(paths(&index.search("u8").in_args), ["foo::f"]);
+}
@@ -0,0 +1,20 @@
+// compile-flags: -Zmir-gvn -C overflow-checks=on
+
+// EMIT_MIR gvn.checked.GlobalValueNumbering.diff
+fn checked(a: u32, b: u32) -> u32 {
+    let x = a + b;
+    let y = a + b;
+    x * y
+}
+
+// EMIT_MIR gvn.slices.GlobalValueNumbering.diff
+fn slices(s: &[u32], i: usize) -> u32 {
+    let a = s[i];
+    let b = s[i];
+    a ^ b
+}
+
+fn main() {
+    checked(1, 2);
+    slices(&[1, 2], 1);
+}
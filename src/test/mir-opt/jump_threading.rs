@@ -0,0 +1,56 @@
+// compile-flags: -Zmir-opt-level=1 -Zmir-jump-threading
+// EMIT_MIR jump_threading.rematch.JumpThreading.diff
+
+fn rematch(r: Result<u8, i32>) -> u8 {
+    let o = match r {
+        Ok(x) => Some(x),
+        Err(_) => None,
+    };
+    match o {
+        Some(x) => x,
+        None => 0,
+    }
+}
+
+// The `otherwise` edge of the outer `if` is only taken when `c` is true.
+// EMIT_MIR jump_threading.nested_if.JumpThreading.diff
+fn nested_if(c: bool) -> u8 {
+    if c {
+        if c { 1 } else { 2 }
+    } else {
+        3
+    }
+}
+
+// The value of `ok` is known on both edges entering the second `if`, but its block is too costly
+// to duplicate.
+// EMIT_MIR jump_threading.too_costly.JumpThreading.diff
+fn too_costly(r: Result<u8, u8>, a: u8, b: u8) -> u8 {
+    let ok = match r {
+        Ok(_) => true,
+        Err(_) => false,
+    };
+    let x = a & b;
+    let y = a | b;
+    let z = a ^ b;
+    if ok { x } else { y ^ z }
+}
+
+// `c` is modified through a reference between the two `if`s.
+// EMIT_MIR jump_threading.borrowed.JumpThreading.diff
+fn borrowed(mut c: bool) -> u8 {
+    if c {
+        let r = &mut c;
+        *r = false;
+        if c { 1 } else { 2 }
+    } else {
+        3
+    }
+}
+
+fn main() {
+    rematch(Ok(1));
+    nested_if(true);
+    too_costly(Ok(1), 2, 3);
+    borrowed(true);
+}
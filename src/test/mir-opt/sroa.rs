@@ -0,0 +1,33 @@
+// compile-flags: -Zmir-sroa -C overflow-checks=no
+
+struct Point {
+    x: u32,
+    y: u32,
+}
+
+// EMIT_MIR sroa.structs.ScalarReplacementOfAggregates.diff
+fn structs(a: u32, b: u32) -> u32 {
+    let p = Point { x: a, y: b };
+    p.x + p.y
+}
+
+// EMIT_MIR sroa.tuples.ScalarReplacementOfAggregates.diff
+fn tuples(a: u32, b: u32) -> u32 {
+    let t = (a, b);
+    let u = t;
+    u.0 * t.1
+}
+
+// EMIT_MIR sroa.escaping.ScalarReplacementOfAggregates.diff
+fn escaping(a: u32, b: u32) -> u32 {
+    let p = Point { x: a, y: b };
+    let r = &p;
+    let q = (a, b);
+    r.x + q.0
+}
+
+fn main() {
+    structs(1, 2);
+    tuples(1, 2);
+    escaping(1, 2);
+}
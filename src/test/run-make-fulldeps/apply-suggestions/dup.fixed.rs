@@ -0,0 +1,4 @@
+pub fn f() {
+    let y = 2;
+    println!("{}", y);
+}
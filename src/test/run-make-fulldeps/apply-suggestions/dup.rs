@@ -0,0 +1,4 @@
+pub fn f() {
+    let mut y = 2;
+    println!("{}", y);
+}
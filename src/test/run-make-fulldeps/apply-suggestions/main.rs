@@ -0,0 +1,11 @@
+#[path = "sub/../dup.rs"]
+mod dup;
+#[path = "sub/dup.rs"]
+mod sub_dup;
+
+fn main() {
+    let mut x = 1;
+    println!("{}", x);
+    dup::f();
+    sub_dup::g();
+}
@@ -0,0 +1,4 @@
+pub fn g() {
+    let mut z = 3;
+    println!("{}", z);
+}
@@ -0,0 +1,9 @@
+#![crate_type = "rlib"]
+
+pub fn foo() -> u32 {
+    1
+}
+
+pub fn bar() -> u32 {
+    foo() + 1
+}
@@ -0,0 +1,11 @@
+pub mod changed {
+    pub fn foo() -> u32 {
+        3
+    }
+}
+
+pub mod unchanged {
+    pub fn bar() -> u32 {
+        2
+    }
+}
@@ -0,0 +1,6 @@
+#![crate_type = "lib"]
+
+pub fn f() {
+    let unused = 1;
+    undefined();
+}
@@ -0,0 +1,22 @@
+#!/usr/bin/env python
+
+# Checks that the file given as first argument holds a single SARIF log, whose results or
+# notifications contain each of the other arguments.
+
+import sys
+import json
+
+with open(sys.argv[1]) as f:
+    log = json.load(f)
+
+assert log["version"] == "2.1.0", log["version"]
+assert len(log["runs"]) == 1, log["runs"]
+
+run = log["runs"][0]
+messages = [result["message"]["text"] for result in run["results"]]
+for invocation in run["invocations"]:
+    for notification in invocation["toolExecutionNotifications"]:
+        messages.append(notification["message"]["text"])
+
+for expected in sys.argv[2:]:
+    assert any(expected in message for message in messages), (expected, messages)
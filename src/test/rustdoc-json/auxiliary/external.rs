@@ -0,0 +1,17 @@
+pub struct Foo;
+
+impl Foo {
+    pub fn new() -> Foo {
+        Foo
+    }
+}
+
+pub trait Greet {
+    fn greet(&self) -> &'static str;
+}
+
+impl Greet for Foo {
+    fn greet(&self) -> &'static str {
+        "hello"
+    }
+}
@@ -5,7 +5,8 @@
 # relative ordering to check them against eachother by looking them up in their respective blob's
 # `index` or `paths` mappings. To add a new test run `rustdoc --output-format json -o . yourtest.rs`
 # and then create `yourtest.expected` by stripping unnecessary details from `yourtest.json`. If
-# you're on windows, replace `\` with `/`.
+# you're on windows, replace `\` with `/`. An ID mapped to `null` in the expected `index` must
+# not be in the actual `index`.
 
 import copy
 import sys
@@ -72,9 +73,15 @@ def check_subset(expected_main, actual_main, base_dir):
                 _check_subset(expected, actual, new_trace)
         elif expected_type is ID and expected not in already_checked:
             already_checked.add(expected)
-            _check_subset(
-                expected_index.get(expected, {}), actual_index.get(actual, {}), trace
-            )
+            if expected in expected_index and expected_index[expected] is None:
+                if actual in actual_index:
+                    raise SubsetException(
+                        "ID `{}` should not be in the index".format(actual), trace
+                    )
+            else:
+                _check_subset(
+                    expected_index.get(expected, {}), actual_index.get(actual, {}), trace
+                )
             _check_subset(
                 expected_paths.get(expected, {}), actual_paths.get(actual, {}), trace
             )
@@ -0,0 +1,7 @@
+// aux-crate:external=external.rs
+// compile-flags: -Z unstable-options --json-include-external
+// edition:2018
+
+pub fn make() -> external::Foo {
+    external::Foo::new()
+}
@@ -0,0 +1,6 @@
+// aux-crate:external=external.rs
+// edition:2018
+
+pub fn make() -> external::Foo {
+    external::Foo::new()
+}
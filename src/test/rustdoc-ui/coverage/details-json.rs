@@ -0,0 +1,17 @@
+// compile-flags:-Z unstable-options --output-format json --show-coverage --coverage-details
+// check-pass
+
+//! Make sure that the items missing documentation or examples are listed in the JSON output.
+//!
+//! ```
+//! let x = 1;
+//! ```
+
+/// Documented.
+///
+/// ```
+/// let x = 2;
+/// ```
+pub struct Foo;
+
+pub fn undocumented() {}
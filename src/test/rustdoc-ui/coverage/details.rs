@@ -0,0 +1,35 @@
+// compile-flags:-Z unstable-options --show-coverage --coverage-details
+// check-pass
+
+//! Make sure that the items missing documentation or examples are listed, along with the
+//! coverage per kind of item.
+//!
+//! ```
+//! let x = 1;
+//! ```
+
+/// Documented, with an example.
+///
+/// ```
+/// let x = 2;
+/// ```
+pub struct Foo;
+
+impl Foo {
+    /// Documented.
+    pub fn documented(&self) {}
+
+    pub fn undocumented(&self) {}
+}
+
+pub struct Bar {
+    /// Documented.
+    pub field: u32,
+}
+
+pub mod inner {
+    /// Documented.
+    pub const DOCUMENTED: u32 = 0;
+
+    pub fn undocumented() {}
+}
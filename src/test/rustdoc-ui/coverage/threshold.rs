@@ -0,0 +1,8 @@
+// compile-flags:-Z unstable-options --show-coverage --coverage-threshold 75
+
+//! Make sure that rustdoc errors out when the documentation coverage is below the threshold.
+
+/// Documented.
+pub struct Foo;
+
+pub struct Bar;
@@ -0,0 +1,32 @@
+// compile-flags:--test -Z unstable-options --merge-doctests --test-args=--test-threads=1
+// normalize-stdout-test: "src/test/rustdoc-ui" -> "$$DIR"
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// check-pass
+
+// Merged doctests still pass or panic separately, and the ones which can't be merged are compiled
+// on their own.
+
+/// ```
+/// assert_eq!(2 + 2, 4);
+/// ```
+///
+/// ```should_panic
+/// assert_eq!(2 + 2, 5);
+/// ```
+///
+/// ```
+/// let answer: u32 = "42".parse()?;
+/// assert_eq!(answer, 42);
+/// # Ok::<(), std::num::ParseIntError>(())
+/// ```
+///
+/// ```
+/// fn main() {
+///     assert_eq!(2 + 2, 4);
+/// }
+/// ```
+///
+/// ```compile_fail
+/// let x: u32 = "42";
+/// ```
+pub struct Foo;
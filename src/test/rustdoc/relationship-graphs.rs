@@ -0,0 +1,59 @@
+// compile-flags: -Z unstable-options --generate-graphs
+
+#![crate_name = "foo"]
+
+pub trait Super {}
+
+// @has foo/trait.Trait.html '//h2[@id="relationships"]' 'Relationships'
+// @has - '//svg[@class="relationship-graph"]//g[@class="current"]' 'Trait'
+// @has - '//svg[@class="relationship-graph"]//a[@href="../foo/trait.Super.html"]' 'Super'
+// @has - '//svg[@class="relationship-graph"]//a[@href="../foo/struct.Foo.html"]' 'Foo'
+// @has - '//svg[@class="relationship-graph"]//g[@class="associatedtype"]' 'Trait::Assoc'
+// @has - '//svg[@class="relationship-graph"]//text[@class="edge-label"]' 'Assoc ='
+// @has - '//svg[@class="relationship-graph"]//a[@href="../foo/struct.Bar.html"]' 'Bar'
+pub trait Trait: Super {
+    type Assoc: Clone;
+}
+
+pub struct Foo;
+
+impl Super for Foo {}
+
+impl Trait for Foo {
+    type Assoc = Bar;
+}
+
+// @has foo/index.html '//h2[@id="relationships"]' 'Relationships'
+// @has - '//svg[@class="relationship-graph"]//a[@href="../foo/trait.Trait.html"]' 'Trait'
+// @has - '//svg[@class="relationship-graph"]//a[@href="../foo/struct.Foo.html"]' 'Foo'
+// Derived impls are left out.
+// @!has - '//svg[@class="relationship-graph"]' 'Clone'
+#[derive(Clone)]
+pub struct Bar;
+
+// @!has foo/trait.Lonely.html 'relationship-graph'
+pub trait Lonely {}
+
+// Types are told apart by their path rather than by their name.
+// @has foo/trait.Shared.html
+// @has - '//svg[@class="relationship-graph"]//a[@href="../foo/a/struct.Same.html"]' 'Same'
+// @has - '//svg[@class="relationship-graph"]//a[@href="../foo/b/struct.Same.html"]' 'Same'
+pub trait Shared {}
+
+pub mod a {
+    pub struct Same;
+
+    impl super::Shared for Same {}
+}
+
+pub mod b {
+    pub struct Same;
+
+    impl super::Shared for Same {}
+}
+
+// A generic type is a single node, whether or not it's printed with its generic arguments.
+// @count foo/index.html '//svg[@class="relationship-graph"]//a[@href="../foo/struct.Wrapper.html"]' 1
+pub struct Wrapper<T>(T);
+
+impl<T> Shared for Wrapper<T> {}
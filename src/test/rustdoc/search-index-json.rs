@@ -0,0 +1,25 @@
+// compile-flags: -Z unstable-options --search-index-json
+
+#![crate_name = "foo"]
+
+// @has 'foo/search-index.json' '"crate":"foo"'
+// @has - '"kind":"struct","name":"Foo","path":"foo","desc":"Foo short summary."'
+// @has - '"kind":"method","name":"frobnicate","path":"foo","desc":"","parent":{"kind":"struct","name":"Foo"}'
+// @has - '"inputs":[{"name":"bar","kind":"struct","generics":[]}]'
+// @has - '"output":[{"name":"baz","kind":"struct","generics":[]}]'
+// @has - '"aliases":{"frob":['
+// @!has - 'More Foo.'
+
+/// Foo short summary.
+///
+/// More Foo.
+pub struct Foo;
+pub struct Bar;
+pub struct Baz;
+
+impl Foo {
+    #[doc(alias = "frob")]
+    pub fn frobnicate(&self, _bar: Bar) -> Baz {
+        Baz
+    }
+}
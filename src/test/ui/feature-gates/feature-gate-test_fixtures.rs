@@ -0,0 +1,8 @@
+// compile-flags: --test
+
+#[test_setup] //~ ERROR use of unstable library feature 'test_fixtures'
+fn setup() {}
+
+#[test]
+#[test_params(1, 2)] //~ ERROR parametrized tests are an unstable feature
+fn test(_n: u32) {}
@@ -0,0 +1,36 @@
+// compile-flags: --test -Cpanic=abort -Zpanic_abort_tests
+// run-flags: --test-threads=1
+// run-fail
+// check-run-results
+// exec-env:RUST_BACKTRACE=0
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+
+// ignore-wasm no panic or subprocess support
+// ignore-emscripten no panic or subprocess support
+// ignore-sgx no subprocess support
+
+// The teardown hooks run after a test which panics, even though its process aborts.
+
+#![feature(test_fixtures)]
+#![cfg(test)]
+
+#[test_setup]
+fn setup() {
+    println!("setup");
+}
+
+#[test_teardown]
+fn teardown() {
+    println!("teardown");
+}
+
+#[test]
+fn it_fails() {
+    println!("test");
+    panic!("test failed");
+}
+
+#[test]
+fn it_works() {
+    println!("test");
+}
@@ -0,0 +1,78 @@
+// compile-flags: --test
+// run-flags: --test-threads=1
+// run-pass
+
+// The setup and teardown hooks of a module run around each of its tests, and the tests of its
+// submodules. With one test thread, the tests run in alphabetical order.
+
+#![feature(test_fixtures)]
+#![cfg(test)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static SETUPS: AtomicUsize = AtomicUsize::new(0);
+static TEARDOWNS: AtomicUsize = AtomicUsize::new(0);
+static NESTED_SETUPS: AtomicUsize = AtomicUsize::new(0);
+
+fn counts() -> (usize, usize, usize) {
+    (
+        SETUPS.load(Ordering::SeqCst),
+        TEARDOWNS.load(Ordering::SeqCst),
+        NESTED_SETUPS.load(Ordering::SeqCst),
+    )
+}
+
+mod a {
+    use super::*;
+
+    #[test_setup]
+    fn setup() {
+        SETUPS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test_teardown]
+    fn teardown() {
+        TEARDOWNS.fetch_add(1, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn first() {
+        assert_eq!(counts(), (1, 0, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "the teardown still runs")]
+    fn panics() {
+        assert_eq!(counts(), (3, 2, 1));
+        panic!("the teardown still runs");
+    }
+
+    #[test]
+    fn second() {
+        assert_eq!(counts(), (4, 3, 1));
+    }
+
+    mod nested {
+        use super::super::*;
+
+        #[test_setup]
+        fn setup() -> Result<(), String> {
+            // The hooks of outer modules run first.
+            if SETUPS.load(Ordering::SeqCst) != 2 {
+                return Err("the outer setup didn't run".to_string());
+            }
+            NESTED_SETUPS.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        #[test]
+        fn third() {
+            assert_eq!(counts(), (2, 1, 1));
+        }
+    }
+}
+
+#[test]
+fn outside() {
+    assert_eq!(counts(), (4, 4, 1));
+}
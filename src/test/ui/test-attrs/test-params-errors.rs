@@ -0,0 +1,14 @@
+// compile-flags: --test
+
+#![feature(test_fixtures)]
+
+#[test]
+#[test_params()] //~ ERROR `#[test_params]` requires at least one case
+fn no_cases(_n: u32) {}
+
+#[test]
+#[test_params = "1"] //~ ERROR attribute must be of the form `#[test_params(case1, case2, ...)]`
+fn not_a_list(_n: u32) {}
+
+#[test]
+fn missing_params(_n: u32) {} //~ ERROR functions used as tests can not have any arguments
@@ -0,0 +1,33 @@
+// compile-flags: --test
+// run-flags: --test-threads=1 --skip add::case_2
+// run-pass
+// check-run-results
+// normalize-stdout-test "finished in \d+\.\d+s" -> "finished in $$TIME"
+// ignore-wasm32 no support for `Instant`
+
+// Each case of a parametrized test is run as its own test, which can be filtered.
+
+#![feature(test_fixtures)]
+#![cfg(test)]
+
+use std::num::ParseIntError;
+
+#[test]
+#[test_params((1, 2, 3), (2, 2, 4), (-1, 1, 0))]
+fn add(a: i32, b: i32, sum: i32) {
+    assert_eq!(a + b, sum);
+}
+
+#[test]
+#[test_params("1", "42")]
+fn parse(s: &str) -> Result<(), ParseIntError> {
+    s.parse::<u32>()?;
+    Ok(())
+}
+
+#[test]
+#[test_params(1, 3)]
+#[should_panic(expected = "not even")]
+fn check_odd_numbers_panic(n: u32) {
+    assert!(n % 2 == 0, "not even");
+}
@@ -477,9 +477,15 @@ pub fn test_opts(config: &Config) -> test::TestOpts {
         color: config.color,
         test_threads: None,
         skip: vec![],
+        shard_index: 0,
+        shard_count: 1,
+        shuffle_seed: None,
         list: false,
         options: test::Options::new(),
         time_options: None,
+        timeout: None,
+        save_baseline: None,
+        baseline: None,
         force_run_in_process: false,
     }
 }
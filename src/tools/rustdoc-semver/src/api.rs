@@ -0,0 +1,104 @@
+//! Collection of the public API of a crate from its JSON documentation.
+
+use std::collections::{BTreeMap, HashSet};
+
+use rustdoc_json_types::*;
+
+/// The public items of a crate, by path.
+pub struct PublicApi<'a> {
+    pub krate: &'a Crate,
+    pub items: BTreeMap<String, &'a Item>,
+    visited: HashSet<&'a Id>,
+}
+
+impl<'a> PublicApi<'a> {
+    pub fn new(krate: &'a Crate) -> PublicApi<'a> {
+        let mut api = PublicApi { krate, items: BTreeMap::new(), visited: HashSet::new() };
+        if let Some(root) = krate.index.get(&krate.root) {
+            let name = root.name.clone().unwrap_or_default();
+            api.visit_module(root, &name);
+        }
+        api
+    }
+
+    pub fn get(&self, id: &Id) -> Option<&'a Item> {
+        self.krate.index.get(id)
+    }
+
+    /// Returns the items with the given ids, skipping the ones missing from the index.
+    pub fn resolve(&self, ids: &'a [Id]) -> impl Iterator<Item = &'a Item> + '_ {
+        ids.iter().filter_map(move |id| self.get(id))
+    }
+
+    fn visit_module(&mut self, module: &'a Item, path: &str) {
+        if !self.visited.insert(&module.id) {
+            return;
+        }
+        let items = match &module.inner {
+            ItemEnum::ModuleItem(m) => &m.items,
+            _ => return,
+        };
+        for item in self.resolve(items).collect::<Vec<_>>() {
+            if item.visibility != Visibility::Public {
+                continue;
+            }
+            match &item.inner {
+                ItemEnum::ImportItem(import) => self.visit_import(item, import, path),
+                ItemEnum::ImplItem(_) | ItemEnum::ExternCrateItem { .. } => {}
+                _ => {
+                    if let Some(name) = &item.name {
+                        self.add(format!("{}::{}", path, name), item);
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_import(&mut self, item: &'a Item, import: &'a Import, path: &str) {
+        let target = import.id.as_ref().and_then(|id| self.get(id));
+        match target {
+            Some(target) if import.glob => {
+                if let ItemEnum::ModuleItem(_) = target.inner {
+                    self.visit_module(target, path);
+                } else if let ItemEnum::EnumItem(e) = &target.inner {
+                    for variant in self.resolve(&e.variants).collect::<Vec<_>>() {
+                        if let Some(name) = &variant.name {
+                            self.add(format!("{}::{}", path, name), variant);
+                        }
+                    }
+                }
+            }
+            Some(target) => self.add(format!("{}::{}", path, import.name), target),
+            // Re-exports of items from other crates are kept as is, only their source path can
+            // be compared.
+            None if !import.glob => self.add(format!("{}::{}", path, import.name), item),
+            None => {}
+        }
+    }
+
+    fn add(&mut self, path: String, item: &'a Item) {
+        if let ItemEnum::ModuleItem(_) = item.inner {
+            self.visit_module(item, &path);
+        }
+        // The inherent associated items of types are part of the API as well.
+        if let ItemEnum::StructItem(Struct { impls, .. }) | ItemEnum::EnumItem(Enum { impls, .. }) =
+            &item.inner
+        {
+            for impl_ in self.resolve(impls).collect::<Vec<_>>() {
+                if let ItemEnum::ImplItem(Impl { trait_: None, items, .. }) = &impl_.inner {
+                    for assoc in self.resolve(items).collect::<Vec<_>>() {
+                        if let (Visibility::Public, Some(name)) = (&assoc.visibility, &assoc.name) {
+                            self.items.insert(format!("{}::{}", path, name), assoc);
+                        }
+                    }
+                }
+            }
+        }
+        self.items.insert(path, item);
+    }
+}
+
+/// Whether the item is marked `#[non_exhaustive]`.
+pub fn is_non_exhaustive(item: &Item) -> bool {
+    item.attrs.iter().any(|attr| attr == "#[non_exhaustive]")
+}
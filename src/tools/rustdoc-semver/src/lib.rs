@@ -0,0 +1,541 @@
+//! Classification of the changes between two versions of the public API of a crate, as
+//! documented by `rustdoc --output-format json`, following the semver rules of
+//! [RFC 1105](https://github.com/rust-lang/rfcs/blob/master/text/1105-api-evolution.md).
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::str::FromStr;
+
+use rustdoc_json_types::*;
+
+mod api;
+mod print;
+
+#[cfg(test)]
+mod tests;
+
+use api::{is_non_exhaustive, PublicApi};
+
+/// The auto traits whose implementations are inferred from the fields of a type, and so can
+/// change without the type's definition mentioning them.
+const AUTO_TRAITS: &[&str] = &["Send", "Sync", "Unpin", "UnwindSafe", "RefUnwindSafe"];
+
+/// The version bump required by a change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl FromStr for Bump {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Bump, String> {
+        match s {
+            "patch" => Ok(Bump::Patch),
+            "minor" => Ok(Bump::Minor),
+            "major" => Ok(Bump::Major),
+            _ => Err(format!("unknown version bump `{}`", s)),
+        }
+    }
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        })
+    }
+}
+
+/// A change to the public API.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub bump: Bump,
+    /// The path of the changed item.
+    pub path: String,
+    pub message: String,
+    /// Where the item is defined, in the new version of the crate unless it was removed.
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: `{}`: {}", self.bump, self.path, self.message)?;
+        if let Some(span) = &self.span {
+            write!(f, "\n  --> {}:{}:{}", span.filename.display(), span.begin.0, span.begin.1 + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the changes between the public APIs of `old` and `new`, most severe first.
+pub fn diff(old: &Crate, new: &Crate) -> Vec<Change> {
+    let old = PublicApi::new(old);
+    let new = PublicApi::new(new);
+    let mut cx = DiffContext { old: &old, new: &new, changes: Vec::new() };
+
+    for (path, &old_item) in &old.items {
+        match new.items.get(path) {
+            Some(&new_item) => cx.item(path, old_item, new_item),
+            None => cx.push(Bump::Major, path, old_item, "removed".to_string()),
+        }
+    }
+    for (path, &new_item) in &new.items {
+        if !old.items.contains_key(path) {
+            cx.push(Bump::Minor, path, new_item, "added".to_string());
+        }
+    }
+
+    let mut changes = cx.changes;
+    changes.sort_by(|a, b| b.bump.cmp(&a.bump).then_with(|| a.path.cmp(&b.path)));
+    changes
+}
+
+/// Returns the version bump required by all of `changes` together.
+pub fn required_bump(changes: &[Change]) -> Bump {
+    changes.iter().map(|change| change.bump).max().unwrap_or(Bump::Patch)
+}
+
+struct DiffContext<'a, 'b> {
+    old: &'b PublicApi<'a>,
+    new: &'b PublicApi<'a>,
+    changes: Vec<Change>,
+}
+
+impl<'a, 'b> DiffContext<'a, 'b> {
+    fn push(&mut self, bump: Bump, path: &str, item: &Item, message: String) {
+        self.changes.push(Change {
+            bump,
+            path: path.to_string(),
+            message,
+            span: item.source.clone(),
+        });
+    }
+
+    /// Reports `old` to `new` as a change of `what` if they differ.
+    fn compare(&mut self, bump: Bump, path: &str, item: &Item, what: &str, old: &str, new: &str) {
+        if old != new {
+            self.push(bump, path, item, format!("{} changed from `{}` to `{}`", what, old, new));
+        }
+    }
+
+    /// Reports the items of `old` missing from `new` with `removed`, and the items of `new` missing
+    /// from `old` with `added`.
+    fn compare_sets(
+        &mut self,
+        path: &str,
+        item: &Item,
+        old: &BTreeSet<String>,
+        new: &BTreeSet<String>,
+        removed: (Bump, &str),
+        added: (Bump, &str),
+    ) {
+        for s in old.difference(new) {
+            self.push(removed.0, path, item, format!("{} `{}`", removed.1, s));
+        }
+        for s in new.difference(old) {
+            self.push(added.0, path, item, format!("{} `{}`", added.1, s));
+        }
+    }
+
+    fn item(&mut self, path: &str, old: &'a Item, new: &'a Item) {
+        if old.kind != new.kind {
+            let message = format!("changed from {:?} to {:?}", old.kind, new.kind).to_lowercase();
+            self.push(Bump::Major, path, new, message);
+            return;
+        }
+        if old.deprecation.is_none() && new.deprecation.is_some() {
+            self.push(Bump::Minor, path, new, "deprecated".to_string());
+        }
+
+        match (&old.inner, &new.inner) {
+            (ItemEnum::FunctionItem(o), ItemEnum::FunctionItem(n)) => {
+                self.header(path, new, &o.header, &n.header);
+                self.compare(Bump::Major, path, new, "ABI", &o.abi, &n.abi);
+                self.function(path, new, (&o.generics, &o.decl), (&n.generics, &n.decl));
+            }
+            (ItemEnum::MethodItem(o), ItemEnum::MethodItem(n)) => {
+                self.header(path, new, &o.header, &n.header);
+                self.function(path, new, (&o.generics, &o.decl), (&n.generics, &n.decl));
+            }
+            (ItemEnum::StructItem(o), ItemEnum::StructItem(n)) => {
+                self.struct_(path, old, new, o, n);
+                self.type_impls(path, new, &o.impls, &n.impls);
+            }
+            (ItemEnum::EnumItem(o), ItemEnum::EnumItem(n)) => {
+                self.enum_(path, old, new, o, n);
+                self.type_impls(path, new, &o.impls, &n.impls);
+            }
+            (ItemEnum::TraitItem(o), ItemEnum::TraitItem(n)) => self.trait_(path, new, o, n),
+            (ItemEnum::TypedefItem(o), ItemEnum::TypedefItem(n)) => {
+                self.generics(path, new, &o.generics, &n.generics, true);
+                let (old_ty, new_ty) = (print::type_(&o.type_), print::type_(&n.type_));
+                self.compare(Bump::Major, path, new, "type", &old_ty, &new_ty);
+            }
+            (ItemEnum::ConstantItem(o), ItemEnum::ConstantItem(n)) => {
+                let (old_ty, new_ty) = (print::type_(&o.type_), print::type_(&n.type_));
+                self.compare(Bump::Major, path, new, "type", &old_ty, &new_ty);
+                self.compare(Bump::Patch, path, new, "value", &o.expr, &n.expr);
+            }
+            (ItemEnum::StaticItem(o), ItemEnum::StaticItem(n)) => {
+                let (old_ty, new_ty) = (print::type_(&o.type_), print::type_(&n.type_));
+                self.compare(Bump::Major, path, new, "type", &old_ty, &new_ty);
+                if o.mutable != n.mutable {
+                    let message = if n.mutable { "made mutable" } else { "made immutable" };
+                    self.push(Bump::Major, path, new, message.to_string());
+                }
+            }
+            (ItemEnum::ProcMacroItem(o), ItemEnum::ProcMacroItem(n)) => {
+                let old_helpers = o.helpers.iter().cloned().collect();
+                let new_helpers = n.helpers.iter().cloned().collect();
+                let removed = (Bump::Major, "removed helper attribute");
+                let added = (Bump::Minor, "added helper attribute");
+                self.compare_sets(path, new, &old_helpers, &new_helpers, removed, added);
+            }
+            (ItemEnum::ImportItem(o), ItemEnum::ImportItem(n)) => {
+                self.compare(Bump::Major, path, new, "re-export", &o.span, &n.span);
+            }
+            (
+                ItemEnum::AssocConstItem { type_: o, .. },
+                ItemEnum::AssocConstItem { type_: n, .. },
+            ) => {
+                let (old_ty, new_ty) = (print::type_(o), print::type_(n));
+                self.compare(Bump::Major, path, new, "type", &old_ty, &new_ty);
+            }
+            _ => {}
+        }
+    }
+
+    fn header(&mut self, path: &str, item: &Item, old: &str, new: &str) {
+        let has = |header: &str, qualifier| header.split_whitespace().any(|q| q == qualifier);
+        // The bumps required to add and to remove each qualifier.
+        for &(qualifier, added, removed) in &[
+            ("const", Bump::Minor, Bump::Major),
+            ("unsafe", Bump::Major, Bump::Minor),
+            ("async", Bump::Major, Bump::Major),
+        ] {
+            match (has(old, qualifier), has(new, qualifier)) {
+                (false, true) => self.push(added, path, item, format!("made `{}`", qualifier)),
+                (true, false) => {
+                    self.push(removed, path, item, format!("no longer `{}`", qualifier))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn function(
+        &mut self,
+        path: &str,
+        item: &Item,
+        (old_generics, old_decl): (&Generics, &FnDecl),
+        (new_generics, new_decl): (&Generics, &FnDecl),
+    ) {
+        self.generics(path, item, old_generics, new_generics, false);
+        let (old, new) = (print::decl(old_decl, false), print::decl(new_decl, false));
+        if old != new {
+            let name = item.name.as_deref().unwrap_or_default();
+            let old = print::fn_signature(name, "", old_generics, old_decl);
+            let new = print::fn_signature(name, "", new_generics, new_decl);
+            self.compare(Bump::Major, path, item, "signature", &old, &new);
+        }
+    }
+
+    /// Compares the generic parameters and their bounds. New parameters are only compatible if
+    /// they have defaults and `defaults_allowed` is set, which isn't the case for functions since
+    /// they can be given explicitly with the turbofish syntax.
+    fn generics(
+        &mut self,
+        path: &str,
+        item: &Item,
+        old: &Generics,
+        new: &Generics,
+        defaults_allowed: bool,
+    ) {
+        fn params(generics: &Generics) -> BTreeMap<String, &GenericParamDefKind> {
+            generics.params.iter().map(|param| (param.name.clone(), &param.kind)).collect()
+        }
+        let (old_params, new_params) = (params(old), params(new));
+        for (name, kind) in &new_params {
+            match (old_params.get(name), kind) {
+                (Some(_), _) => {}
+                (None, GenericParamDefKind::Type { default: Some(_), .. }) if defaults_allowed => {
+                    self.push(
+                        Bump::Minor,
+                        path,
+                        item,
+                        format!("added defaulted parameter `{}`", name),
+                    );
+                }
+                // Impl trait parameters can't be given explicitly anyway.
+                (None, GenericParamDefKind::Type { .. }) if name.starts_with("impl ") => {}
+                (None, _) => {
+                    self.push(
+                        Bump::Major,
+                        path,
+                        item,
+                        format!("added generic parameter `{}`", name),
+                    );
+                }
+            }
+        }
+        for name in old_params.keys() {
+            if !new_params.contains_key(name) {
+                self.push(Bump::Major, path, item, format!("removed generic parameter `{}`", name));
+            }
+        }
+
+        let bounds = |generics: &Generics| -> BTreeSet<String> {
+            let mut bounds = BTreeSet::new();
+            for param in &generics.params {
+                if let GenericParamDefKind::Type { bounds: b, .. } = &param.kind {
+                    bounds.extend(b.iter().map(|b| format!("{}: {}", param.name, print::bound(b))));
+                }
+            }
+            for predicate in &generics.where_predicates {
+                match predicate {
+                    WherePredicate::BoundPredicate { ty, bounds: b } => {
+                        let ty = print::type_(ty);
+                        bounds.extend(b.iter().map(|b| format!("{}: {}", ty, print::bound(b))));
+                    }
+                    _ => {
+                        bounds.insert(print::where_predicate(predicate));
+                    }
+                }
+            }
+            bounds
+        };
+        let removed = (Bump::Minor, "removed bound");
+        let added = (Bump::Major, "added bound");
+        self.compare_sets(path, item, &bounds(old), &bounds(new), removed, added);
+    }
+
+    fn non_exhaustive(&mut self, path: &str, old: &Item, new: &Item) {
+        match (is_non_exhaustive(old), is_non_exhaustive(new)) {
+            (false, true) => {
+                self.push(Bump::Major, path, new, "made `#[non_exhaustive]`".to_string())
+            }
+            (true, false) => {
+                self.push(Bump::Minor, path, new, "no longer `#[non_exhaustive]`".to_string())
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the public fields of a struct or struct variant, by name.
+    fn fields(api: &PublicApi<'a>, fields: &'a [Id]) -> BTreeMap<String, String> {
+        api.resolve(fields)
+            .filter_map(|field| match (&field.name, &field.inner) {
+                (Some(name), ItemEnum::StructFieldItem(ty)) => {
+                    Some((name.clone(), print::type_(ty)))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn struct_(
+        &mut self,
+        path: &str,
+        old_item: &Item,
+        new_item: &Item,
+        old: &'a Struct,
+        new: &'a Struct,
+    ) {
+        self.generics(path, new_item, &old.generics, &new.generics, true);
+        self.non_exhaustive(path, old_item, new_item);
+        if old.struct_type != new.struct_type {
+            let message =
+                format!("changed from {:?} to {:?} struct", old.struct_type, new.struct_type);
+            self.push(Bump::Major, path, new_item, message.to_lowercase());
+            return;
+        }
+
+        // A struct can be built with a literal or matched exhaustively from other crates as long
+        // as all of its fields are public.
+        let constructible = |item, s: &Struct| !s.fields_stripped && !is_non_exhaustive(item);
+        if constructible(old_item, old) && new.fields_stripped {
+            self.push(Bump::Major, path, new_item, "added private fields".to_string());
+        }
+        let breaking_to_add = constructible(old_item, old) && constructible(new_item, new);
+        let old_fields = Self::fields(self.old, &old.fields);
+        let new_fields = Self::fields(self.new, &new.fields);
+        self.fields_(path, new_item, &old_fields, &new_fields, breaking_to_add);
+    }
+
+    fn fields_(
+        &mut self,
+        path: &str,
+        item: &Item,
+        old: &BTreeMap<String, String>,
+        new: &BTreeMap<String, String>,
+        breaking_to_add: bool,
+    ) {
+        for (name, old_ty) in old {
+            match new.get(name) {
+                Some(new_ty) => {
+                    let what = format!("type of field `{}`", name);
+                    self.compare(Bump::Major, path, item, &what, old_ty, new_ty);
+                }
+                None => self.push(Bump::Major, path, item, format!("removed field `{}`", name)),
+            }
+        }
+        for name in new.keys().filter(|name| !old.contains_key(*name)) {
+            let bump = if breaking_to_add { Bump::Major } else { Bump::Minor };
+            self.push(bump, path, item, format!("added field `{}`", name));
+        }
+    }
+
+    fn enum_(
+        &mut self,
+        path: &str,
+        old_item: &Item,
+        new_item: &Item,
+        old: &'a Enum,
+        new: &'a Enum,
+    ) {
+        self.generics(path, new_item, &old.generics, &new.generics, true);
+        self.non_exhaustive(path, old_item, new_item);
+
+        let variants = |api: &PublicApi<'a>, ids: &'a [Id]| -> BTreeMap<String, &'a Item> {
+            api.resolve(ids).filter_map(|v| Some((v.name.clone()?, v))).collect()
+        };
+        let old_variants = variants(self.old, &old.variants);
+        let new_variants = variants(self.new, &new.variants);
+        for (name, old_variant) in &old_variants {
+            let new_variant = match new_variants.get(name) {
+                Some(new_variant) => new_variant,
+                None => {
+                    self.push(Bump::Major, path, new_item, format!("removed variant `{}`", name));
+                    continue;
+                }
+            };
+            let variant_path = format!("{}::{}", path, name);
+            match (&old_variant.inner, &new_variant.inner) {
+                (
+                    ItemEnum::VariantItem(Variant::Tuple(o)),
+                    ItemEnum::VariantItem(Variant::Tuple(n)),
+                ) => {
+                    let old = o.iter().map(print::type_).collect::<Vec<_>>().join(", ");
+                    let new = n.iter().map(print::type_).collect::<Vec<_>>().join(", ");
+                    self.compare(Bump::Major, &variant_path, new_variant, "fields", &old, &new);
+                }
+                (
+                    ItemEnum::VariantItem(Variant::Struct(o)),
+                    ItemEnum::VariantItem(Variant::Struct(n)),
+                ) => {
+                    let old = Self::fields(self.old, o);
+                    let new = Self::fields(self.new, n);
+                    let breaking_to_add = !is_non_exhaustive(old_variant);
+                    self.fields_(&variant_path, new_variant, &old, &new, breaking_to_add);
+                }
+                (ItemEnum::VariantItem(o), ItemEnum::VariantItem(n)) if o != n => {
+                    let message = "changed kind of variant".to_string();
+                    self.push(Bump::Major, &variant_path, new_variant, message);
+                }
+                _ => {}
+            }
+        }
+        let bump = if is_non_exhaustive(old_item) { Bump::Minor } else { Bump::Major };
+        for name in new_variants.keys().filter(|name| !old_variants.contains_key(*name)) {
+            self.push(bump, path, new_item, format!("added variant `{}`", name));
+        }
+    }
+
+    /// Compares the trait implementations of a struct or enum, including the auto traits it
+    /// implements because of its fields.
+    fn type_impls(&mut self, path: &str, item: &Item, old: &'a [Id], new: &'a [Id]) {
+        let traits = |api: &PublicApi<'a>, ids: &'a [Id]| -> (BTreeSet<String>, BTreeSet<String>) {
+            let mut auto = BTreeSet::new();
+            let mut traits = BTreeSet::new();
+            for impl_ in api.resolve(ids) {
+                if let ItemEnum::ImplItem(Impl {
+                    trait_: Some(trait_),
+                    negative: false,
+                    blanket_impl: None,
+                    synthetic,
+                    ..
+                }) = &impl_.inner
+                {
+                    let name = print::type_(trait_);
+                    if *synthetic && AUTO_TRAITS.iter().any(|t| name == *t) {
+                        auto.insert(name);
+                    } else if !*synthetic {
+                        traits.insert(name);
+                    }
+                }
+            }
+            (auto, traits)
+        };
+        let (old_auto, old_traits) = traits(self.old, old);
+        let (new_auto, new_traits) = traits(self.new, new);
+        let removed = (Bump::Major, "no longer implements auto trait");
+        let added = (Bump::Minor, "now implements auto trait");
+        self.compare_sets(path, item, &old_auto, &new_auto, removed, added);
+        let removed = (Bump::Major, "no longer implements");
+        let added = (Bump::Minor, "now implements");
+        self.compare_sets(path, item, &old_traits, &new_traits, removed, added);
+    }
+
+    fn trait_(&mut self, path: &str, item: &Item, old: &'a Trait, new: &'a Trait) {
+        self.generics(path, item, &old.generics, &new.generics, true);
+        let supertraits = |t: &Trait| t.bounds.iter().map(print::bound).collect();
+        let removed = (Bump::Minor, "removed supertrait");
+        let added = (Bump::Major, "added supertrait");
+        self.compare_sets(path, item, &supertraits(old), &supertraits(new), removed, added);
+        if old.is_unsafe != new.is_unsafe {
+            let message = if new.is_unsafe { "made `unsafe`" } else { "no longer `unsafe`" };
+            self.push(Bump::Major, path, item, message.to_string());
+        }
+        if old.is_auto != new.is_auto {
+            let message =
+                if new.is_auto { "made an auto trait" } else { "no longer an auto trait" };
+            self.push(Bump::Major, path, item, message.to_string());
+        }
+
+        let items = |api: &PublicApi<'a>, ids: &'a [Id]| -> BTreeMap<String, &'a Item> {
+            api.resolve(ids).filter_map(|i| Some((i.name.clone()?, i))).collect()
+        };
+        // Whether implementors of the trait have to provide the item.
+        let required = |item: &Item| match &item.inner {
+            ItemEnum::MethodItem(m) => !m.has_body,
+            ItemEnum::AssocConstItem { default, .. } => default.is_none(),
+            ItemEnum::AssocTypeItem { default, .. } => default.is_none(),
+            _ => false,
+        };
+        let old_items = items(self.old, &old.items);
+        let new_items = items(self.new, &new.items);
+        for (name, old_item) in &old_items {
+            let item_path = format!("{}::{}", path, name);
+            match new_items.get(name) {
+                Some(new_item) => {
+                    self.item(&item_path, old_item, new_item);
+                    if !required(old_item) && required(new_item) {
+                        self.push(
+                            Bump::Major,
+                            &item_path,
+                            new_item,
+                            "no longer provided".to_string(),
+                        );
+                    }
+                }
+                None => self.push(Bump::Major, &item_path, old_item, "removed".to_string()),
+            }
+        }
+        for (name, new_item) in &new_items {
+            if !old_items.contains_key(name) {
+                let item_path = format!("{}::{}", path, name);
+                let (bump, message) = if required(new_item) {
+                    (Bump::Major, "added without a default")
+                } else {
+                    (Bump::Minor, "added")
+                };
+                self.push(bump, &item_path, new_item, message.to_string());
+            }
+        }
+    }
+}
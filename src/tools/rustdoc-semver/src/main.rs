@@ -0,0 +1,87 @@
+//! Compares two versions of the public API of a crate, as documented by
+//! `rustdoc -Z unstable-options --output-format json`, and reports the version bump the changes
+//! require under semver.
+//!
+//! Usage: `rustdoc-semver [--max-bump patch|minor|major] OLD.json NEW.json`
+//!
+//! With `--max-bump`, exits with an error if the changes require a larger bump than given, e.g.
+//! `--max-bump minor` for a release which isn't allowed to break the API.
+
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use rustdoc_json_types::{Crate, FORMAT_VERSION};
+use rustdoc_semver::Bump;
+
+fn main() {
+    match doit() {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Returns whether the changes are within the allowed bump.
+fn doit() -> Result<bool, Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let mut max_bump = None;
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--max-bump" => {
+                max_bump = match args.next() {
+                    Some(s) => Some(s.parse::<Bump>()?),
+                    None => {
+                        return Err("--max-bump requires one of `patch`, `minor` or `major`".into())
+                    }
+                };
+            }
+            s if s.starts_with('-') => return Err(format!("unexpected argument `{}`", s).into()),
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    let (old, new) = match &paths[..] {
+        [old, new] => (load(old)?, load(new)?),
+        _ => return Err("expected the paths of the old and new JSON outputs".into()),
+    };
+
+    let changes = rustdoc_semver::diff(&old, &new);
+    for change in &changes {
+        println!("{}", change);
+    }
+    let bump = rustdoc_semver::required_bump(&changes);
+    println!("required version bump: {} ({} changes)", bump, changes.len());
+
+    match max_bump {
+        Some(max_bump) if bump > max_bump => {
+            eprintln!(
+                "error: the changes require a {} version bump, only {} is allowed",
+                bump, max_bump
+            );
+            Ok(false)
+        }
+        _ => Ok(true),
+    }
+}
+
+fn load(path: &Path) -> Result<Crate, Box<dyn Error>> {
+    let file =
+        File::open(path).map_err(|e| format!("failed to open `{}`: {}", path.display(), e))?;
+    let krate: Crate = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| format!("failed to parse `{}`: {}", path.display(), e))?;
+    if krate.format_version != FORMAT_VERSION {
+        return Err(format!(
+            "`{}` has format version {}, but only version {} is supported",
+            path.display(),
+            krate.format_version,
+            FORMAT_VERSION
+        )
+        .into());
+    }
+    Ok(krate)
+}
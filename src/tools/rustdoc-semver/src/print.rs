@@ -0,0 +1,196 @@
+//! Rendering of the JSON types back into Rust syntax, used both to compare signatures without
+//! being affected by the item ids (which change between builds) and to report them.
+
+use rustdoc_json_types::*;
+
+pub fn type_(ty: &Type) -> String {
+    match ty {
+        Type::ResolvedPath { name, args, param_names, .. } => {
+            let path = match args {
+                Some(args) => format!("{}{}", name, generic_args(args)),
+                None => name.clone(),
+            };
+            if param_names.is_empty() {
+                path
+            } else {
+                format!("dyn {} + {}", path, bounds(param_names))
+            }
+        }
+        Type::Generic(name) | Type::Primitive(name) => name.clone(),
+        Type::FunctionPointer(f) => {
+            let mut s = String::new();
+            if !f.generic_params.is_empty() {
+                s.push_str(&format!("for<{}> ", generic_params(&f.generic_params)));
+            }
+            if f.is_unsafe {
+                s.push_str("unsafe ");
+            }
+            if f.abi != "\"Rust\"" {
+                s.push_str(&format!("extern {} ", f.abi));
+            }
+            s.push_str(&format!("fn{}", decl(&f.decl, false)));
+            s
+        }
+        Type::Tuple(types) if types.len() == 1 => format!("({},)", type_(&types[0])),
+        Type::Tuple(types) => format!("({})", list(types, type_)),
+        Type::Slice(ty) => format!("[{}]", type_(ty)),
+        Type::Array { type_: ty, len } => format!("[{}; {}]", type_(ty), len),
+        Type::ImplTrait(b) => format!("impl {}", bounds(b)),
+        Type::Never => "!".to_string(),
+        Type::Infer => "_".to_string(),
+        Type::RawPointer { mutable, type_: ty } => {
+            format!("*{} {}", if *mutable { "mut" } else { "const" }, type_(ty))
+        }
+        Type::BorrowedRef { lifetime, mutable, type_: ty } => {
+            let mut s = String::from("&");
+            if let Some(lifetime) = lifetime {
+                s.push_str(lifetime);
+                s.push(' ');
+            }
+            if *mutable {
+                s.push_str("mut ");
+            }
+            s.push_str(&type_(ty));
+            s
+        }
+        Type::QualifiedPath { name, self_type, trait_ } => {
+            format!("<{} as {}>::{}", type_(self_type), type_(trait_), name)
+        }
+    }
+}
+
+fn generic_args(args: &GenericArgs) -> String {
+    match args {
+        GenericArgs::AngleBracketed { args, bindings } => {
+            let mut parts: Vec<_> = args
+                .iter()
+                .map(|arg| match arg {
+                    GenericArg::Lifetime(lifetime) => lifetime.clone(),
+                    GenericArg::Type(ty) => type_(ty),
+                    GenericArg::Const(c) => c.expr.clone(),
+                })
+                .collect();
+            parts.extend(bindings.iter().map(|binding| match &binding.binding {
+                TypeBindingKind::Equality(ty) => format!("{} = {}", binding.name, type_(ty)),
+                TypeBindingKind::Constraint(b) => format!("{}: {}", binding.name, bounds(b)),
+            }));
+            if parts.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", parts.join(", "))
+            }
+        }
+        GenericArgs::Parenthesized { inputs, output } => match output {
+            Some(output) => format!("({}) -> {}", list(inputs, type_), type_(output)),
+            None => format!("({})", list(inputs, type_)),
+        },
+    }
+}
+
+pub fn bound(bound: &GenericBound) -> String {
+    match bound {
+        GenericBound::TraitBound { trait_, generic_params: params, modifier } => {
+            let mut s = String::new();
+            if !params.is_empty() {
+                s.push_str(&format!("for<{}> ", generic_params(params)));
+            }
+            match modifier {
+                TraitBoundModifier::None => {}
+                TraitBoundModifier::Maybe => s.push('?'),
+                TraitBoundModifier::MaybeConst => s.push_str("?const "),
+            }
+            s.push_str(&type_(trait_));
+            s
+        }
+        GenericBound::Outlives(lifetime) => lifetime.clone(),
+    }
+}
+
+pub fn bounds(bounds: &[GenericBound]) -> String {
+    bounds.iter().map(bound).collect::<Vec<_>>().join(" + ")
+}
+
+fn generic_param(param: &GenericParamDef) -> String {
+    match &param.kind {
+        GenericParamDefKind::Lifetime => param.name.clone(),
+        GenericParamDefKind::Type { bounds: b, default } => {
+            let mut s = param.name.clone();
+            if !b.is_empty() {
+                s.push_str(&format!(": {}", bounds(b)));
+            }
+            if let Some(default) = default {
+                s.push_str(&format!(" = {}", type_(default)));
+            }
+            s
+        }
+        GenericParamDefKind::Const(ty) => format!("const {}: {}", param.name, type_(ty)),
+    }
+}
+
+fn generic_params(params: &[GenericParamDef]) -> String {
+    list(params, generic_param)
+}
+
+pub fn where_predicate(predicate: &WherePredicate) -> String {
+    match predicate {
+        WherePredicate::BoundPredicate { ty, bounds: b } => {
+            format!("{}: {}", type_(ty), bounds(b))
+        }
+        WherePredicate::RegionPredicate { lifetime, bounds: b } => {
+            format!("{}: {}", lifetime, bounds(b))
+        }
+        WherePredicate::EqPredicate { lhs, rhs } => format!("{} == {}", type_(lhs), type_(rhs)),
+    }
+}
+
+/// Renders generics as they appear after the name of an item, with the where clause separate.
+pub fn generics(generics: &Generics) -> (String, String) {
+    let params = if generics.params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", generic_params(&generics.params))
+    };
+    let where_clause = if generics.where_predicates.is_empty() {
+        String::new()
+    } else {
+        format!(" where {}", list(&generics.where_predicates, where_predicate))
+    };
+    (params, where_clause)
+}
+
+/// Renders the parameters and return type of a function. Argument names are only included if
+/// `names` is set, since changing them doesn't affect the API.
+pub fn decl(decl: &FnDecl, names: bool) -> String {
+    let mut inputs: Vec<_> = decl
+        .inputs
+        .iter()
+        .map(|(name, ty)| match ty {
+            Type::Generic(g) if name == "self" && g == "Self" => "self".to_string(),
+            Type::BorrowedRef { lifetime, mutable, type_: ty }
+                if name == "self" && **ty == Type::Generic("Self".to_string()) =>
+            {
+                let lifetime = lifetime.as_ref().map(|l| format!("{} ", l)).unwrap_or_default();
+                format!("&{}{}self", lifetime, if *mutable { "mut " } else { "" })
+            }
+            _ if names => format!("{}: {}", name, type_(ty)),
+            _ => type_(ty),
+        })
+        .collect();
+    if decl.c_variadic {
+        inputs.push("...".to_string());
+    }
+    match &decl.output {
+        Some(output) => format!("({}) -> {}", inputs.join(", "), type_(output)),
+        None => format!("({})", inputs.join(", ")),
+    }
+}
+
+/// Renders the signature of a function or method named `name`.
+pub fn fn_signature(name: &str, header: &str, generics_: &Generics, decl_: &FnDecl) -> String {
+    let (params, where_clause) = generics(generics_);
+    format!("{}fn {}{}{}{}", header, name, params, decl(decl_, true), where_clause)
+}
+
+fn list<T>(items: &[T], f: impl Fn(&T) -> String) -> String {
+    items.iter().map(f).collect::<Vec<_>>().join(", ")
+}
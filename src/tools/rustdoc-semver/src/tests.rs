@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::*;
+
+/// Builds the JSON documentation of a crate named `foo`.
+struct Builder {
+    index: HashMap<Id, Item>,
+    root: Vec<Id>,
+}
+
+impl Builder {
+    fn new() -> Builder {
+        Builder { index: HashMap::new(), root: Vec::new() }
+    }
+
+    fn item(&mut self, name: &str, kind: ItemKind, inner: ItemEnum) -> Id {
+        let id = Id(format!("0:{}", self.index.len() + 1));
+        let item = Item {
+            id: id.clone(),
+            crate_id: 0,
+            name: if name.is_empty() { None } else { Some(name.to_string()) },
+            source: Some(Span {
+                filename: PathBuf::from("src/lib.rs"),
+                begin: (self.index.len() + 1, 4),
+                end: (self.index.len() + 1, 8),
+            }),
+            visibility: Visibility::Public,
+            docs: String::new(),
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            kind,
+            inner,
+        };
+        self.index.insert(id.clone(), item);
+        id
+    }
+
+    /// Adds an item to the root module.
+    fn public(&mut self, name: &str, kind: ItemKind, inner: ItemEnum) -> Id {
+        let id = self.item(name, kind, inner);
+        self.root.push(id.clone());
+        id
+    }
+
+    fn attr(&mut self, id: &Id, attr: &str) {
+        self.index.get_mut(id).unwrap().attrs.push(attr.to_string());
+    }
+
+    fn field(&mut self, name: &str, ty: Type) -> Id {
+        self.item(name, ItemKind::StructField, ItemEnum::StructFieldItem(ty))
+    }
+
+    fn impl_(&mut self, trait_: Option<&str>, synthetic: bool, items: Vec<Id>) -> Id {
+        let inner = ItemEnum::ImplItem(Impl {
+            is_unsafe: false,
+            generics: Generics::default(),
+            provided_trait_methods: Vec::new(),
+            trait_: trait_.map(path),
+            for_: path("S"),
+            items,
+            negative: false,
+            synthetic,
+            blanket_impl: None,
+        });
+        self.item("", ItemKind::Impl, inner)
+    }
+
+    fn krate(mut self) -> Crate {
+        let root = self.item(
+            "foo",
+            ItemKind::Module,
+            ItemEnum::ModuleItem(Module { is_crate: true, items: self.root.clone() }),
+        );
+        Crate {
+            root,
+            crate_version: None,
+            includes_private: false,
+            index: self.index,
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: FORMAT_VERSION,
+        }
+    }
+}
+
+fn path(name: &str) -> Type {
+    Type::ResolvedPath {
+        name: name.to_string(),
+        id: Id("1:1".to_string()),
+        args: None,
+        param_names: vec![],
+    }
+}
+
+fn primitive(name: &str) -> Type {
+    Type::Primitive(name.to_string())
+}
+
+fn decl(inputs: Vec<Type>, output: Option<Type>) -> FnDecl {
+    let inputs = inputs.into_iter().enumerate().map(|(i, ty)| (format!("x{}", i), ty)).collect();
+    FnDecl { inputs, output, c_variadic: false }
+}
+
+fn function(generics: Generics, decl: FnDecl) -> ItemEnum {
+    ItemEnum::FunctionItem(Function {
+        decl,
+        generics,
+        header: String::new(),
+        abi: "\"Rust\"".to_string(),
+    })
+}
+
+fn method(decl: FnDecl, has_body: bool) -> ItemEnum {
+    ItemEnum::MethodItem(Method {
+        decl,
+        generics: Generics::default(),
+        header: String::new(),
+        has_body,
+    })
+}
+
+fn type_param(name: &str, bounds: &[&str]) -> Generics {
+    let bounds = bounds
+        .iter()
+        .map(|b| GenericBound::TraitBound {
+            trait_: path(b),
+            generic_params: vec![],
+            modifier: TraitBoundModifier::None,
+        })
+        .collect();
+    Generics {
+        params: vec![GenericParamDef {
+            name: name.to_string(),
+            kind: GenericParamDefKind::Type { bounds, default: None },
+        }],
+        where_predicates: vec![],
+    }
+}
+
+fn struct_(fields: Vec<Id>, fields_stripped: bool, impls: Vec<Id>) -> ItemEnum {
+    ItemEnum::StructItem(Struct {
+        struct_type: StructType::Plain,
+        generics: Generics::default(),
+        fields_stripped,
+        fields,
+        impls,
+    })
+}
+
+fn messages(changes: &[Change]) -> Vec<String> {
+    changes.iter().map(|c| format!("{}: {}: {}", c.bump, c.path, c.message)).collect()
+}
+
+fn crate_with(f: impl Fn(&mut Builder)) -> Crate {
+    let mut b = Builder::new();
+    f(&mut b);
+    b.krate()
+}
+
+#[test]
+fn unchanged() {
+    let build = |b: &mut Builder| {
+        b.public("f", ItemKind::Function, function(Generics::default(), decl(vec![], None)));
+    };
+    let changes = diff(&crate_with(build), &crate_with(build));
+    assert!(changes.is_empty());
+    assert_eq!(required_bump(&changes), Bump::Patch);
+}
+
+#[test]
+fn removed_and_added() {
+    let old = crate_with(|b| {
+        b.public("a", ItemKind::Function, function(Generics::default(), decl(vec![], None)));
+    });
+    let new = crate_with(|b| {
+        b.public("b", ItemKind::Function, function(Generics::default(), decl(vec![], None)));
+    });
+    let changes = diff(&old, &new);
+    assert_eq!(messages(&changes), ["major: foo::a: removed", "minor: foo::b: added"]);
+    assert_eq!(required_bump(&changes), Bump::Major);
+}
+
+#[test]
+fn signature() {
+    let build = |ty: &'static str| {
+        move |b: &mut Builder| {
+            let f = function(Generics::default(), decl(vec![primitive(ty)], Some(path("String"))));
+            b.public("f", ItemKind::Function, f);
+        }
+    };
+    let changes = diff(&crate_with(build("u32")), &crate_with(build("u64")));
+    assert_eq!(
+        messages(&changes),
+        ["major: foo::f: signature changed from `fn f(x0: u32) -> String` to \
+          `fn f(x0: u64) -> String`"]
+    );
+}
+
+#[test]
+fn trait_bounds() {
+    let build = |bounds: &'static [&'static str]| {
+        move |b: &mut Builder| {
+            let f = function(type_param("T", bounds), decl(vec![Type::Generic("T".into())], None));
+            b.public("f", ItemKind::Function, f);
+        }
+    };
+    let (old, new) = (crate_with(build(&[])), crate_with(build(&["Clone"])));
+    assert_eq!(messages(&diff(&old, &new)), ["major: foo::f: added bound `T: Clone`"]);
+    assert_eq!(messages(&diff(&new, &old)), ["minor: foo::f: removed bound `T: Clone`"]);
+}
+
+#[test]
+fn non_exhaustive() {
+    let build = |non_exhaustive: bool| {
+        move |b: &mut Builder| {
+            let s = b.public("S", ItemKind::Struct, struct_(vec![], true, vec![]));
+            if non_exhaustive {
+                b.attr(&s, "#[non_exhaustive]");
+            }
+        }
+    };
+    let (old, new) = (crate_with(build(false)), crate_with(build(true)));
+    assert_eq!(messages(&diff(&old, &new)), ["major: foo::S: made `#[non_exhaustive]`"]);
+    assert_eq!(messages(&diff(&new, &old)), ["minor: foo::S: no longer `#[non_exhaustive]`"]);
+}
+
+#[test]
+fn struct_fields() {
+    let build = |fields: &'static [&'static str], fields_stripped: bool| {
+        move |b: &mut Builder| {
+            let fields = fields.iter().map(|f| b.field(f, primitive("u8"))).collect();
+            b.public("S", ItemKind::Struct, struct_(fields, fields_stripped, vec![]));
+        }
+    };
+    // Struct literals have to list all the fields.
+    let changes = diff(&crate_with(build(&["a"], false)), &crate_with(build(&["a", "b"], false)));
+    assert_eq!(messages(&changes), ["major: foo::S: added field `b`"]);
+    let changes = diff(&crate_with(build(&["a"], true)), &crate_with(build(&["a", "b"], true)));
+    assert_eq!(messages(&changes), ["minor: foo::S: added field `b`"]);
+    let changes = diff(&crate_with(build(&["a"], false)), &crate_with(build(&["a"], true)));
+    assert_eq!(messages(&changes), ["major: foo::S: added private fields"]);
+    let changes = diff(&crate_with(build(&["a", "b"], true)), &crate_with(build(&["a"], true)));
+    assert_eq!(messages(&changes), ["major: foo::S: removed field `b`"]);
+}
+
+#[test]
+fn enum_variants() {
+    let build = |variants: &'static [&'static str], non_exhaustive: bool| {
+        move |b: &mut Builder| {
+            let variants = variants
+                .iter()
+                .map(|v| b.item(v, ItemKind::Variant, ItemEnum::VariantItem(Variant::Plain)))
+                .collect();
+            let e = ItemEnum::EnumItem(Enum {
+                generics: Generics::default(),
+                variants_stripped: false,
+                variants,
+                impls: vec![],
+            });
+            let e = b.public("E", ItemKind::Enum, e);
+            if non_exhaustive {
+                b.attr(&e, "#[non_exhaustive]");
+            }
+        }
+    };
+    let changes = diff(&crate_with(build(&["A"], false)), &crate_with(build(&["A", "B"], false)));
+    assert_eq!(messages(&changes), ["major: foo::E: added variant `B`"]);
+    let changes = diff(&crate_with(build(&["A"], true)), &crate_with(build(&["A", "B"], true)));
+    assert_eq!(messages(&changes), ["minor: foo::E: added variant `B`"]);
+}
+
+#[test]
+fn auto_traits() {
+    let build = |auto_traits: &'static [&'static str]| {
+        move |b: &mut Builder| {
+            let mut impls: Vec<_> =
+                auto_traits.iter().map(|t| b.impl_(Some(t), true, vec![])).collect();
+            impls.push(b.impl_(Some("Clone"), false, vec![]));
+            b.public("S", ItemKind::Struct, struct_(vec![], true, impls));
+        }
+    };
+    let (old, new) = (crate_with(build(&["Send", "Sync"])), crate_with(build(&["Sync"])));
+    assert_eq!(
+        messages(&diff(&old, &new)),
+        ["major: foo::S: no longer implements auto trait `Send`"]
+    );
+    assert_eq!(messages(&diff(&new, &old)), ["minor: foo::S: now implements auto trait `Send`"]);
+}
+
+#[test]
+fn inherent_methods() {
+    let build = |methods: &'static [&'static str]| {
+        move |b: &mut Builder| {
+            let methods = methods
+                .iter()
+                .map(|m| b.item(m, ItemKind::Method, method(decl(vec![], None), true)))
+                .collect();
+            let impl_ = b.impl_(None, false, methods);
+            b.public("S", ItemKind::Struct, struct_(vec![], true, vec![impl_]));
+        }
+    };
+    let changes = diff(&crate_with(build(&["a", "b"])), &crate_with(build(&["a", "c"])));
+    assert_eq!(messages(&changes), ["major: foo::S::b: removed", "minor: foo::S::c: added"]);
+}
+
+#[test]
+fn trait_items() {
+    let build = |items: &'static [(&'static str, bool)]| {
+        move |b: &mut Builder| {
+            let items = items
+                .iter()
+                .map(|&(name, provided)| {
+                    b.item(name, ItemKind::Method, method(decl(vec![], None), provided))
+                })
+                .collect();
+            let t = ItemEnum::TraitItem(Trait {
+                is_auto: false,
+                is_unsafe: false,
+                items,
+                generics: Generics::default(),
+                bounds: vec![],
+                implementors: vec![],
+            });
+            b.public("T", ItemKind::Trait, t);
+        }
+    };
+    let old = crate_with(build(&[("a", false)]));
+    let changes = diff(&old, &crate_with(build(&[("a", false), ("b", true)])));
+    assert_eq!(messages(&changes), ["minor: foo::T::b: added"]);
+    let changes = diff(&old, &crate_with(build(&[("a", false), ("b", false)])));
+    assert_eq!(messages(&changes), ["major: foo::T::b: added without a default"]);
+    let changes = diff(&crate_with(build(&[("a", true)])), &old);
+    assert_eq!(messages(&changes), ["major: foo::T::a: no longer provided"]);
+}
+
+#[test]
+fn display() {
+    let old = crate_with(|b| {
+        b.public("f", ItemKind::Function, function(Generics::default(), decl(vec![], None)));
+    });
+    let changes = diff(&old, &crate_with(|_| {}));
+    assert_eq!(changes[0].to_string(), "major: `foo::f`: removed\n  --> src/lib.rs:1:5");
+}
+
+#[test]
+fn roundtrip() {
+    // The item kinds are untagged in the JSON, so make sure functions and methods can be told
+    // apart when reading it back.
+    let krate = crate_with(|b| {
+        let f = function(type_param("T", &["Clone"]), decl(vec![], None));
+        b.public("f", ItemKind::Function, f);
+        let m = b.item("m", ItemKind::Method, method(decl(vec![], None), false));
+        let impl_ = b.impl_(None, false, vec![m]);
+        b.public("S", ItemKind::Struct, struct_(vec![], true, vec![impl_]));
+    });
+    let json = serde_json::to_string(&krate).unwrap();
+    assert_eq!(serde_json::from_str::<Crate>(&json).unwrap(), krate);
+}